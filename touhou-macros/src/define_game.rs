@@ -22,12 +22,20 @@ mod kw {
 
 #[derive(Debug)]
 struct GameValueDef {
+    attrs: Vec<Attribute>,
     ident: Ident,
     display_name: LitStr,
+    short_name: Option<LitStr>,
 }
 
 impl Parse for GameValueDef {
     fn parse(input: ParseStream) -> Result<Self> {
+        let mut attrs = input.call(Attribute::parse_outer)?;
+        let short_name = util::attribute_as_lit_str("short_name", &attrs)
+            .transpose()?
+            .cloned();
+        attrs.retain(|attr| !attr.path().is_ident("short_name"));
+
         let ident: Ident = input.parse()?;
         let lookahead = input.lookahead1();
 
@@ -39,8 +47,10 @@ impl Parse for GameValueDef {
         };
 
         Ok(Self {
+            attrs,
             ident,
             display_name,
+            short_name,
         })
     }
 }
@@ -97,11 +107,77 @@ struct GameValues {
     values: Punctuated<GameValueDef, Token![,]>,
 }
 
+/// Stage variants marked with `#[final_stage]` or `#[extra]`, along with the progress order of each stage.
+///
+/// Stages marked with `#[branch]` are alternate routes through the game, and share the same progress order
+/// as the stage immediately preceding them.
+#[derive(Debug, Default)]
+struct StageMarkers {
+    final_stages: Vec<Ident>,
+    extra_stages: Vec<Ident>,
+    progress_order: Vec<(Ident, u16)>,
+}
+
 impl GameValues {
+    /// Extracts the stage marker attributes from this definition's variants.
+    ///
+    /// Markers are only accepted on stage definitions; any other attribute on a variant is an error.
+    fn take_stage_markers(&mut self) -> Result<StageMarkers> {
+        let is_stage = matches!(self.type_kw, GameValueType::Stage(_));
+        let mut markers = StageMarkers::default();
+        let mut next_order: u16 = 0;
+
+        for value in self.values.iter_mut() {
+            let mut is_branch = false;
+
+            for attr in value.attrs.drain(..) {
+                let dest = if !is_stage {
+                    None
+                } else if attr.path().is_ident("final_stage") {
+                    Some(&mut markers.final_stages)
+                } else if attr.path().is_ident("extra") {
+                    Some(&mut markers.extra_stages)
+                } else if attr.path().is_ident("branch") {
+                    if next_order == 0 {
+                        return Err(syn_error_from!(attr, "first stage cannot be a branch"));
+                    }
+
+                    attr.meta.require_path_only()?;
+                    is_branch = true;
+                    continue;
+                } else {
+                    None
+                };
+
+                if let Some(dest) = dest {
+                    attr.meta.require_path_only()?;
+                    dest.push(value.ident.clone());
+                } else {
+                    return Err(syn_error_from!(
+                        attr,
+                        "unexpected attribute on value definition"
+                    ));
+                }
+            }
+
+            if !is_branch {
+                next_order += 1;
+            }
+
+            markers
+                .progress_order
+                .push((value.ident.clone(), next_order - 1));
+        }
+
+        Ok(markers)
+    }
+
     pub fn into_numeric_enum(self, game_id: Ident, game_type: Ident) -> NumericEnum {
         NumericEnum::new(
             self.type_kw.into(),
-            self.values.into_iter().map(|v| (v.ident, v.display_name)),
+            self.values
+                .into_iter()
+                .map(|v| (v.ident, v.display_name, v.short_name)),
             self.type_kw.into_conversion_err(game_id, game_type),
             self.attrs,
         )
@@ -252,6 +328,7 @@ pub struct GameDefinition {
     shot_power: PowerDefinition,
     shot_type: NumericEnum,
     stage: NumericEnum,
+    stage_markers: StageMarkers,
     difficulty: NumericEnum,
 }
 
@@ -287,18 +364,21 @@ impl Parse for GameDefinition {
                         return Err(syn_error_from!(item_kw, "duplicate game ID definition"));
                     }
                 }
-                GameDefItem::Values(def) => match def.type_kw {
+                GameDefItem::Values(mut def) => match def.type_kw {
                     GameValueType::ShotType(item_kw) => {
+                        def.take_stage_markers()?;
                         if shot_type.replace(def).is_some() {
                             return Err(syn_error_from!(item_kw, "duplicate shot type definition"));
                         }
                     }
                     GameValueType::Stage(item_kw) => {
-                        if stage.replace(def).is_some() {
+                        let markers = def.take_stage_markers()?;
+                        if stage.replace((def, markers)).is_some() {
                             return Err(syn_error_from!(item_kw, "duplicate stage definition"));
                         }
                     }
                     GameValueType::Difficulty(item_kw) => {
+                        def.take_stage_markers()?;
                         if difficulty.replace(def).is_some() {
                             return Err(syn_error_from!(
                                 item_kw,
@@ -319,8 +399,13 @@ impl Parse for GameDefinition {
         let shot_power = shot_power
             .ok_or_else(|| syn_error_from!(struct_name, "missing shot power definition"))?;
 
-        let stage = stage
-            .map(|def| def.into_numeric_enum(game_id.clone(), struct_name.clone()))
+        let (stage, stage_markers) = stage
+            .map(|(def, markers)| {
+                (
+                    def.into_numeric_enum(game_id.clone(), struct_name.clone()),
+                    markers,
+                )
+            })
             .ok_or_else(|| syn_error_from!(struct_name, "missing stage definition"))?;
 
         let difficulty = difficulty
@@ -338,6 +423,7 @@ impl Parse for GameDefinition {
             spell_id,
             shot_type,
             stage,
+            stage_markers,
             difficulty,
             shot_power,
         })
@@ -345,7 +431,12 @@ impl Parse for GameDefinition {
 }
 
 impl GameDefinition {
-    fn impl_wrapper_integer_conversion(&self, enum_type: &NumericEnum, wrapper_type: &Path, integral_type: Ident) -> TokenStream {
+    fn impl_wrapper_integer_conversion(
+        &self,
+        enum_type: &NumericEnum,
+        wrapper_type: &Path,
+        integral_type: Ident,
+    ) -> TokenStream {
         let enum_name = enum_type.name();
         let conv_err = enum_type.err_type();
         let game_struct = &self.struct_name;
@@ -386,8 +477,6 @@ impl GameDefinition {
     }
 
     fn define_numeric_enums(&self, items: &[(&NumericEnum, &str, &str)]) -> TokenStream {
-
-
         let game_struct = &self.struct_name;
         let mut main_defs = TokenStream::new();
         let mut iter_defs = TokenStream::new();
@@ -409,11 +498,28 @@ impl GameDefinition {
                 }
             });
 
+            let name_match_arms = enum_type.variants().iter().map(|item| {
+                let variant_name = item.name();
+                let ident_str = variant_name.to_string();
+                let display_name = item.display_name();
+                quote! {
+                    if s.eq_ignore_ascii_case(#ident_str) || s.eq_ignore_ascii_case(#display_name) {
+                        return Ok(#enum_name::#variant_name);
+                    }
+                }
+            });
+
             let int_conversions = [
                 "u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64", "usize", "isize",
-            ].into_iter().map(
-                |s| self.impl_wrapper_integer_conversion(enum_type, &main_wrapper_type, Ident::new(s, Span::call_site()))
-            );
+            ]
+            .into_iter()
+            .map(|s| {
+                self.impl_wrapper_integer_conversion(
+                    enum_type,
+                    &main_wrapper_type,
+                    Ident::new(s, Span::call_site()),
+                )
+            });
 
             main_defs.extend(enum_type.define_enum(false));
             iter_defs.extend(enum_type.impl_iteration());
@@ -491,6 +597,26 @@ impl GameDefinition {
                     }
                 }
 
+                #[automatically_derived]
+                impl std::str::FromStr for #enum_name {
+                    type Err = #conv_error_type;
+
+                    /// Parses a value from either its variant name or its display name, ignoring case.
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        #(#name_match_arms)*
+                        Err(<#conv_error_type>::unknown_name())
+                    }
+                }
+
+                #[automatically_derived]
+                impl std::str::FromStr for #main_wrapper_type<#game_struct> {
+                    type Err = #conv_error_type;
+
+                    fn from_str(s: &str) -> Result<Self, Self::Err> {
+                        s.parse::<#enum_name>().map(Self::new)
+                    }
+                }
+
                 #(#int_conversions)*
             })
         }
@@ -545,7 +671,7 @@ impl GameDefinition {
                     self.unwrap().eq(other)
                 }
             }
-    
+
             #[automatically_derived]
             impl PartialOrd<crate::types::ShotPower<#game_struct>> for #shot_power_type {
                 fn partial_cmp(&self, other: &crate::types::ShotPower<#game_struct>) -> Option<std::cmp::Ordering> {
@@ -579,6 +705,7 @@ impl GameDefinition {
 
             impl crate::types::Game for #game_struct {
                 const GAME_ID: crate::types::GameId = GameId::#game_id;
+                const MAX_SPELL_ID: u32 = #spell_type::MAX_ID;
 
                 type SpellID = #spell_type;
                 type ShotTypeID = #shot_type;
@@ -589,6 +716,63 @@ impl GameDefinition {
                 fn card_info(id: SpellId) -> &'static crate::types::SpellCardInfo<Self> {
                     id.card_info()
                 }
+
+                fn is_final_stage(stage: #stage_type) -> bool {
+                    stage.is_final_stage()
+                }
+
+                fn is_extra_stage(stage: #stage_type) -> bool {
+                    stage.is_extra()
+                }
+
+                fn stage_progress_order(stage: #stage_type) -> u16 {
+                    stage.progress_order()
+                }
+            }
+        }
+    }
+
+    fn define_stage_markers(&self) -> TokenStream {
+        let stage_type = self.stage.name();
+        let match_stages = |stages: &[Ident]| {
+            if stages.is_empty() {
+                quote!(false)
+            } else {
+                quote!(matches!(self, #(Self::#stages)|*))
+            }
+        };
+
+        let is_final = match_stages(&self.stage_markers.final_stages);
+        let is_extra = match_stages(&self.stage_markers.extra_stages);
+        let progress_arms = self
+            .stage_markers
+            .progress_order
+            .iter()
+            .map(|(stage, order)| quote!(Self::#stage => #order));
+
+        quote! {
+            #[automatically_derived]
+            impl #stage_type {
+                /// Returns whether this is the final stage of the main game.
+                ///
+                /// Games with branching final stages (such as Imperishable Night) return `true` for every branch.
+                pub const fn is_final_stage(self) -> bool {
+                    #is_final
+                }
+
+                /// Returns whether this is an extra stage (such as Extra or Phantasm) rather than part of the main game.
+                pub const fn is_extra(self) -> bool {
+                    #is_extra
+                }
+
+                /// Returns how far into the game this stage is, for comparing progress between runs.
+                ///
+                /// Alternate routes (such as Imperishable Night's Stage 4 Uncanny and Powerful) share the same value.
+                pub const fn progress_order(self) -> u16 {
+                    match self {
+                        #(#progress_arms),*
+                    }
+                }
             }
         }
     }
@@ -602,6 +786,7 @@ impl GameDefinition {
         ]));
 
         ret.extend(self.define_shot_power_traits());
+        ret.extend(self.define_stage_markers());
 
         ret
     }
@@ -18,6 +18,7 @@ pub mod kw {
     syn::custom_keyword!(Nonspell);
     syn::custom_keyword!(Spells);
     syn::custom_keyword!(LastSpell);
+    syn::custom_keyword!(Survival);
 }
 
 #[derive(Debug)]
@@ -65,11 +66,22 @@ pub enum BossPhaseDef {
         _paren: token::Paren,
         ranges: Punctuated<SpellRange, Token![,]>,
     },
+    /// Survival cards, which are either regular spells (`Survival(1..=4)`) or last spells
+    /// (`Survival(LastSpell(1..=4, 5..=8))`).
+    Survival {
+        _kw: kw::Survival,
+        _paren: token::Paren,
+        last_spell: Option<(kw::LastSpell, token::Paren)>,
+        ranges: Punctuated<SpellRange, Token![,]>,
+    },
 }
 
 impl BossPhaseDef {
     fn peek(lookahead: &Lookahead1) -> bool {
-        lookahead.peek(kw::Nonspell) || lookahead.peek(kw::Spells) || lookahead.peek(kw::LastSpell)
+        lookahead.peek(kw::Nonspell)
+            || lookahead.peek(kw::Spells)
+            || lookahead.peek(kw::LastSpell)
+            || lookahead.peek(kw::Survival)
     }
 }
 
@@ -95,6 +107,29 @@ impl Parse for BossPhaseDef {
                 _paren: parenthesized!(content in input),
                 ranges: content.parse_terminated(SpellRange::parse, Token![,])?,
             })
+        } else if lookahead.peek(kw::Survival) {
+            let content;
+            let _kw = input.parse()?;
+            let _paren = parenthesized!(content in input);
+
+            if content.peek(kw::LastSpell) {
+                let inner;
+                Ok(Self::Survival {
+                    _kw,
+                    _paren,
+                    last_spell: Some((content.parse()?, parenthesized!(inner in content))),
+                    ranges: inner.parse_terminated(SpellRange::parse, Token![,])?,
+                })
+            } else {
+                let mut ranges = Punctuated::new();
+                ranges.push_value(content.parse()?);
+                Ok(Self::Survival {
+                    _kw,
+                    _paren,
+                    last_spell: None,
+                    ranges,
+                })
+            }
         } else {
             Err(lookahead.error())
         }
@@ -3,6 +3,7 @@ use std::ops::RangeInclusive;
 
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
+use syn::punctuated::Punctuated;
 use syn::{Ident, Index, Token, Visibility};
 
 use super::ast;
@@ -14,6 +15,7 @@ pub struct LocationVariant {
     variant_ident: Ident,
     display_name: String,
     spell_range: Option<RangeInclusive<u32>>,
+    survival: bool,
     full_path: TokenStream,
 }
 
@@ -30,6 +32,7 @@ impl LocationVariant {
             variant_ident,
             display_name,
             spell_range,
+            survival: false,
             full_path,
         }
     }
@@ -84,6 +87,16 @@ impl LocationVariant {
         )
     }
 
+    /// Marks this variant as a survival card, which can only be timed out rather than captured.
+    ///
+    /// Survival cards are otherwise numbered and named like regular spell cards or last spells.
+    pub fn into_survival(self) -> Self {
+        Self {
+            survival: true,
+            ..self
+        }
+    }
+
     pub fn new_boss_last_spell(
         type_ident: Ident,
         seq: Option<u32>,
@@ -133,6 +146,10 @@ impl LocationVariant {
         self.spell_range.as_ref()
     }
 
+    pub fn is_survival(&self) -> bool {
+        self.survival
+    }
+
     pub fn display_name(&self) -> &str {
         &self.display_name
     }
@@ -452,6 +469,36 @@ impl StageState {
         Ok(())
     }
 
+    fn push_last_spells(
+        type_ident: &Ident,
+        ranges: &Punctuated<ast::SpellRange, Token![,]>,
+        survival: bool,
+        phases: &mut Vec<BossPhase>,
+    ) -> Result<(), syn::Error> {
+        for (idx, range) in ranges.iter().enumerate() {
+            let spell_ids = range.parse_range()?;
+            let seq = if ranges.len() > 1 {
+                Some(idx as u32)
+            } else {
+                None
+            };
+
+            let variant =
+                LocationVariant::new_boss_last_spell(type_ident.clone(), seq, spell_ids.clone());
+
+            phases.push(BossPhase::LastSpell {
+                variant: if survival {
+                    variant.into_survival()
+                } else {
+                    variant
+                },
+                spell_ids,
+            });
+        }
+
+        Ok(())
+    }
+
     fn push_boss(
         &mut self,
         err_span: Span,
@@ -507,25 +554,38 @@ impl StageState {
                     seq_numbers.1 += 1;
                     phases.push(phase);
                 }
-                BossPhaseDef::LastSpell { ranges, .. } => {
-                    for (idx, range) in ranges.iter().enumerate() {
+                BossPhaseDef::Survival {
+                    last_spell: None,
+                    ranges,
+                    ..
+                } => {
+                    /* survival cards are handled exactly like regular spells, aside from the flag on the variant */
+                    for range in ranges {
                         let spell_ids = range.parse_range()?;
-                        let seq = if ranges.len() > 1 {
-                            Some(idx as u32)
-                        } else {
-                            None
-                        };
-
-                        phases.push(BossPhase::LastSpell {
-                            variant: LocationVariant::new_boss_last_spell(
+                        let phase = BossPhase::Spells {
+                            variant: LocationVariant::new_boss_spells(
                                 self.type_ident.clone(),
-                                seq,
+                                midboss,
+                                seq_numbers.1,
                                 spell_ids.clone(),
-                            ),
+                            )
+                            .into_survival(),
                             spell_ids,
-                        })
+                        };
+                        seq_numbers.1 += 1;
+                        phases.push(phase);
                     }
                 }
+                BossPhaseDef::LastSpell { ranges, .. } => {
+                    Self::push_last_spells(&self.type_ident, ranges, false, &mut phases)?;
+                }
+                BossPhaseDef::Survival {
+                    last_spell: Some(_),
+                    ranges,
+                    ..
+                } => {
+                    Self::push_last_spells(&self.type_ident, ranges, true, &mut phases)?;
+                }
             };
         }
 
@@ -837,6 +897,14 @@ impl StageLocations {
                 idx as u64
             });
 
+        let is_survival_method = self.define_mapping_method(
+            "is_survival",
+            false,
+            true,
+            quote! { bool },
+            |_, variant, _| variant.is_survival(),
+        );
+
         let spell_method = self.define_mapping_method(
             "spell",
             true,
@@ -871,13 +939,19 @@ impl StageLocations {
                         valid: #range
                     })
                 });
-                rev_index_arms.push(quote! {
-                    (#idx, None) => Err(crate::memory::InvalidLocationData::MissingSpell {
-                        stage: #stage_name,
-                        loc_name: #name,
-                        valid: #range
-                    })
-                })
+                if variant.spell_range().is_some_and(|r| r.start() == r.end()) {
+                    // only one spell can appear here, so it doesn't need to be specified
+                    let spell_id = *variant.spell_range().unwrap().start();
+                    rev_index_arms.push(quote! { (#idx, None) => Ok(#path(crate::types::SpellCard::new(#spell_id.try_into().unwrap()))) });
+                } else {
+                    rev_index_arms.push(quote! {
+                        (#idx, None) => Err(crate::memory::InvalidLocationData::MissingSpell {
+                            stage: #stage_name,
+                            loc_name: #name,
+                            valid: #range
+                        })
+                    });
+                }
             } else {
                 rev_index_arms.push(quote! {
                     (#idx, _) => Ok(#path)
@@ -968,6 +1042,7 @@ impl StageLocations {
                 #name_method
                 #index_method
                 #spell_method
+                #is_survival_method
 
                 pub(crate) fn from_index(index: u64, spell_id: Option<u32>) -> Result<Self, crate::memory::InvalidLocationData<#game>> {
                     match (index, spell_id) {
@@ -1312,6 +1387,18 @@ impl GameLocations {
             })
             .collect::<Vec<_>>();
 
+        let is_survival_match_arms = self
+            .stages
+            .iter()
+            .map(|stage| {
+                let stage_id = &stage.stage_ident;
+
+                quote! {
+                    Self::#stage_id(section) => section.is_survival()
+                }
+            })
+            .collect::<Vec<_>>();
+
         let from_spell_match_arms = self
             .stages
             .iter()
@@ -1352,6 +1439,23 @@ impl GameLocations {
             quote! { #stage_type::#stage_id => Err(crate::memory::InvalidLocationData::NoStageData { stage: #stage_type::#stage_id.name() }) }
         })).collect::<Vec<_>>();
 
+        let mut cur_idx = 0;
+        let global_index_match_arms = self
+            .stages
+            .iter()
+            .filter_map(move |stage| {
+                let stage_id = &stage.stage_ident;
+                let stage_type_ident = &stage.type_ident;
+                let start = cur_idx as u64;
+                cur_idx += stage.iter_variants().count();
+                let end = (cur_idx as u64).checked_sub(1)?;
+
+                (end >= start).then(|| quote! {
+                    #start..=#end => #stage_type_ident::from_index(index - #start, None).map(Self::#stage_id)
+                })
+            })
+            .collect::<Vec<_>>();
+
         let to_index_match_arms = self.stages.iter().map(move |stage| {
             let stage_id = &stage.stage_ident;
 
@@ -1377,6 +1481,12 @@ impl GameLocations {
             }
         });
 
+        let total_count = self
+            .stages
+            .iter()
+            .map(|stage| stage.iter_variants().count() as u64)
+            .sum::<u64>();
+
         let resolve_impl = self.impl_resolve();
 
         quote! {
@@ -1390,6 +1500,20 @@ impl GameLocations {
 
             #[automatically_derived]
             impl #type_name {
+                /// The total number of distinct locations defined for this game, across all stages.
+                pub const COUNT: u64 = #total_count;
+
+                /// Returns the range of values that can be returned by [`index`](Self::index).
+                ///
+                /// This range is empty if no locations are defined.
+                pub const fn index_range() -> std::ops::RangeInclusive<u64> {
+                    if Self::COUNT == 0 {
+                        std::ops::RangeInclusive::new(1, 0)
+                    } else {
+                        0..=(Self::COUNT - 1)
+                    }
+                }
+
                 pub const fn name(self) -> &'static str {
                     match self {
                         #(#name_match_arms),*
@@ -1426,12 +1550,34 @@ impl GameLocations {
                     }
                 }
 
+                /// Returns whether this location is a survival card that can only be timed out, not captured.
+                pub const fn is_survival(self) -> bool {
+                    match self {
+                        #(#is_survival_match_arms),*
+                    }
+                }
+
                 pub const fn from_spell(spell: crate::types::SpellCard<#game>) -> Option<Self> {
                     match spell.unwrap().unwrap() {
                         #(#from_spell_match_arms)*
                         _ => None
                     }
                 }
+
+                /// Gets the location corresponding to a value returned by [`index`](Self::index).
+                ///
+                /// For spell card locations, the spell is inferred from the index if only one card can appear there;
+                /// otherwise, this returns an error.
+                pub fn from_index(index: u64) -> Result<Self, crate::memory::InvalidLocationData<#game>> {
+                    match index {
+                        #(#global_index_match_arms,)*
+                        index => Err(crate::memory::InvalidLocationData::InvalidIndex {
+                            stage: "(any)",
+                            index,
+                            valid: Self::index_range()
+                        })
+                    }
+                }
             }
 
             #[automatically_derived]
@@ -1496,12 +1642,22 @@ impl GameLocations {
                     }
                 }
 
+                fn is_survival(&self) -> bool {
+                    match self {
+                        #(#is_survival_match_arms),*
+                    }
+                }
+
                 fn from_spell(spell: crate::types::SpellCard<#game>) -> Option<Self> {
                     match spell.unwrap().unwrap() {
                         #(#from_spell_match_arms)*
                         _ => None
                     }
                 }
+
+                fn from_index(index: u64) -> Result<Self, crate::memory::InvalidLocationData<#game>> {
+                    #type_name::from_index(index)
+                }
             }
 
             #[automatically_derived]
@@ -1576,10 +1732,41 @@ impl GameLocations {
         }
     }
 
+    /// Generates a test checking that `AllIterable::iter_all` yields locations in `index` order.
+    ///
+    /// Location indexes are stored in tracking databases, so this catches any change that would reorder them.
+    /// Spell card locations with more than one possible card share an index, so consecutive locations
+    /// can have equal indexes, but the indexes must otherwise count up from 0 without gaps.
+    pub fn define_index_order_test(&self) -> TokenStream {
+        let type_name = &self.type_ident;
+
+        quote! {
+            #[cfg(test)]
+            mod index_order_tests {
+                use super::*;
+
+                #[test]
+                fn iter_all_yields_locations_in_index_order() {
+                    let mut prev: Option<u64> = None;
+
+                    for location in <#type_name as crate::types::AllIterable>::iter_all() {
+                        let index = location.index();
+                        let expected = prev.map_or(0..=0, |prev| prev..=(prev + 1));
+                        assert!(expected.contains(&index), "{} has index {} after index {:?}", location, index, prev);
+                        prev = Some(index);
+                    }
+
+                    assert_eq!(prev, #type_name::COUNT.checked_sub(1));
+                }
+            }
+        }
+    }
+
     pub fn to_definitions(&self) -> TokenStream {
         let mut ret = self.define_sub_enums();
         ret.extend(self.define_main_enum());
         ret.extend(self.define_iter_module());
+        ret.extend(self.define_index_order_test());
         ret
     }
 }
@@ -1,5 +1,5 @@
 use proc_macro2::TokenStream;
-use quote::{quote, quote_spanned};
+use quote::{format_ident, quote, quote_spanned};
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
@@ -10,6 +10,8 @@ mod kw {
     syn::custom_keyword!(snapshot);
     syn::custom_keyword!(access);
     syn::custom_keyword!(game);
+    syn::custom_keyword!(arch);
+    syn::custom_keyword!(stride);
 }
 
 #[derive(Debug)]
@@ -20,19 +22,99 @@ struct MemoryField {
     _at: Token![@],
     _bracket: token::Bracket,
     offsets: Punctuated<LitInt, Token![,]>,
+    stride: Option<FieldStride>,
+    arch: Option<FieldArch>,
+    optional: bool,
 }
 
-impl Parse for MemoryField {
+/// The spacing between elements of an array field, given as `; stride = N` after its offsets.
+#[derive(Debug)]
+struct FieldStride {
+    _semi: Token![;],
+    _kw: kw::stride,
+    _eq: Token![=],
+    stride: LitInt,
+}
+
+impl Parse for FieldStride {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            _semi: input.parse()?,
+            _kw: input.parse()?,
+            _eq: input.parse()?,
+            stride: input.parse()?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct FieldArch {
+    _at: Token![@],
+    _kw: kw::arch,
+    _bracket: token::Bracket,
+    arch: Box<Type>,
+}
+
+impl Parse for FieldArch {
     fn parse(input: ParseStream) -> Result<Self> {
         let content;
 
         Ok(Self {
-            name: input.parse()?,
-            _colon: input.parse()?,
-            elem_type: input.parse()?,
             _at: input.parse()?,
+            _kw: input.parse()?,
             _bracket: bracketed!(content in input),
-            offsets: content.parse_terminated(LitInt::parse, Token![,])?,
+            arch: content.parse()?,
+        })
+    }
+}
+
+impl Parse for MemoryField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let content;
+        let name = input.parse()?;
+        let _colon = input.parse()?;
+        let elem_type: Type = input.parse()?;
+        let _at = input.parse()?;
+        let _bracket = bracketed!(content in input);
+
+        let mut offsets = Punctuated::new();
+        while !content.is_empty() && !content.peek(Token![;]) {
+            offsets.push_value(content.parse()?);
+            if content.is_empty() || content.peek(Token![;]) {
+                break;
+            }
+            offsets.push_punct(content.parse()?);
+        }
+
+        let stride: Option<FieldStride> = if content.is_empty() {
+            None
+        } else {
+            Some(content.parse()?)
+        };
+
+        if let Some(stride) = &stride {
+            if !matches!(elem_type, Type::Array(_)) {
+                return Err(syn::Error::new(
+                    stride.stride.span(),
+                    "fields with a stride must have an array type",
+                ));
+            }
+        }
+
+        Ok(Self {
+            name,
+            _colon,
+            elem_type,
+            _at,
+            _bracket,
+            offsets,
+            stride,
+            arch: if input.peek(Token![@]) && input.peek2(kw::arch) {
+                Some(input.parse()?)
+            } else {
+                None
+            },
+            optional: false,
         })
     }
 }
@@ -67,9 +149,64 @@ impl MemoryField {
         }
     }
 
+    fn format_stride_docs(&self) -> Option<String> {
+        let stride = self.stride.as_ref()?.stride.base10_parse::<u32>().unwrap();
+        Some(format!(
+            "Elements of this array are spaced `{:#x}` bytes apart.",
+            stride
+        ))
+    }
+
+    /// Gets the element type and length of this field, if it is a strided array.
+    fn array_parts(&self) -> Option<(&Type, &syn::Expr, &LitInt)> {
+        match (&self.elem_type, &self.stride) {
+            (Type::Array(array), Some(stride)) => Some((&array.elem, &array.len, &stride.stride)),
+            _ => None,
+        }
+    }
+
+    /// Gets the type of the value stored at each of this field's locations.
+    fn item_type(&self) -> &Type {
+        self.array_parts()
+            .map(|(elem, _, _)| elem)
+            .unwrap_or(&self.elem_type)
+    }
+
+    /// Gets the type of value read from this field, not accounting for optional fields.
+    ///
+    /// Strided arrays are read as a `Vec`.
+    fn read_type(&self) -> TokenStream {
+        if let Some((elem, _, _)) = self.array_parts() {
+            quote! { Vec<#elem> }
+        } else {
+            let elem_type = &self.elem_type;
+            quote! { #elem_type }
+        }
+    }
+
+    /// Gets the type returned by this field's accessors, which is wrapped in an `Option` for optional fields.
+    fn value_type(&self) -> TokenStream {
+        let elem_type = self.read_type();
+        if self.optional {
+            quote! { Option<#elem_type> }
+        } else {
+            elem_type
+        }
+    }
+
+    /// Gets an expression that reads this field from an access struct, producing an `io::Result`.
+    fn read_expr(&self) -> TokenStream {
+        let name = &self.name;
+        if let Some((_, len, stride)) = self.array_parts() {
+            quote! { self.#name.read_strided(#stride, #len) }
+        } else {
+            quote! { self.#name.read() }
+        }
+    }
+
     fn snapshot_field_def(&self, attrs: &[Attribute]) -> TokenStream {
         let name = &self.name;
-        let elem_type = &self.elem_type;
+        let elem_type = self.value_type();
         quote! {
             #(#attrs)*
             #name: #elem_type
@@ -78,10 +215,25 @@ impl MemoryField {
 
     fn snapshot_access_fn(&self) -> TokenStream {
         let name = &self.name;
-        let elem_type = &self.elem_type;
-        quote! {
-            pub fn #name(&self) -> #elem_type {
-                self.#name
+
+        match (self.array_parts(), self.optional) {
+            (Some((elem, _, _)), false) => quote! {
+                pub fn #name(&self) -> &[#elem] {
+                    &self.#name
+                }
+            },
+            (Some((elem, _, _)), true) => quote! {
+                pub fn #name(&self) -> Option<&[#elem]> {
+                    self.#name.as_deref()
+                }
+            },
+            (None, _) => {
+                let elem_type = self.value_type();
+                quote! {
+                    pub fn #name(&self) -> #elem_type {
+                        self.#name
+                    }
+                }
             }
         }
     }
@@ -91,9 +243,17 @@ impl MemoryField {
         quote! { #name: self.#name()? }
     }
 
-    fn access_field_def(&self, attrs: &[Attribute]) -> TokenStream {
+    fn arch<'a>(&'a self, default_arch: &'a Type) -> &'a Type {
+        self.arch
+            .as_ref()
+            .map(|field_arch| &*field_arch.arch)
+            .unwrap_or(default_arch)
+    }
+
+    fn access_field_def(&self, attrs: &[Attribute], default_arch: &Type) -> TokenStream {
         let name = &self.name;
-        let elem_type = &self.elem_type;
+        let elem_type = self.item_type();
+        let arch = self.arch(default_arch);
         let offset_docs = self.format_offset_docs();
         let span = name.span();
 
@@ -101,44 +261,101 @@ impl MemoryField {
             #(#attrs)*
             ///
             #[doc = #offset_docs]
-            #name: touhou_process::FixedData<#elem_type, touhou_process::LittleEndian<4>>
+            #name: touhou_process::FixedData<#elem_type, #arch>
         }
     }
 
-    fn access_create_expr(&self) -> TokenStream {
+    fn access_create_expr(&self, default_arch: &Type) -> TokenStream {
         let name = &self.name;
         let offsets = self.offsets.iter();
+        let arch = self.arch(default_arch);
         let span = self.elem_type.span();
 
-        quote_spanned!(span=> #name: handle.new_fixed_item(&[#(#offsets),*]))
+        quote_spanned!(span=> #name: handle.try_new_fixed_item_arch(<#arch as Default>::default(), &[#(#offsets),*])?)
     }
 
     fn access_fn(&self, attrs: &[Attribute], game: &Ident) -> TokenStream {
         let name = &self.name;
-        let elem_type = &self.elem_type;
+        let elem_type = self.value_type();
         let offset_docs = self.format_offset_docs();
-        let span = elem_type.span();
+        let stride_docs = self.format_stride_docs().map(|docs| {
+            quote! {
+                ///
+                #[doc = #docs]
+            }
+        });
+        let read_expr = self.read_expr();
+        let span = self.elem_type.span();
 
-        quote_spanned! {span=>
-            #(#attrs)*
-            ///
-            #[doc = #offset_docs]
-            pub fn #name(&self) -> Result<#elem_type, crate::memory::MemoryReadError<#game>> {
-                use crate::memory::MemoryReadError;
-                self.#name.read().map_err(MemoryReadError::from)
+        let fill_fn = self.array_parts().map(|(elem, len, stride)| {
+            let fill_name = format_ident!("{}_into", name);
+            let fill_docs = format!(
+                "Reads the elements of [`{}`](Self::{}) into an existing slice, up to the length of either.",
+                name, name
+            );
+
+            quote_spanned! {span=>
+                #[doc = #fill_docs]
+                pub fn #fill_name(&self, dest: &mut [#elem]) -> Result<(), crate::memory::MemoryReadError<#game>> {
+                    use crate::memory::MemoryReadError;
+                    let n = dest.len().min(#len);
+                    self.#name.read_strided_into(#stride, &mut dest[..n]).map_err(MemoryReadError::from)
+                }
             }
+        });
+
+        let access_fn = if self.optional {
+            quote_spanned! {span=>
+                #(#attrs)*
+                ///
+                #[doc = #offset_docs]
+                #stride_docs
+                ///
+                /// Returns `None` if this value could not be read due to a null or otherwise invalid pointer.
+                pub fn #name(&self) -> Result<#elem_type, crate::memory::MemoryReadError<#game>> {
+                    use crate::memory::MemoryReadError;
+                    match #read_expr {
+                        Ok(value) => Ok(Some(value)),
+                        Err(err) if err.kind() == std::io::ErrorKind::InvalidData => Ok(None),
+                        Err(err) => Err(MemoryReadError::from(err)),
+                    }
+                }
+            }
+        } else {
+            quote_spanned! {span=>
+                #(#attrs)*
+                ///
+                #[doc = #offset_docs]
+                #stride_docs
+                pub fn #name(&self) -> Result<#elem_type, crate::memory::MemoryReadError<#game>> {
+                    use crate::memory::MemoryReadError;
+                    #read_expr.map_err(MemoryReadError::from)
+                }
+            }
+        };
+
+        quote! {
+            #access_fn
+            #fill_fn
         }
     }
 
     fn wrapper_access_fn(&self, attrs: &[Attribute], game: &Ident) -> TokenStream {
         let name = &self.name;
-        let elem_type = &self.elem_type;
+        let elem_type = self.value_type();
         let offset_docs = self.format_offset_docs();
+        let optional_docs = self.optional.then(|| {
+            quote! {
+                ///
+                /// Returns `None` if the game process is no longer attached, and `Some(None)` if the value could not be read.
+            }
+        });
 
         quote! {
             #(#attrs)*
             ///
             #[doc = #offset_docs]
+            #optional_docs
             pub fn #name(&mut self) -> Result<Option<#elem_type>, crate::memory::MemoryReadError<#game>> {
                 self.0.access().map(|inner| inner.#name()).transpose()
             }
@@ -171,6 +388,12 @@ enum MemoryDefElement {
         _eq: Token![=],
         name: Ident,
     },
+    ArchType {
+        _attrs: Vec<Attribute>,
+        _kw: kw::arch,
+        _eq: Token![=],
+        arch: Type,
+    },
     Field {
         attrs: Vec<Attribute>,
         field: MemoryField,
@@ -209,6 +432,13 @@ impl Parse for MemoryDefElement {
                 _eq: input.parse()?,
                 name: input.parse()?,
             })
+        } else if lookahead.peek(kw::arch) {
+            Ok(Self::ArchType {
+                _attrs: attrs,
+                _kw: input.parse()?,
+                _eq: input.parse()?,
+                arch: input.parse()?,
+            })
         } else {
             Ok(Self::Field {
                 attrs,
@@ -247,6 +477,7 @@ pub struct MemoryDef {
     access_name: (Vec<Attribute>, Ident),
     process_names: Vec<LitStr>,
     game_type: Ident,
+    arch: Type,
     fields: Vec<(Vec<Attribute>, MemoryField)>,
 }
 
@@ -261,12 +492,21 @@ impl Parse for MemoryDef {
         let mut snapshot_name = None;
         let mut access_name = None;
         let mut game_type = None;
+        let mut arch = None;
         let mut process_names = Vec::new();
         let mut fields = Vec::new();
 
         for elem in elems {
             match elem {
-                MemoryDefElement::Field { attrs, field } => fields.push((attrs, field)),
+                MemoryDefElement::Field {
+                    mut attrs,
+                    mut field,
+                } => {
+                    let n_attrs = attrs.len();
+                    attrs.retain(|attr| !attr.path().is_ident("optional"));
+                    field.optional = attrs.len() != n_attrs;
+                    fields.push((attrs, field));
+                }
                 MemoryDefElement::ProcessName { name, .. } => process_names.push(name),
                 MemoryDefElement::SnapshotType { attrs, name, .. } => {
                     if snapshot_name.is_none() {
@@ -295,6 +535,18 @@ impl Parse for MemoryDef {
                         return Err(syn::Error::new(name.span(), "multiple game types given"));
                     }
                 }
+                MemoryDefElement::ArchType {
+                    arch: arch_type, ..
+                } => {
+                    if arch.is_none() {
+                        arch = Some(arch_type);
+                    } else {
+                        return Err(syn::Error::new(
+                            arch_type.span(),
+                            "multiple architectures given",
+                        ));
+                    }
+                }
             }
         }
 
@@ -308,6 +560,7 @@ impl Parse for MemoryDef {
             snapshot_name,
             access_name: access_name.ok_or_else(|| input.error("no access type name given"))?,
             game_type: game_type.ok_or_else(|| input.error("no game type given"))?,
+            arch: arch.unwrap_or_else(|| syn::parse_quote!(touhou_process::LittleEndian<4>)),
             process_names,
             fields,
         })
@@ -343,11 +596,11 @@ impl MemoryDef {
         let field_defs = self
             .fields
             .iter()
-            .map(|(attrs, field)| field.access_field_def(attrs));
+            .map(|(attrs, field)| field.access_field_def(attrs, &self.arch));
         let field_create = self
             .fields
             .iter()
-            .map(|(_, field)| field.access_create_expr());
+            .map(|(_, field)| field.access_create_expr(&self.arch));
         let field_access = self
             .fields
             .iter()
@@ -378,9 +631,9 @@ impl MemoryDef {
             #[automatically_derived]
             impl ProcessAttached for #access_name {
                 fn from_pid(pid: u32) -> std::io::Result<Self> {
-                    touhou_process::Pid::from(pid).try_into_process_handle().map(|handle| Self {
+                    touhou_process::Pid::from(pid).try_into_process_handle().and_then(|handle| Ok(Self {
                         #(#field_create),*
-                    })
+                    }))
                 }
 
                 fn is_attachable_process(proc: &sysinfo::Process) -> bool {
@@ -394,6 +647,17 @@ impl MemoryDef {
                 #(#field_access)*
 
                 #snapshot_create
+
+                /// Runs a read using this access struct, retrying it up to `max_attempts` times in total
+                /// if it fails with a transient error.
+                ///
+                /// See [`retry_read`](crate::memory::retry_read) for more details.
+                pub fn retry_read<T, F>(&self, max_attempts: u32, mut read: F) -> Result<T, crate::memory::MemoryReadError<#game>>
+                where
+                    F: FnMut(&Self) -> Result<T, crate::memory::MemoryReadError<#game>>,
+                {
+                    crate::memory::retry_read(max_attempts, || read(self))
+                }
             }
         }
     }
@@ -411,8 +675,11 @@ impl MemoryDef {
 
         let snapshot_access = self.snapshot_name.as_ref().map(|(_, snapshot_name)| {
             quote! {
-                pub fn read_snapshot(&mut self) -> Result<#snapshot_name, crate::memory::MemoryReadError<#game>> {
-                    self.0.access().and_then(|inner| inner.read_snapshot())
+                /// Reads a snapshot of every field at once.
+                ///
+                /// Returns `Ok(None)` instead of an error if the game process is no longer attached.
+                pub fn read_snapshot(&mut self) -> Result<Option<#snapshot_name>, crate::memory::MemoryReadError<#game>> {
+                    self.0.access().map(|inner| inner.read_snapshot()).transpose()
                 }
             }
         });
@@ -441,10 +708,29 @@ impl MemoryDef {
                     Attached::from_pid(pid).map(Self).map_err(MemoryReadError::from)
                 }
 
+                /// Attaches to the process with the given PID, after checking that it is actually running this game.
+                pub fn attach_to_pid(pid: u32) -> Result<Self, crate::memory::MemoryReadError<#game>> {
+                    use crate::memory::MemoryReadError;
+                    Attached::attach_to_pid(pid).map(Self).map_err(MemoryReadError::from)
+                }
+
+                /// Attaches a new handle to the same game process.
+                ///
+                /// This type does not implement [`Clone`], since this requires reopening the process, which fails if it has since exited.
+                pub fn try_clone(&self) -> Result<Self, crate::memory::MemoryReadError<#game>> {
+                    use crate::memory::MemoryReadError;
+                    self.0.try_clone().map(Self).map_err(MemoryReadError::from)
+                }
+
                 pub fn is_running(&mut self) -> bool {
                     self.0.is_running()
                 }
 
+                /// Checks whether the attached game process is still running, or whether it has been replaced by a new process.
+                pub fn status(&mut self) -> crate::memory::ProcessStatus {
+                    self.0.status()
+                }
+
                 pub fn pid(&self) -> u32 {
                     self.0.pid()
                 }
@@ -458,12 +744,6 @@ impl MemoryDef {
                 #snapshot_access
             }
 
-            impl Clone for #name {
-                fn clone(&self) -> Self {
-                    Self::from_pid(self.pid()).unwrap()
-                }
-            }
-
             impl crate::memory::GameMemory<#game> for #name {
                 type MemoryAccess = #access_name;
 
@@ -471,6 +751,10 @@ impl MemoryDef {
                     self.0.is_running()
                 }
 
+                fn status(&mut self) -> crate::memory::ProcessStatus {
+                    self.0.status()
+                }
+
                 fn pid(&self) -> u32 {
                     self.0.pid()
                 }
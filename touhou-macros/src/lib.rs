@@ -23,7 +23,10 @@ pub fn spellcards(input: TokenStream) -> TokenStream {
         .into()
 }
 
-#[proc_macro_derive(NumericEnum, attributes(name, error_type, convert_error))]
+#[proc_macro_derive(
+    NumericEnum,
+    attributes(name, short_name, alias, error_type, convert_error)
+)]
 pub fn numeric_enum(input: TokenStream) -> TokenStream {
     match NumericEnum::from_derive(parse_macro_input!(input as DeriveInput)) {
         Ok(input) => input.impl_traits(true),
@@ -32,6 +35,24 @@ pub fn numeric_enum(input: TokenStream) -> TokenStream {
     .into()
 }
 
+/// Derives [`NumericEnum`] along with `crate::types::GameValue` for a hand-written game value enum.
+///
+/// The enum must be annotated with `#[game_id(...)]` giving the game's `GameId` variant,
+/// `#[game_type(...)]` giving the game's type, and `#[value_type(...)]` giving the kind of value
+/// (one of `ShotType`, `Stage`, or `Difficulty`). This generates the same trait implementations
+/// as `define_game!` does for its enums, so it should not be combined with `#[derive(NumericEnum)]`.
+#[proc_macro_derive(
+    GameValue,
+    attributes(name, short_name, alias, game_id, game_type, value_type)
+)]
+pub fn game_value(input: TokenStream) -> TokenStream {
+    match NumericEnum::from_game_value_derive(parse_macro_input!(input as DeriveInput)) {
+        Ok(input) => input.impl_traits(true),
+        Err(err) => err.into_compile_error(),
+    }
+    .into()
+}
+
 #[proc_macro]
 pub fn define_memory(input: TokenStream) -> TokenStream {
     parse_macro_input!(input as MemoryDef).into_defines().into()
@@ -9,7 +9,7 @@ use crate::util;
 use crate::util::syn_error_from;
 
 #[derive(Debug, Clone)]
-pub struct VariantDef(Ident, LitInt, LitStr, isize);
+pub struct VariantDef(Ident, LitInt, LitStr, isize, Option<LitStr>);
 
 impl VariantDef {
     pub fn name(&self) -> &Ident {
@@ -27,6 +27,11 @@ impl VariantDef {
     pub fn discriminant_val(&self) -> isize {
         self.3
     }
+
+    /// Gets the short name for this variant, falling back to the display name if none was given.
+    pub fn short_name(&self) -> &LitStr {
+        self.4.as_ref().unwrap_or(&self.2)
+    }
 }
 
 impl From<VariantDef> for Variant {
@@ -120,9 +125,10 @@ impl ConversionError {
         }
     }
 
-    fn error_arm(&self, n_variants: usize) -> TokenStream {
-        let max_range = (n_variants - 1) as u16;
-
+    /// Generates the fallback match arm for converting unmapped integer values.
+    ///
+    /// `valid` must contain every accepted raw value (including aliases), sorted in ascending order.
+    fn error_arm(&self, valid: &[isize]) -> TokenStream {
         match self {
             Self::Default { ident } => quote! {
                 other => Err(#ident(other as u64))
@@ -130,9 +136,30 @@ impl ConversionError {
             Self::Custom { map_func, .. } => quote! {
                 other => Err(#map_func(other as u64))
             },
-            Self::GameValue { base_type, .. } => quote! {
-                other => Err(#base_type::out_of_range(other as u16, 0..=#max_range))
-            },
+            Self::GameValue { base_type, .. } => {
+                let min = valid[0] as u16;
+                let max = valid[valid.len() - 1] as u16;
+                let contiguous = valid
+                    .iter()
+                    .enumerate()
+                    .all(|(idx, val)| *val == valid[0] + idx as isize);
+
+                if contiguous {
+                    quote! {
+                        other => Err(#base_type::out_of_range(other as u16, #min..=#max))
+                    }
+                } else {
+                    let valid_str = valid
+                        .iter()
+                        .map(isize::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    quote! {
+                        other => Err(#base_type::not_in_set(other as u16, #valid_str))
+                    }
+                }
+            }
         }
     }
 }
@@ -141,12 +168,13 @@ impl ConversionError {
 pub struct NumericEnum {
     name: Ident,
     variants: Vec<VariantDef>,
+    aliases: Vec<(LitInt, Ident)>,
     conv_err: ConversionError,
     attrs: Vec<Attribute>,
 }
 
 impl NumericEnum {
-    pub fn new<I: IntoIterator<Item = (Ident, LitStr)>>(
+    pub fn new<I: IntoIterator<Item = (Ident, LitStr, Option<LitStr>)>>(
         name: Ident,
         variants: I,
         conv_err: ConversionError,
@@ -155,9 +183,15 @@ impl NumericEnum {
         let mut variants = variants
             .into_iter()
             .enumerate()
-            .map(|(idx, (var_ident, var_name))| {
+            .map(|(idx, (var_ident, var_name, short_name))| {
                 let var_discriminant = LitInt::new(&idx.to_string(), name.span());
-                VariantDef(var_ident, var_discriminant, var_name, idx as isize)
+                VariantDef(
+                    var_ident,
+                    var_discriminant,
+                    var_name,
+                    idx as isize,
+                    short_name,
+                )
             })
             .collect::<Vec<_>>();
 
@@ -166,6 +200,7 @@ impl NumericEnum {
         Self {
             name,
             variants,
+            aliases: Vec::new(),
             conv_err,
             attrs,
         }
@@ -174,6 +209,7 @@ impl NumericEnum {
     pub fn from_derive(input: DeriveInput) -> Result<Self, syn::Error> {
         if let Data::Enum(enum_data) = input.data {
             let mut variants = Vec::new();
+            let mut aliases = Vec::new();
 
             let conv_err = match util::parse_attribute_str("error_type", &input.attrs)? {
                 Some(err_type) => ConversionError::Custom {
@@ -187,6 +223,13 @@ impl NumericEnum {
 
             for variant in enum_data.variants {
                 let variant_name = variant.ident;
+
+                for attr in &variant.attrs {
+                    if attr.path().is_ident("alias") {
+                        aliases.push((attr.parse_args::<LitInt>()?, variant_name.clone()));
+                    }
+                }
+
                 let display_name = util::attribute_as_lit_str("name", &variant.attrs)
                     .transpose()?
                     .cloned()
@@ -197,10 +240,20 @@ impl NumericEnum {
                         )
                     });
 
+                let short_name = util::attribute_as_lit_str("short_name", &variant.attrs)
+                    .transpose()?
+                    .cloned();
+
                 if let Some((_, Expr::Lit(lit))) = variant.discriminant {
                     if let Lit::Int(value) = lit.lit {
                         let parsed_val = value.base10_parse()?;
-                        variants.push(VariantDef(variant_name, value, display_name, parsed_val));
+                        variants.push(VariantDef(
+                            variant_name,
+                            value,
+                            display_name,
+                            parsed_val,
+                            short_name,
+                        ));
                     } else {
                         unreachable!("variant {} discriminant is not an integer", variant_name)
                     }
@@ -217,6 +270,7 @@ impl NumericEnum {
             Ok(Self {
                 name: input.ident,
                 variants,
+                aliases,
                 conv_err,
                 attrs: Vec::new(),
             })
@@ -225,6 +279,36 @@ impl NumericEnum {
         }
     }
 
+    /// Parses an enum for `#[derive(GameValue)]`.
+    ///
+    /// This works like [`from_derive`](Self::from_derive), except that the conversion error type is
+    /// determined by the `game_id`, `game_type`, and `value_type` attributes, the same way that
+    /// `define_game!` sets up its generated enums.
+    pub fn from_game_value_derive(input: DeriveInput) -> Result<Self, syn::Error> {
+        let game_id: Ident = util::find_and_parse_attribute("game_id", &input.attrs)?
+            .ok_or_else(|| syn_error_from!(&input.ident, "missing #[game_id(...)] attribute"))?;
+        let game_type: Ident = util::find_and_parse_attribute("game_type", &input.attrs)?
+            .ok_or_else(|| syn_error_from!(&input.ident, "missing #[game_type(...)] attribute"))?;
+        let value_type: Ident = util::find_and_parse_attribute("value_type", &input.attrs)?
+            .ok_or_else(|| syn_error_from!(&input.ident, "missing #[value_type(...)] attribute"))?;
+
+        let conv_err = match value_type.to_string().as_str() {
+            "ShotType" => ConversionError::shot_type(game_id, game_type),
+            "Stage" => ConversionError::stage(game_id, game_type),
+            "Difficulty" => ConversionError::difficulty(game_id, game_type),
+            _ => {
+                return Err(syn_error_from!(
+                    value_type,
+                    "expected one of ShotType, Stage, or Difficulty"
+                ))
+            }
+        };
+
+        let mut ret = Self::from_derive(input)?;
+        ret.conv_err = conv_err;
+        Ok(ret)
+    }
+
     pub fn name(&self) -> &Ident {
         &self.name
     }
@@ -241,21 +325,53 @@ impl NumericEnum {
         let type_name = &self.name;
         self.variants
             .iter()
-            .map(move |VariantDef(name, val, _, _)| quote!(#type_name::#name => #val))
+            .map(move |VariantDef(name, val, ..)| quote!(#type_name::#name => #val))
     }
 
     fn iter_rev_match_arms(&self) -> impl Iterator<Item = TokenStream> + '_ {
         let type_name = &self.name;
         self.variants
             .iter()
-            .map(move |VariantDef(name, val, _, _)| quote!(#val => Ok(#type_name::#name)))
+            .map(move |VariantDef(name, val, ..)| quote!(#val => Ok(#type_name::#name)))
+            .chain(
+                self.aliases
+                    .iter()
+                    .map(move |(val, name)| quote!(#val => Ok(#type_name::#name))),
+            )
+    }
+
+    /// Gets all raw values that can be converted into this enum, including aliases, in ascending order.
+    fn valid_values(&self) -> Vec<isize> {
+        let mut ret = self
+            .variants
+            .iter()
+            .map(VariantDef::discriminant_val)
+            .chain(
+                self.aliases
+                    .iter()
+                    .map(|(val, _)| val.base10_parse().unwrap()),
+            )
+            .collect::<Vec<_>>();
+
+        ret.sort_unstable();
+        ret.dedup();
+        ret
     }
 
     fn iter_name_match_arms(&self) -> impl Iterator<Item = TokenStream> + '_ {
         let type_name = &self.name;
         self.variants
             .iter()
-            .map(move |VariantDef(name, _, val, _)| quote!(#type_name::#name => #val))
+            .map(move |VariantDef(name, _, val, ..)| quote!(#type_name::#name => #val))
+    }
+
+    fn iter_short_name_match_arms(&self) -> impl Iterator<Item = TokenStream> + '_ {
+        let type_name = &self.name;
+        self.variants.iter().map(move |variant| {
+            let name = variant.name();
+            let val = variant.short_name();
+            quote!(#type_name::#name => #val)
+        })
     }
 
     fn define_error_type(&self) -> TokenStream {
@@ -281,7 +397,7 @@ impl NumericEnum {
         let rev_arms = self.iter_rev_match_arms();
         let type_name = &self.name;
         let error_name = self.conv_err.error_ident();
-        let err_arm = self.conv_err.error_arm(self.variants.len());
+        let err_arm = self.conv_err.error_arm(&self.valid_values());
 
         quote! {
             #[automatically_derived]
@@ -325,6 +441,7 @@ impl NumericEnum {
 
     fn impl_display(&self) -> TokenStream {
         let arms = self.iter_name_match_arms();
+        let short_arms = self.iter_short_name_match_arms();
         let type_name = &self.name;
 
         quote! {
@@ -338,12 +455,21 @@ impl NumericEnum {
                         #(#arms),*
                     }
                 }
+
+                /// Returns a short name for this variant, for use in compact layouts.
+                ///
+                /// This is the same as [`Self::name`] unless a `#[short_name = "..."]` attribute was given.
+                pub fn short_name(&self) -> &'static str {
+                    match self {
+                        #(#short_arms),*
+                    }
+                }
             }
 
             #[automatically_derived]
             impl std::fmt::Display for #type_name {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    self.name().fmt(f)
+                    f.pad(self.name())
                 }
             }
         }
@@ -355,6 +481,10 @@ impl NumericEnum {
 
         let n_variants = self.variants.len();
         let _variants = self.variants.iter().map(VariantDef::name);
+        let order_test_fn = format_ident!(
+            "iter_all_{}_in_discriminant_order",
+            self.name.to_string().to_lowercase()
+        );
 
         let doctest_example_lines = self.variants.iter().map(VariantDef::name).map(|name| {
             quote! {
@@ -363,17 +493,13 @@ impl NumericEnum {
         }).take(3);
 
         let val_match = {
-            let mut pairs = self
+            /* variants are sorted by discriminant, so match on position to handle gaps between values */
+            let arms = self
                 .variants
                 .iter()
-                .map(|variant| (variant.discriminant_val(), variant.name()))
-                .collect::<Vec<_>>();
-            pairs.sort_unstable_by_key(|pair| pair.0);
-
-            let arms = pairs.into_iter().map(|(idx, name)| {
-                let idx = idx as usize;
-                quote! { #idx => #self_type::#name }
-            });
+                .map(VariantDef::name)
+                .enumerate()
+                .map(|(idx, name)| quote! { #idx => #self_type::#name });
 
             quote! {
                 #(#arms,)*
@@ -439,6 +565,20 @@ impl NumericEnum {
                     #iter_type(0..#n_variants)
                 }
             }
+
+            /* raw values are used as stable identifiers in serialized data, so iteration order must follow them */
+            #[cfg(test)]
+            #[test]
+            fn #order_test_fn() {
+                let values: Vec<u64> = <#self_type as crate::types::AllIterable>::iter_all()
+                    .map(u64::from)
+                    .collect();
+
+                assert_eq!(values.len(), #n_variants);
+                for pair in values.windows(2) {
+                    assert!(pair[0] < pair[1], "values yielded out of order: {} before {}", pair[0], pair[1]);
+                }
+            }
         }
     }
 
@@ -556,6 +696,10 @@ impl NumericEnum {
                     fn name(&self) -> &'static str {
                         self.name()
                     }
+
+                    fn short_name(&self) -> &'static str {
+                        self.short_name()
+                    }
                 }
             })
         } else {
@@ -159,14 +159,16 @@ impl SpellList {
 
         entries.sort_unstable_by_key(|entry| entry.id());
 
-        let first_id = entries.first().unwrap().id();
-        for (i, entry) in entries.iter().enumerate() {
-            let expected_id = (i as u32) + first_id;
-            if entry.id() != expected_id {
-                return Err(syn::Error::new(
-                    entry.id_span().span(),
-                    format!("duplicate or missing spell ID {}", expected_id),
-                ));
+        /* games without numbered spell cards (such as PoFV) have no entries to check */
+        if let Some(first_id) = entries.first().map(SpellEntry::id) {
+            for (i, entry) in entries.iter().enumerate() {
+                let expected_id = (i as u32) + first_id;
+                if entry.id() != expected_id {
+                    return Err(syn::Error::new(
+                        entry.id_span().span(),
+                        format!("duplicate or missing spell ID {}", expected_id),
+                    ));
+                }
             }
         }
 
@@ -186,6 +188,23 @@ impl SpellList {
         let n_cards = self.entries.len() as u16;
         let n_cards_u32 = self.entries.len() as u32;
         let n_cards_usize = self.entries.len();
+
+        /* written out explicitly when there are no spells, since `1..=0` is flagged as a reversed range */
+        let (valid_ids, valid_ids_u32, id_check, valid_ids_doc) = if self.entries.is_empty() {
+            (
+                quote! { std::ops::RangeInclusive::new(1, 0) },
+                quote! { std::ops::RangeInclusive::new(1, 0) },
+                quote! { false },
+                String::from("This game has no spell cards, so there are no valid spell IDs."),
+            )
+        } else {
+            (
+                quote! { 1..=#n_cards },
+                quote! { 1..=#n_cards_u32 },
+                quote! { value <= #n_cards },
+                format!("Valid spell IDs range from 1 to {}, inclusive.", n_cards),
+            )
+        };
         let spells = self
             .entries
             .iter()
@@ -224,11 +243,14 @@ impl SpellList {
 
             #[automatically_derived]
             impl SpellId {
+                /// The highest valid spell ID for this game.
+                pub const MAX_ID: u32 = #n_cards_u32;
+
                 /// Creates a new `SpellId` if the value represents a valid spell.
                 ///
-                #[doc = concat!("Valid spell IDs range from 1 to ", stringify!(#n_cards), ", inclusive.")]
+                #[doc = #valid_ids_doc]
                 pub const fn new(value: u16) -> Result<Self, crate::types::errors::InvalidCardId<#game>> {
-                    if value <= #n_cards {
+                    if #id_check {
                         if let Some(value) = std::num::NonZeroU16::new(value) {
                             return Ok(Self(value));
                         }
@@ -236,10 +258,18 @@ impl SpellList {
 
                     Err(crate::types::errors::InvalidCardId::out_of_range(
                         value as u32,
-                        1..=#n_cards_u32,
+                        #valid_ids_u32,
                     ))
                 }
 
+                /// Gets static information for the spell with the given raw ID, if it is valid.
+                ///
+                /// Unlike going through [`Self::new`] and [`Self::card_info`], this never panics or
+                /// requires unwrapping, which makes it convenient for skipping over invalid IDs.
+                pub fn try_card_info(raw: u32) -> Option<&'static crate::types::SpellCardInfo<#game>> {
+                    u16::try_from(raw).ok().and_then(|id| Self::new(id).ok()).map(|id| id.card_info())
+                }
+
                 /// Gets a reference to static information for this spell.
                 pub const fn card_info(&self) -> &'static crate::types::SpellCardInfo<#game> {
                     &SPELL_CARDS[(self.0.get() - 1) as usize]
@@ -249,6 +279,36 @@ impl SpellList {
                 pub const fn unwrap(self) -> u16 {
                     self.0.get()
                 }
+
+                /// Iterates over every spell that appears on the given difficulty, in ID order.
+                pub fn cards_for_difficulty(
+                    difficulty: impl Into<crate::types::Difficulty<#game>>,
+                ) -> impl Iterator<Item = crate::types::SpellCard<#game>> {
+                    let difficulty = difficulty.into();
+                    <Self as crate::types::AllIterable>::iter_all()
+                        .filter(move |id| id.card_info().difficulty == difficulty)
+                        .map(crate::types::SpellCard::new)
+                }
+
+                /// Iterates over every spell that appears on the given stage and difficulty, in ID order.
+                pub fn cards(
+                    stage: impl Into<crate::types::Stage<#game>>,
+                    difficulty: impl Into<crate::types::Difficulty<#game>>,
+                ) -> impl Iterator<Item = crate::types::SpellCard<#game>> {
+                    let stage = stage.into();
+                    let difficulty = difficulty.into();
+                    <Self as crate::types::AllIterable>::iter_all()
+                        .filter(move |id| id.card_info().stage == stage && id.card_info().difficulty == difficulty)
+                        .map(crate::types::SpellCard::new)
+                }
+
+                /// Iterates over every spell whose name contains the given string, ignoring case, in ID order.
+                pub fn search_cards(query: &str) -> impl Iterator<Item = crate::types::SpellCard<#game>> {
+                    let query = query.to_lowercase();
+                    <Self as crate::types::AllIterable>::iter_all()
+                        .filter(move |id| id.card_info().name.to_lowercase().contains(&query))
+                        .map(crate::types::SpellCard::new)
+                }
             }
 
             #(#conversions)*
@@ -341,7 +401,7 @@ impl SpellList {
 
                 #[inline]
                 fn iter_all() -> IterAllSpells {
-                    IterAllSpells(1..=#n_cards)
+                    IterAllSpells(#valid_ids)
                 }
             }
 
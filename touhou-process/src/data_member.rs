@@ -1,10 +1,19 @@
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use bytemuck::{AnyBitPattern, CheckedBitPattern};
+use bytemuck::{AnyBitPattern, CheckedBitPattern, NoUninit};
 
 use crate::{Architecture, ProcessHandle};
 
+/// The error returned when writing to an item whose offset chain doesn't resolve to an address.
+fn unresolved_write() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "cannot write to data item with an empty offset chain",
+    )
+}
+
 #[derive(Debug, Copy, Clone)]
 pub struct FixedData<T: ?Sized + 'static, A: Architecture> {
     offsets: &'static [usize],
@@ -29,6 +38,11 @@ impl<T: ?Sized + 'static, A: Architecture> FixedData<T, A> {
             .map(Option::unwrap)
     }
 
+    /// Gets the handle to the process that this item is read from.
+    pub fn handle(&self) -> ProcessHandle {
+        self.handle
+    }
+
     /// Unsafely read this item into a mutable reference.
     ///
     /// # Safety
@@ -52,6 +66,12 @@ impl<T: CheckedBitPattern, A: Architecture> FixedData<T, A> {
         self.get_address()
             .and_then(|addr| self.handle.read_checked(addr))
     }
+
+    /// Read `count` consecutive values starting at this item's address, checking each for a valid bit pattern.
+    pub fn read_checked_slice(&self, count: usize) -> std::io::Result<Vec<Option<T>>> {
+        self.get_address()
+            .and_then(|addr| self.handle.read_checked_slice(addr, count))
+    }
 }
 
 impl<T: AnyBitPattern, A: Architecture> FixedData<T, A> {
@@ -68,41 +88,203 @@ impl<T: AnyBitPattern, A: Architecture> FixedData<T, A> {
     pub fn read(&self) -> std::io::Result<T> {
         self.get_address().and_then(|addr| self.handle.read(addr))
     }
+
+    /// Read a series of evenly-spaced items, starting at this item's address.
+    ///
+    /// Element `i` of `dest` is read from `base + i * stride`, where `base` is the address of this item.
+    /// The entire span covering all of the items is read at once, so it must be readable as a whole.
+    pub fn read_strided_into(&self, stride: usize, dest: &mut [T]) -> std::io::Result<()> {
+        if dest.is_empty() {
+            return Ok(());
+        }
+
+        let base = self.get_address()?;
+        let item_sz = std::mem::size_of::<T>();
+        let span = (dest.len() - 1)
+            .checked_mul(stride)
+            .and_then(|offset| offset.checked_add(item_sz))
+            .filter(|span| base.checked_add(*span).is_some())
+            .ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "address calculation overflowed",
+                )
+            })?;
+
+        let mut buf = vec![0u8; span];
+        self.handle.read_into_slice(base, &mut buf[..])?;
+
+        for (i, item) in dest.iter_mut().enumerate() {
+            let start = i * stride;
+            *item = bytemuck::pod_read_unaligned(&buf[start..(start + item_sz)]);
+        }
+
+        Ok(())
+    }
+
+    /// Read `count` evenly-spaced items, starting at this item's address.
+    ///
+    /// This is a convenience method around [`read_strided_into`](Self::read_strided_into).
+    pub fn read_strided(&self, stride: usize, count: usize) -> std::io::Result<Vec<T>> {
+        let mut ret = vec![T::zeroed(); count];
+        self.read_strided_into(stride, &mut ret)?;
+        Ok(ret)
+    }
+}
+
+impl<T: NoUninit, A: Architecture> FixedData<T, A> {
+    pub fn write(&self, value: &T) -> std::io::Result<()> {
+        let addr = self
+            .handle
+            .get_offset(&self.arch, self.offsets)?
+            .ok_or_else(unresolved_write)?;
+
+        self.handle.write(addr, value)
+    }
+
+    pub fn write_slice(&self, values: &[T]) -> std::io::Result<()> {
+        let addr = self
+            .handle
+            .get_offset(&self.arch, self.offsets)?
+            .ok_or_else(unresolved_write)?;
+
+        self.handle.write_slice(addr, values)
+    }
 }
 
-#[derive(Debug, Clone)]
-pub struct DataItem<T: ?Sized + 'static, A: Architecture> {
-    offsets: Vec<usize>,
+/// A value in another process's memory, located by following a runtime-specified chain of pointer offsets.
+///
+/// By default, items walk the whole offset chain from the root pointer on every access, as do items created
+/// explicitly with [`new_volatile`](Self::new_volatile). Items created with [`new_cached`](Self::new_cached)
+/// (or [`new_cached_with_arch`](Self::new_cached_with_arch)) instead cache the resolved address after the first
+/// access, and only walk the chain again if accessing the cached address fails (or after
+/// [`invalidate`](Self::invalidate) is called).
+///
+/// Which mode is appropriate depends on where the value lives:
+/// - The first-generation Windows games (such as PCB and IN) keep most of their game state in statically allocated
+///   structures at fixed addresses, which can be accessed through [`FixedData`] or a cached `DataItem`.
+/// - Newer games (such as MoF and later) allocate managers for enemies, bosses, and the player on the heap, and
+///   reallocate them when stages are loaded. Values behind these pointers can move without accesses to the old
+///   address failing, so they should be read using volatile items.
+///
+/// By default, the offset chain is stored in a `Vec`. Items with a chain length known at compile time can instead
+/// store their offsets inline in an array (`DataItem<T, A, [usize; N]>`), which avoids a heap allocation per item;
+/// see [`ProcessHandle::new_data_item_inline`].
+#[derive(Debug)]
+pub struct DataItem<T: ?Sized + 'static, A: Architecture, O: AsRef<[usize]> = Vec<usize>> {
+    offsets: O,
     arch: A,
     handle: ProcessHandle,
+    volatile: bool,
+    cached: AtomicUsize,
     _marker: PhantomData<(&'static T, A)>,
 }
 
+impl<T: ?Sized + 'static, A: Architecture + Clone, O: AsRef<[usize]> + Clone> Clone
+    for DataItem<T, A, O>
+{
+    fn clone(&self) -> Self {
+        Self {
+            offsets: self.offsets.clone(),
+            arch: self.arch.clone(),
+            handle: self.handle,
+            volatile: self.volatile,
+            cached: AtomicUsize::new(self.cached.load(Ordering::Relaxed)),
+            _marker: PhantomData,
+        }
+    }
+}
+
 impl<T: ?Sized + 'static, A: Architecture> DataItem<T, A> {
     pub const fn new_with_arch(handle: ProcessHandle, arch: A) -> Self {
+        Self::new_offsets_with_arch(handle, arch, Vec::new())
+    }
+}
+
+impl<T: ?Sized + 'static, A: Architecture, O: AsRef<[usize]>> DataItem<T, A, O> {
+    pub const fn new_offsets_with_arch(handle: ProcessHandle, arch: A, offsets: O) -> Self {
         Self {
             handle,
             arch,
-            offsets: Vec::new(),
+            offsets,
+            volatile: true,
+            cached: AtomicUsize::new(0),
             _marker: PhantomData,
         }
     }
 
-    pub const fn new_offsets_with_arch(
-        handle: ProcessHandle,
-        arch: A,
-        offsets: Vec<usize>,
-    ) -> Self {
+    /// Create an item that re-resolves its offset chain every time it is accessed.
+    ///
+    /// This is equivalent to [`new_offsets_with_arch`](Self::new_offsets_with_arch).
+    pub const fn new_volatile_with_arch(handle: ProcessHandle, arch: A, offsets: O) -> Self {
+        Self::new_offsets_with_arch(handle, arch, offsets)
+    }
+
+    /// Create an item that caches its resolved address, and only re-resolves its offset chain when accessing
+    /// the cached address fails.
+    ///
+    /// This should only be used for values that never move once allocated.
+    pub const fn new_cached_with_arch(handle: ProcessHandle, arch: A, offsets: O) -> Self {
         Self {
             handle,
             arch,
             offsets,
+            volatile: false,
+            cached: AtomicUsize::new(0),
             _marker: PhantomData,
         }
     }
 
+    /// Gets whether this item re-resolves its offset chain on every access.
+    pub fn is_volatile(&self) -> bool {
+        self.volatile
+    }
+
+    /// Resolve this item's address by walking its offset chain.
+    ///
+    /// This always walks the full chain, regardless of whether an address has been cached.
     pub fn get_address(&self) -> std::io::Result<Option<NonZeroUsize>> {
-        self.handle.get_offset(&self.arch, &self.offsets)
+        self.handle.get_offset(&self.arch, self.offsets.as_ref())
+    }
+
+    /// Discard any cached address, so that the offset chain is walked again on the next access.
+    pub fn invalidate(&self) {
+        self.cached.store(0, Ordering::Relaxed);
+    }
+
+    fn resolve_address(&self) -> std::io::Result<Option<NonZeroUsize>> {
+        let addr = self.get_address()?;
+        if !self.volatile {
+            self.cached
+                .store(addr.map_or(0, NonZeroUsize::get), Ordering::Relaxed);
+        }
+
+        Ok(addr)
+    }
+
+    /// Run an access against this item's address, re-resolving it and trying again if a cached address fails.
+    fn with_address<U>(
+        &self,
+        mut f: impl FnMut(NonZeroUsize) -> std::io::Result<U>,
+    ) -> std::io::Result<Option<U>> {
+        let cached = if self.volatile {
+            None
+        } else {
+            NonZeroUsize::new(self.cached.load(Ordering::Relaxed))
+        };
+
+        if let Some(addr) = cached {
+            if let Ok(ret) = f(addr) {
+                return Ok(Some(ret));
+            }
+        }
+
+        self.resolve_address()?.map(f).transpose()
+    }
+
+    /// Gets the handle to the process that this item is read from.
+    pub fn handle(&self) -> ProcessHandle {
+        self.handle
     }
 
     /// Unsafely read this item into a mutable reference.
@@ -113,11 +295,8 @@ impl<T: ?Sized + 'static, A: Architecture> DataItem<T, A> {
     /// many of the same rules need to be followed. In particular, the caller
     /// must ensure that the read value is valid for type `T`.
     pub unsafe fn read_unsafe(&self, dest: &mut T) -> std::io::Result<()> {
-        if let Some(addr) = self.get_address()? {
-            self.handle.read_unsafe(addr, dest)
-        } else {
-            Ok(())
-        }
+        self.with_address(|addr| self.handle.read_unsafe(addr, dest))
+            .map(|_| ())
     }
 }
 
@@ -125,40 +304,76 @@ impl<T: ?Sized + 'static, A: Architecture + Default> DataItem<T, A> {
     pub fn new(handle: ProcessHandle) -> Self {
         Self::new_with_arch(handle, Default::default())
     }
+}
 
-    pub fn new_offsets(handle: ProcessHandle, offsets: Vec<usize>) -> Self {
+impl<T: ?Sized + 'static, A: Architecture + Default, O: AsRef<[usize]>> DataItem<T, A, O> {
+    pub fn new_offsets(handle: ProcessHandle, offsets: O) -> Self {
         Self::new_offsets_with_arch(handle, Default::default(), offsets)
     }
+
+    /// Create an item that re-resolves its offset chain every time it is accessed.
+    ///
+    /// This is equivalent to [`new_offsets`](Self::new_offsets).
+    pub fn new_volatile(handle: ProcessHandle, offsets: O) -> Self {
+        Self::new_volatile_with_arch(handle, Default::default(), offsets)
+    }
+
+    /// Create an item that caches its resolved address.
+    ///
+    /// See [`new_cached_with_arch`](Self::new_cached_with_arch) for details.
+    pub fn new_cached(handle: ProcessHandle, offsets: O) -> Self {
+        Self::new_cached_with_arch(handle, Default::default(), offsets)
+    }
 }
 
-impl<T: CheckedBitPattern, A: Architecture> DataItem<T, A> {
-    pub fn read_checked(&self) -> std::io::Result<Option<T>> {
-        self.get_address()?
-            .and_then(|addr| self.handle.read_checked(addr).transpose())
-            .transpose()
+impl<T: CheckedBitPattern, A: Architecture, O: AsRef<[usize]>> DataItem<T, A, O> {
+    /// Read this item, checking it for a valid bit pattern.
+    ///
+    /// Returns `None` if this item's offset chain is empty, and `Some(None)` if the value read
+    /// was not a valid bit pattern for `T`.
+    pub fn read_checked(&self) -> std::io::Result<Option<Option<T>>> {
+        self.with_address(|addr| self.handle.read_checked(addr))
+    }
+
+    /// Read `count` consecutive values starting at this item's address, checking each for a valid bit pattern.
+    ///
+    /// Returns `None` if this item's offset chain is empty.
+    pub fn read_checked_slice(&self, count: usize) -> std::io::Result<Option<Vec<Option<T>>>> {
+        self.with_address(|addr| self.handle.read_checked_slice(addr, count))
     }
 }
 
-impl<T: AnyBitPattern, A: Architecture> DataItem<T, A> {
+impl<T: AnyBitPattern, A: Architecture, O: AsRef<[usize]>> DataItem<T, A, O> {
     pub fn read_into(&self, dest: &mut T) -> std::io::Result<()> {
-        if let Some(addr) = self.get_address()? {
-            self.handle.read_into(addr, dest)
-        } else {
-            Ok(())
-        }
+        self.with_address(|addr| self.handle.read_into(addr, dest))
+            .map(|_| ())
     }
 
     pub fn read_into_slice(&self, dest: &mut [T]) -> std::io::Result<()> {
-        if let Some(addr) = self.get_address()? {
-            self.handle.read_into_slice(addr, dest)
-        } else {
-            Ok(())
-        }
+        self.with_address(|addr| self.handle.read_into_slice(addr, dest))
+            .map(|_| ())
     }
 
     pub fn read(&self) -> std::io::Result<Option<T>> {
-        self.get_address()?
-            .map(|addr| self.handle.read(addr))
-            .transpose()
+        self.with_address(|addr| self.handle.read(addr))
+    }
+}
+
+impl<T: NoUninit, A: Architecture, O: AsRef<[usize]>> DataItem<T, A, O> {
+    /// Write a value to this item's address.
+    ///
+    /// Returns an error of kind [`NotFound`](std::io::ErrorKind::NotFound) if this item's offset chain is empty,
+    /// rather than silently doing nothing.
+    pub fn write(&self, value: &T) -> std::io::Result<()> {
+        self.with_address(|addr| self.handle.write(addr, value))?
+            .ok_or_else(unresolved_write)
+    }
+
+    /// Write several consecutive values starting at this item's address.
+    ///
+    /// Like [`write`](Self::write), this fails if this item's offset chain is empty.
+    pub fn write_slice(&self, values: &[T]) -> std::io::Result<()> {
+        self.with_address(|addr| self.handle.write_slice(addr, values))?
+            .ok_or_else(unresolved_write)
     }
 }
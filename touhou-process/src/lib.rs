@@ -2,8 +2,9 @@ use std::borrow::Borrow;
 use std::io::ErrorKind;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
+use std::ops::Range;
 
-use bytemuck::{AnyBitPattern, CheckedBitPattern};
+use bytemuck::{AnyBitPattern, CheckedBitPattern, NoUninit};
 
 mod data_member;
 
@@ -14,6 +15,19 @@ pub use data_member::{DataItem, FixedData};
 #[path = "windows.rs"]
 mod platform;
 
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod platform;
+
+/// Maximum size of each block of memory read by [`ProcessHandle::read_cstring`].
+const CSTRING_CHUNK_SIZE: usize = 64;
+
+/// Page size assumed when splitting reads to avoid crossing into unmapped memory.
+const PAGE_SIZE: usize = 0x1000;
+
+/// Size of each block of memory read by [`ProcessHandle::scan_signature`].
+const SCAN_CHUNK_SIZE: usize = 0x10000;
+
 mod private {
     pub trait Sealed {}
 }
@@ -24,9 +38,21 @@ mod private {
 pub struct Pid(platform::Pid);
 
 impl Pid {
+    /// Open a handle for reading the memory of the process with this PID.
+    ///
+    /// Handles opened this way may not be able to write to the process's memory on every platform;
+    /// use [`try_into_writable_process_handle`](Self::try_into_writable_process_handle) for that.
     pub fn try_into_process_handle(self) -> std::io::Result<ProcessHandle> {
         platform::try_into_process_handle(self.0).map(ProcessHandle)
     }
+
+    /// Open a handle for both reading and writing the memory of the process with this PID.
+    ///
+    /// This requests additional access rights compared to [`try_into_process_handle`](Self::try_into_process_handle),
+    /// so it may fail in environments where the process's memory can only be read.
+    pub fn try_into_writable_process_handle(self) -> std::io::Result<ProcessHandle> {
+        platform::try_into_writable_process_handle(self.0).map(ProcessHandle)
+    }
 }
 
 impl From<u32> for Pid {
@@ -98,6 +124,37 @@ impl ProcessHandle {
         unsafe { self.read_unsafe(addr, dest) }
     }
 
+    /// Read a NUL-terminated string from another process's address space.
+    ///
+    /// This reads at most `max_len` bytes starting at `addr`, stopping at the first NUL byte.
+    /// The returned buffer does not include the terminator. Memory is read in small blocks that
+    /// never cross a page boundary, so this will not fail just because the string is
+    /// immediately followed by unmapped memory.
+    pub fn read_cstring(&self, addr: NonZeroUsize, max_len: usize) -> std::io::Result<Vec<u8>> {
+        let mut ret = Vec::new();
+        let mut buf = [0; CSTRING_CHUNK_SIZE];
+        let mut cur = addr;
+
+        while ret.len() < max_len {
+            let to_page_end = PAGE_SIZE - (cur.get() % PAGE_SIZE);
+            let len = CSTRING_CHUNK_SIZE.min(max_len - ret.len()).min(to_page_end);
+
+            self.read_into_slice(cur, &mut buf[..len])?;
+
+            if let Some(end) = buf[..len].iter().position(|&b| b == 0) {
+                ret.extend_from_slice(&buf[..end]);
+                break;
+            }
+
+            ret.extend_from_slice(&buf[..len]);
+            cur = cur.checked_add(len).ok_or_else(|| {
+                std::io::Error::new(ErrorKind::InvalidData, "address calculation overflowed")
+            })?;
+        }
+
+        Ok(ret)
+    }
+
     /// Safely read a value from another process's address space.
     ///
     /// This is a convenience method around [`read_into`] that reads into a temporary value,
@@ -125,6 +182,135 @@ impl ProcessHandle {
         }
     }
 
+    /// Check whether `len` bytes starting at `addr` are mapped and readable in another process's address space.
+    ///
+    /// This queries the target process's memory map (via `VirtualQueryEx` on Windows, or `/proc/<pid>/maps` on Linux)
+    /// without actually reading anything, which is useful for failing fast when following pointers into memory that
+    /// the game hasn't set up yet. If the memory map can't be queried, this returns `false`.
+    ///
+    /// Note that the target process can change its memory mappings at any time, so a subsequent read may still fail.
+    pub fn is_readable(&self, addr: NonZeroUsize, len: usize) -> bool {
+        platform::is_readable(self.0, addr, len)
+    }
+
+    /// Read an array of `count` values from another process's address space, checking each for a valid bit pattern.
+    ///
+    /// This works like [`read_checked`](Self::read_checked), but for contiguous arrays of values.
+    /// Elements with invalid bit patterns are returned as `None` rather than causing the entire read to fail.
+    pub fn read_checked_slice<T: CheckedBitPattern>(
+        &self,
+        addr: NonZeroUsize,
+        count: usize,
+    ) -> std::io::Result<Vec<Option<T>>> {
+        let mut bits = vec![<T::Bits as bytemuck::Zeroable>::zeroed(); count];
+        self.read_into_slice(addr, &mut bits)?;
+
+        Ok(bits
+            .into_iter()
+            .map(|bits| {
+                if T::is_valid_bit_pattern(&bits) {
+                    // SAFETY: We checked to ensure a valid bit pattern for the transmute.
+                    Some(unsafe { std::mem::transmute_copy(&std::mem::ManuallyDrop::new(bits)) })
+                } else {
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Read several separate regions of another process's address space at once.
+    ///
+    /// Each request is a pair of a source address and a destination buffer that will be filled with
+    /// data from that address. Where the platform supports it, all regions are read using a single
+    /// vectored system call, which reduces overhead and makes it less likely for values to be
+    /// modified by the target process partway through reading them.
+    ///
+    /// If any region cannot be read, this returns an error; in that case the contents of all destination
+    /// buffers are unspecified.
+    pub fn read_batch(&self, requests: &mut [(NonZeroUsize, &mut [u8])]) -> std::io::Result<()> {
+        platform::read_batch(self.0, requests)
+    }
+
+    /// Scan a region of another process's address space for a byte pattern.
+    ///
+    /// Each element of `pattern` either matches one specific byte value, or is `None` to match any byte.
+    /// Returns the address of the first match found that lies entirely within `range`, or `None`
+    /// if no match was found.
+    ///
+    /// The region is read in large blocks, so the entirety of `range` must be readable;
+    /// otherwise an error is returned.
+    pub fn scan_signature(
+        &self,
+        pattern: &[Option<u8>],
+        range: Range<usize>,
+    ) -> std::io::Result<Option<NonZeroUsize>> {
+        if pattern.is_empty() {
+            return Ok(NonZeroUsize::new(range.start).filter(|_| !range.is_empty()));
+        }
+
+        if range.len() < pattern.len() {
+            return Ok(None);
+        }
+
+        let matches = |window: &[u8]| {
+            window
+                .iter()
+                .zip(pattern)
+                .all(|(byte, expected)| expected.is_none() || *expected == Some(*byte))
+        };
+
+        /* consecutive blocks overlap so that matches spanning block boundaries are still found */
+        let mut buf = vec![0; SCAN_CHUNK_SIZE.max(pattern.len())];
+        let mut start = range.start;
+
+        while range.end - start >= pattern.len() {
+            let len = buf.len().min(range.end - start);
+            let addr = NonZeroUsize::new(start).ok_or_else(|| {
+                std::io::Error::new(ErrorKind::InvalidInput, "attempted to scan null pointer")
+            })?;
+
+            self.read_into_slice(addr, &mut buf[..len])?;
+
+            if let Some(pos) = buf[..len].windows(pattern.len()).position(matches) {
+                return Ok(NonZeroUsize::new(start + pos));
+            }
+
+            if start + len >= range.end {
+                break;
+            }
+
+            start += len - (pattern.len() - 1);
+        }
+
+        Ok(None)
+    }
+
+    /// Unsafely write memory at `addr` within another process's address space.
+    ///
+    /// # Safety
+    ///
+    /// This is effectively a raw pointer write but across processes. The caller
+    /// must ensure that `src` contains no uninitialized bytes (such as padding),
+    /// and that the written value is valid for whatever the target process expects
+    /// to find at `addr`.
+    unsafe fn write_unsafe<T: ?Sized>(&self, addr: NonZeroUsize, src: &T) -> std::io::Result<()> {
+        platform::write_unsafe(self.0, addr, src)
+    }
+
+    /// Safely write a value into another process's address space.
+    ///
+    /// This is like [`write_unsafe`], but can be called safely because `T` is bound by [`NoUninit`].
+    fn write<T: NoUninit>(&self, addr: NonZeroUsize, value: &T) -> std::io::Result<()> {
+        // SAFETY: The trait bound ensures that T has no uninitialized bytes.
+        unsafe { self.write_unsafe(addr, value) }
+    }
+
+    /// Safely write multiple items into another process's address space.
+    fn write_slice<T: NoUninit>(&self, addr: NonZeroUsize, values: &[T]) -> std::io::Result<()> {
+        // SAFETY: The trait bound ensures that T has no uninitialized bytes.
+        unsafe { self.write_unsafe(addr, values) }
+    }
+
     /// Get an actual memory location by following a list of offsets.
     fn get_offset<A: Architecture>(
         &self,
@@ -173,6 +359,33 @@ impl ProcessHandle {
         FixedData::new_with_arch(*self, arch, offsets)
     }
 
+    /// Like [`Self::new_fixed_item`], but checks that the offset chain is non-empty and starts from a non-null address.
+    pub fn try_new_fixed_item<T: ?Sized, A: Architecture + Default>(
+        &self,
+        offsets: &'static [usize],
+    ) -> std::io::Result<FixedData<T, A>> {
+        self.try_new_fixed_item_arch(A::default(), offsets)
+    }
+
+    /// Like [`Self::new_fixed_item_arch`], but checks that the offset chain is non-empty and starts from a non-null address.
+    pub fn try_new_fixed_item_arch<T: ?Sized, A: Architecture>(
+        &self,
+        arch: A,
+        offsets: &'static [usize],
+    ) -> std::io::Result<FixedData<T, A>> {
+        match offsets.first() {
+            None => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "offset chain for fixed data item is empty",
+            )),
+            Some(0) => Err(std::io::Error::new(
+                ErrorKind::InvalidInput,
+                "offset chain for fixed data item starts from a null address",
+            )),
+            Some(_) => Ok(self.new_fixed_item_arch(arch, offsets)),
+        }
+    }
+
     pub fn new_data_item<T: ?Sized, A: Architecture + Default>(&self) -> DataItem<T, A> {
         DataItem::new(*self)
     }
@@ -195,6 +408,23 @@ impl ProcessHandle {
     ) -> DataItem<T, A> {
         DataItem::new_offsets_with_arch(*self, arch, offsets)
     }
+
+    /// Like [`Self::new_data_item_offsets`], but stores the offset chain inline instead of in a `Vec`.
+    pub fn new_data_item_inline<T: ?Sized, A: Architecture + Default, const N: usize>(
+        &self,
+        offsets: [usize; N],
+    ) -> DataItem<T, A, [usize; N]> {
+        DataItem::new_offsets(*self, offsets)
+    }
+
+    /// Like [`Self::new_data_item_offsets_arch`], but stores the offset chain inline instead of in a `Vec`.
+    pub fn new_data_item_inline_arch<T: ?Sized, A: Architecture, const N: usize>(
+        &self,
+        arch: A,
+        offsets: [usize; N],
+    ) -> DataItem<T, A, [usize; N]> {
+        DataItem::new_offsets_with_arch(*self, arch, offsets)
+    }
 }
 
 macro_rules! impl_architectures {
@@ -348,3 +578,43 @@ impl_architectures! {
     4: u32,
     8: u64
 }
+
+/// An [`Architecture`] that adds a constant base offset to every pointer read by another architecture.
+///
+/// This is useful for attaching to game images that have been relocated from their usual load address
+/// (for example, under emulation), without needing to rewrite every offset used to access them.
+/// Null pointers are passed through as-is, without the offset being applied.
+#[derive(Debug, Clone, Copy, Default)]
+#[repr(transparent)]
+pub struct OffsetArchitecture<A, const BASE: usize>(A);
+
+impl<A: Architecture, const BASE: usize> OffsetArchitecture<A, BASE> {
+    pub const fn new(inner: A) -> Self {
+        Self(inner)
+    }
+
+    /// Gets a reference to the wrapped architecture.
+    pub const fn inner(&self) -> &A {
+        &self.0
+    }
+}
+
+impl<A: Architecture, const BASE: usize> private::Sealed for OffsetArchitecture<A, BASE> {}
+
+impl<A: Architecture, const BASE: usize> Architecture for OffsetArchitecture<A, BASE> {
+    #[inline(always)]
+    fn read_pointer(
+        &self,
+        handle: &ProcessHandle,
+        addr: NonZeroUsize,
+    ) -> std::io::Result<Option<NonZeroUsize>> {
+        self.0
+            .read_pointer(handle, addr)?
+            .map(|ptr| {
+                ptr.checked_add(BASE).ok_or_else(|| {
+                    std::io::Error::new(ErrorKind::InvalidData, "address calculation overflowed")
+                })
+            })
+            .transpose()
+    }
+}
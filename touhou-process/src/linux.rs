@@ -0,0 +1,331 @@
+use std::fs::{File, OpenOptions};
+use std::io::ErrorKind;
+use std::num::NonZeroUsize;
+use std::os::unix::fs::FileExt;
+
+use super::ProcessHandle as WrappedHandle;
+
+pub(crate) type Pid = libc::pid_t;
+
+/// Processes on Linux don't have handles as such, so we just identify them by PID.
+pub(crate) type ProcessHandle = libc::pid_t;
+
+/// Translate an OS error code from a memory access into an appropriate error.
+fn read_error(errno: i32) -> std::io::Error {
+    match errno {
+        libc::EFAULT | libc::EIO => std::io::Error::new(
+            ErrorKind::InvalidInput,
+            "address is not mapped in target process",
+        ),
+        libc::ESRCH | libc::ENOENT => {
+            std::io::Error::new(ErrorKind::NotFound, "target process does not exist")
+        }
+        libc::EPERM | libc::EACCES => std::io::Error::new(
+            ErrorKind::PermissionDenied,
+            "not permitted to access target process memory",
+        ),
+        other => std::io::Error::from_raw_os_error(other),
+    }
+}
+
+pub(crate) fn try_into_process_handle(pid: Pid) -> std::io::Result<ProcessHandle> {
+    // SAFETY: signal 0 doesn't actually send a signal; it only checks whether the process exists.
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        Ok(pid)
+    } else {
+        let err = std::io::Error::last_os_error();
+        match err.raw_os_error() {
+            /* the process exists, but we might still be able to read its memory via ptrace permissions */
+            Some(libc::EPERM) => Ok(pid),
+            Some(errno) => Err(read_error(errno)),
+            None => Err(err),
+        }
+    }
+}
+
+/// Linux has no per-handle access rights; write permissions are checked when memory is actually written.
+pub(crate) fn try_into_writable_process_handle(pid: Pid) -> std::io::Result<ProcessHandle> {
+    try_into_process_handle(pid)
+}
+
+pub(crate) fn pid_from_u32(value: u32) -> Pid {
+    value as Pid
+}
+
+pub(crate) fn pid_to_u32(value: Pid) -> u32 {
+    value as u32
+}
+
+/// Read memory using `process_vm_readv`.
+///
+/// Returns `Ok(false)` if the syscall isn't available, in which case the caller should fall back to another method.
+fn read_vm(pid: Pid, mut addr: usize, mut dest: &mut [u8]) -> std::io::Result<bool> {
+    while !dest.is_empty() {
+        let local = libc::iovec {
+            iov_base: dest.as_mut_ptr().cast(),
+            iov_len: dest.len(),
+        };
+
+        let remote = libc::iovec {
+            iov_base: addr as *mut libc::c_void,
+            iov_len: dest.len(),
+        };
+
+        // SAFETY: the local iovec covers exactly the memory of `dest`, which we have exclusive access to.
+        let n = unsafe { libc::process_vm_readv(pid, &local, 1, &remote, 1, 0) };
+
+        match n {
+            /* Partial reads only happen when the remote range crosses into an unreadable page,
+             * in which case the next call will fail with EFAULT. */
+            0 => return Err(read_error(libc::EFAULT)),
+            n if n > 0 => {
+                let n = n as usize;
+                dest = &mut dest[n..];
+                addr += n;
+            }
+            _ => {
+                let err = std::io::Error::last_os_error();
+                return match err.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => Ok(false),
+                    Some(errno) => Err(read_error(errno)),
+                    None => Err(err),
+                };
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Read memory via `/proc/<pid>/mem`.
+fn read_proc_mem(pid: Pid, mut addr: usize, mut dest: &mut [u8]) -> std::io::Result<()> {
+    let file = File::open(format!("/proc/{}/mem", pid))
+        .map_err(|e| e.raw_os_error().map(read_error).unwrap_or(e))?;
+
+    while !dest.is_empty() {
+        match file.read_at(dest, addr as u64) {
+            Ok(0) => return Err(read_error(libc::EFAULT)),
+            Ok(n) => {
+                dest = &mut dest[n..];
+                addr += n;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.raw_os_error().map(read_error).unwrap_or(e)),
+        }
+    }
+
+    Ok(())
+}
+
+/// Write memory using `process_vm_writev`.
+///
+/// Returns `Ok(false)` if the syscall isn't available, in which case the caller should fall back to another method.
+fn write_vm(pid: Pid, mut addr: usize, mut src: &[u8]) -> std::io::Result<bool> {
+    while !src.is_empty() {
+        let local = libc::iovec {
+            iov_base: src.as_ptr() as *mut libc::c_void,
+            iov_len: src.len(),
+        };
+
+        let remote = libc::iovec {
+            iov_base: addr as *mut libc::c_void,
+            iov_len: src.len(),
+        };
+
+        // SAFETY: the local iovec covers exactly the memory of `src`, which the syscall only reads from.
+        let n = unsafe { libc::process_vm_writev(pid, &local, 1, &remote, 1, 0) };
+
+        match n {
+            0 => return Err(read_error(libc::EFAULT)),
+            n if n > 0 => {
+                let n = n as usize;
+                src = &src[n..];
+                addr += n;
+            }
+            _ => {
+                let err = std::io::Error::last_os_error();
+                return match err.raw_os_error() {
+                    Some(libc::ENOSYS) | Some(libc::EPERM) => Ok(false),
+                    Some(errno) => Err(read_error(errno)),
+                    None => Err(err),
+                };
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Write memory via `/proc/<pid>/mem`.
+fn write_proc_mem(pid: Pid, mut addr: usize, mut src: &[u8]) -> std::io::Result<()> {
+    let file = OpenOptions::new()
+        .write(true)
+        .open(format!("/proc/{}/mem", pid))
+        .map_err(|e| e.raw_os_error().map(read_error).unwrap_or(e))?;
+
+    while !src.is_empty() {
+        match file.write_at(src, addr as u64) {
+            Ok(0) => return Err(read_error(libc::EFAULT)),
+            Ok(n) => {
+                src = &src[n..];
+                addr += n;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.raw_os_error().map(read_error).unwrap_or(e)),
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) unsafe fn read_unsafe<T: ?Sized>(
+    handle: ProcessHandle,
+    addr: NonZeroUsize,
+    dest: &mut T,
+) -> std::io::Result<()> {
+    let sz = std::mem::size_of_val(dest);
+    if sz == 0 {
+        return Ok(());
+    }
+
+    let dest = std::slice::from_raw_parts_mut((dest as *mut T).cast::<u8>(), sz);
+    if read_vm(handle, addr.get(), dest)? {
+        Ok(())
+    } else {
+        read_proc_mem(handle, addr.get(), dest)
+    }
+}
+
+/// Check whether a range of memory is mapped readable in the target process, according to `/proc/<pid>/maps`.
+pub(crate) fn is_readable(pid: Pid, addr: NonZeroUsize, len: usize) -> bool {
+    let maps = match std::fs::read_to_string(format!("/proc/{}/maps", pid)) {
+        Ok(maps) => maps,
+        Err(_) => return false,
+    };
+
+    let end = match addr.get().checked_add(len) {
+        Some(end) => end,
+        None => return false,
+    };
+
+    /* mappings are listed in ascending address order, so walk forward until the whole range is covered */
+    let mut cur = addr.get();
+    for line in maps.lines() {
+        let mut fields = line.split_whitespace();
+        let (range, perms) = match (fields.next(), fields.next()) {
+            (Some(range), Some(perms)) => (range, perms),
+            _ => continue,
+        };
+
+        let (start, stop) = match range.split_once('-').map(|(start, stop)| {
+            (
+                usize::from_str_radix(start, 16),
+                usize::from_str_radix(stop, 16),
+            )
+        }) {
+            Some((Ok(start), Ok(stop))) => (start, stop),
+            _ => continue,
+        };
+
+        if stop <= cur {
+            continue;
+        }
+
+        if start > cur || !perms.starts_with('r') {
+            return false;
+        }
+
+        if stop >= end {
+            return true;
+        }
+
+        cur = stop;
+    }
+
+    false
+}
+
+pub(crate) fn read_batch(
+    handle: ProcessHandle,
+    requests: &mut [(NonZeroUsize, &mut [u8])],
+) -> std::io::Result<()> {
+    let total: usize = requests.iter().map(|(_, dest)| dest.len()).sum();
+    let mut local = Vec::with_capacity(requests.len());
+    let mut remote = Vec::with_capacity(requests.len());
+
+    for (addr, dest) in requests.iter_mut() {
+        local.push(libc::iovec {
+            iov_base: dest.as_mut_ptr().cast(),
+            iov_len: dest.len(),
+        });
+
+        remote.push(libc::iovec {
+            iov_base: addr.get() as *mut libc::c_void,
+            iov_len: dest.len(),
+        });
+    }
+
+    /* process_vm_readv only accepts up to IOV_MAX iovecs per call */
+    let mut n_read = 0;
+    for (local, remote) in local
+        .chunks(libc::UIO_MAXIOV as usize)
+        .zip(remote.chunks(libc::UIO_MAXIOV as usize))
+    {
+        // SAFETY: each local iovec covers exactly the memory of one destination buffer,
+        // all of which we have exclusive access to through `requests`.
+        let n = unsafe {
+            libc::process_vm_readv(
+                handle,
+                local.as_ptr(),
+                local.len() as libc::c_ulong,
+                remote.as_ptr(),
+                remote.len() as libc::c_ulong,
+                0,
+            )
+        };
+
+        if n < 0 {
+            break;
+        }
+
+        n_read += n as usize;
+    }
+
+    if n_read == total {
+        return Ok(());
+    }
+
+    /* Fall back to reading each region individually, either because the syscall failed
+     * or because one of the regions could only be partially read. This also ensures we
+     * return an appropriate error for whichever region actually failed. */
+    for (addr, dest) in requests.iter_mut() {
+        // SAFETY: any bit pattern is valid for a byte slice.
+        unsafe { read_unsafe(handle, *addr, &mut **dest)? };
+    }
+
+    Ok(())
+}
+
+pub(crate) unsafe fn write_unsafe<T: ?Sized>(
+    handle: ProcessHandle,
+    addr: NonZeroUsize,
+    src: &T,
+) -> std::io::Result<()> {
+    let sz = std::mem::size_of_val(src);
+    if sz == 0 {
+        return Ok(());
+    }
+
+    let src = std::slice::from_raw_parts((src as *const T).cast::<u8>(), sz);
+    if write_vm(handle, addr.get(), src)? {
+        Ok(())
+    } else {
+        write_proc_mem(handle, addr.get(), src)
+    }
+}
+
+impl WrappedHandle {
+    pub fn from_child(child: std::process::Child) -> std::io::Result<Self> {
+        Ok(Self(child.id() as Pid))
+    }
+}
@@ -3,7 +3,14 @@ use std::num::NonZeroUsize;
 use std::os::windows::io::AsRawHandle;
 mod windows {
     pub(crate) use windows::Win32::Foundation::HANDLE;
-    pub(crate) use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+    pub(crate) use windows::Win32::System::Diagnostics::Debug::{
+        ReadProcessMemory, WriteProcessMemory,
+    };
+    pub(crate) use windows::Win32::System::Memory::{
+        VirtualQueryEx, MEMORY_BASIC_INFORMATION, MEM_COMMIT, PAGE_EXECUTE_READ,
+        PAGE_EXECUTE_READWRITE, PAGE_EXECUTE_WRITECOPY, PAGE_GUARD, PAGE_READONLY,
+        PAGE_READWRITE, PAGE_WRITECOPY,
+    };
     pub(crate) use windows::Win32::System::Threading::{
         OpenProcess, PROCESS_CREATE_THREAD, PROCESS_QUERY_INFORMATION, PROCESS_VM_OPERATION,
         PROCESS_VM_READ, PROCESS_VM_WRITE,
@@ -28,6 +35,21 @@ pub(crate) fn try_into_process_handle(pid: Pid) -> std::io::Result<ProcessHandle
     }
 }
 
+pub(crate) fn try_into_writable_process_handle(pid: Pid) -> std::io::Result<ProcessHandle> {
+    unsafe {
+        windows::OpenProcess(
+            windows::PROCESS_CREATE_THREAD
+                | windows::PROCESS_QUERY_INFORMATION
+                | windows::PROCESS_VM_READ
+                | windows::PROCESS_VM_WRITE
+                | windows::PROCESS_VM_OPERATION,
+            false,
+            pid,
+        )
+        .map_err(From::from)
+    }
+}
+
 pub(crate) fn pid_from_u32(value: u32) -> Pid {
     value
 }
@@ -56,6 +78,83 @@ pub(crate) unsafe fn read_unsafe<T: ?Sized>(
     }
 }
 
+pub(crate) fn is_readable(handle: ProcessHandle, addr: NonZeroUsize, len: usize) -> bool {
+    const READABLE: u32 = windows::PAGE_READONLY.0
+        | windows::PAGE_READWRITE.0
+        | windows::PAGE_WRITECOPY.0
+        | windows::PAGE_EXECUTE_READ.0
+        | windows::PAGE_EXECUTE_READWRITE.0
+        | windows::PAGE_EXECUTE_WRITECOPY.0;
+
+    let end = match addr.get().checked_add(len) {
+        Some(end) => end,
+        None => return false,
+    };
+
+    let mut cur = addr.get();
+    loop {
+        let mut info = windows::MEMORY_BASIC_INFORMATION::default();
+
+        // SAFETY: `info` is a valid, writable MEMORY_BASIC_INFORMATION structure of the size we pass in.
+        let n = unsafe {
+            windows::VirtualQueryEx(
+                handle,
+                Some(cur as *const c_void),
+                &mut info,
+                std::mem::size_of::<windows::MEMORY_BASIC_INFORMATION>(),
+            )
+        };
+
+        if n == 0
+            || info.State != windows::MEM_COMMIT
+            || (info.Protect.0 & windows::PAGE_GUARD.0) != 0
+            || (info.Protect.0 & READABLE) == 0
+        {
+            return false;
+        }
+
+        let region_end = (info.BaseAddress as usize).saturating_add(info.RegionSize);
+        if region_end >= end {
+            return true;
+        }
+
+        cur = region_end;
+    }
+}
+
+pub(crate) fn read_batch(
+    handle: ProcessHandle,
+    requests: &mut [(NonZeroUsize, &mut [u8])],
+) -> std::io::Result<()> {
+    /* Windows has no scatter/gather equivalent of ReadProcessMemory, so just issue the reads back-to-back. */
+    for (addr, dest) in requests.iter_mut() {
+        // SAFETY: any bit pattern is valid for a byte slice.
+        unsafe { read_unsafe(handle, *addr, &mut **dest)? };
+    }
+
+    Ok(())
+}
+
+pub(crate) unsafe fn write_unsafe<T: ?Sized>(
+    handle: ProcessHandle,
+    addr: NonZeroUsize,
+    src: &T,
+) -> std::io::Result<()> {
+    let sz = std::mem::size_of_val(src);
+    let src = (src as *const T).cast();
+
+    if sz > 0 {
+        if windows::WriteProcessMemory(handle, addr.get() as *const c_void, src, sz, None) == false
+        {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(())
+        }
+    } else {
+        Ok(())
+    }
+}
+
 impl WrappedHandle {
     pub fn from_child(child: std::process::Child) -> std::io::Result<Self> {
         Ok(Self(windows::HANDLE(child.as_raw_handle() as isize)))
@@ -5,9 +5,9 @@ use std::path::Path;
 use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
-use sqlx::{Acquire, Sqlite};
+use sqlx::Acquire;
 use time::OffsetDateTime;
-use tokio::fs;
+use tokio::{fs, task};
 
 use crate::types::{Difficulty, Game, ScoreFile, ShotType, SpellCard, Stage};
 
@@ -15,6 +15,49 @@ mod row_types;
 
 pub use row_types::{CardSnapshot, CardSnapshotKey, PracticeSnapshot, PracticeSnapshotKey};
 
+/// The database backend used for storing snapshots.
+///
+/// SQLite is currently the only supported backend.
+pub type Backend = sqlx::Sqlite;
+
+/// A connection pool for the [`Backend`] database.
+pub type Pool = sqlx::Pool<Backend>;
+
+impl<G: Game> CardSnapshot<G> {
+    /// Gets every stored snapshot of a card for a shot type taken between `start` and `end` (inclusive),
+    /// ordered by timestamp.
+    ///
+    /// This is the windowed counterpart to [`CardSnapshot::get_first_snapshot_after`], and can be used
+    /// to chart a card's capture rate over time.
+    pub async fn get_snapshots_between<'e, E>(
+        executor: E,
+        card: SpellCard<G>,
+        shot_type: ShotType<G>,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<Vec<Self>, sqlx::Error>
+    where
+        E: sqlx::Executor<'e, Database = Backend>,
+        Self: for<'r> sqlx::FromRow<'r, <Backend as sqlx::Database>::Row> + Send + Unpin,
+        SpellCard<G>: for<'q> sqlx::Encode<'q, Backend> + sqlx::Type<Backend> + Send,
+        ShotType<G>: for<'q> sqlx::Encode<'q, Backend> + sqlx::Type<Backend> + Send,
+    {
+        sqlx::query_as(
+            r#"
+            SELECT * FROM spellcards
+            WHERE card_id = $1 AND shot_type = $2 AND ts >= $3 AND ts <= $4
+            ORDER BY ts ASC
+            "#,
+        )
+        .bind(card)
+        .bind(shot_type)
+        .bind(start)
+        .bind(end)
+        .fetch_all(executor)
+        .await
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CardAttemptInfo {
     is_capture: bool,
@@ -147,6 +190,18 @@ impl<G: Game> FileSnapshot<G> {
         })
     }
 
+    /// Parses a score file snapshot on the blocking thread pool, to avoid stalling the async runtime.
+    pub async fn new_async(
+        game: G,
+        timestamp: OffsetDateTime,
+        data: Vec<u8>,
+    ) -> Result<Self, anyhow::Error>
+    where
+        G: Send + 'static,
+    {
+        task::spawn_blocking(move || Self::new(&game, timestamp, Cursor::new(data))).await?
+    }
+
     pub fn timestamp(&self) -> OffsetDateTime {
         self.timestamp
     }
@@ -178,7 +233,7 @@ impl<G: Game> FileSnapshot<G> {
 
     pub async fn insert<'c, C>(&self, conn: C) -> Result<(), sqlx::Error>
     where
-        C: Acquire<'c, Database = Sqlite>,
+        C: Acquire<'c, Database = Backend>,
     {
         let mut tx = conn.begin().await?;
 
@@ -351,15 +406,33 @@ impl<G: Game> SnapshotStream<G> {
         self.game.score_path()
     }
 
-    pub async fn read_snapshot_data(&mut self) -> Result<FileSnapshot<G>, anyhow::Error> {
+    pub async fn read_snapshot_data(&mut self) -> Result<FileSnapshot<G>, anyhow::Error>
+    where
+        G: Clone + Send + 'static,
+    {
         let timestamp = OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc());
-        fs::read(self.game.score_path())
-            .await
-            .map_err(|e| e.into())
-            .and_then(|data| FileSnapshot::new(&self.game, timestamp, Cursor::new(data)))
+        let data = fs::read(self.game.score_path()).await?;
+        FileSnapshot::new_async(self.game.clone(), timestamp, data).await
     }
 
-    pub async fn refresh_snapshots(&mut self) -> Result<Option<FileSnapshot<G>>, anyhow::Error> {
+    /// Reads a complete snapshot of the score file, regardless of whether it has changed.
+    ///
+    /// This also records the file's current modification time, so that a following call to
+    /// [`Self::refresh_snapshots`] will not read the same data again.
+    pub async fn full_snapshot(&mut self) -> Result<FileSnapshot<G>, anyhow::Error>
+    where
+        G: Clone + Send + 'static,
+    {
+        let mtime = fs::metadata(self.game.score_path()).await?.modified()?;
+        let snapshot = self.read_snapshot_data().await?;
+        self.last_modified = self.last_modified.max(mtime);
+        Ok(snapshot)
+    }
+
+    pub async fn refresh_snapshots(&mut self) -> Result<Option<FileSnapshot<G>>, anyhow::Error>
+    where
+        G: Clone + Send + 'static,
+    {
         let cur_time = SystemTime::now();
         let mtime = fs::metadata(self.game.score_path()).await?.modified()?;
 
@@ -1,10 +1,10 @@
 use std::env;
 use std::time::Duration;
 
-use sqlx::sqlite::SqlitePool;
 use tokio::sync::oneshot;
 use tokio::time::interval;
-use types::Game;
+use touhou::score::{CardStats, CardStatsFormatter};
+use touhou::types::{Game, ShotType, SpellCard};
 
 pub mod crypt;
 pub mod db;
@@ -15,15 +15,32 @@ pub mod score_file;
 pub mod th07;
 pub mod th18;
 
-use db::{CardAttemptInfo, CardSnapshot, SnapshotStream, UpdateStream};
+use db::{CardAttemptInfo, CardSnapshot, Pool, SnapshotStream, UpdateStream};
 use types::Touhou;
 
+impl<G: Game> CardStats<G> for CardSnapshot<G> {
+    fn card(&self) -> SpellCard<G> {
+        self.card
+    }
+
+    fn shot_type(&self) -> ShotType<G> {
+        self.shot_type
+    }
+
+    fn captures(&self) -> u32 {
+        self.captures
+    }
+
+    fn attempts(&self) -> u32 {
+        self.attempts
+    }
+}
+
 pub async fn display_card_stats<G: Game>(
-    pool: &SqlitePool,
+    pool: &Pool,
     snapshot: &CardSnapshot<G>,
     attempt_info: Option<&CardAttemptInfo>,
 ) -> anyhow::Result<()> {
-    let title = format!("#{:03} {}", snapshot.card_id(), snapshot.card_name());
     let update_status = (
         attempt_info.map(|a| a.is_capture()).unwrap_or(false),
         attempt_info.is_some(),
@@ -35,15 +52,6 @@ pub async fn display_card_stats<G: Game>(
         _ => "",
     };
 
-    print!(
-        "{:^85} [{:<8}]: {:>4} / {:<4} ({:^5.1}%",
-        title,
-        snapshot.shot_type.to_string(),
-        snapshot.captures,
-        snapshot.attempts,
-        ((snapshot.captures as f64) / (snapshot.attempts as f64)) * 100.0
-    );
-
     let recent_cutoff = snapshot.timestamp.saturating_sub(time::Duration::hours(6));
     let prev_snap: Option<CardSnapshot<G>> = CardSnapshot::get_first_snapshot_after(
         pool,
@@ -53,33 +61,19 @@ pub async fn display_card_stats<G: Game>(
     )
     .await?;
 
-    if let Some(prev_snap) = prev_snap {
-        let d_attempts = snapshot.attempts.saturating_sub(prev_snap.attempts);
-        let d_captures = snapshot
-            .captures
-            .saturating_sub(prev_snap.captures)
-            .min(d_attempts);
-
-        if d_attempts > 0 {
-            print!(
-                ", recent {} / {} = {:^5.1}%",
-                d_captures,
-                d_attempts,
-                ((d_captures as f64) / (d_attempts as f64)) * 100.0
-            );
-        }
-    }
-
-    println!("){}", capture_status);
+    println!(
+        "{}{}",
+        CardStatsFormatter::new().format(snapshot, prev_snap.as_ref()),
+        capture_status
+    );
 
     Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
-    let pool =
-        SqlitePool::connect(&env::var("DATABASE_URL").unwrap_or(String::from("sqlite:touhou.db")))
-            .await?;
+    let pool = Pool::connect(&env::var("DATABASE_URL").unwrap_or(String::from("sqlite:touhou.db")))
+        .await?;
 
     let (exit_tx, mut exit_rx) = oneshot::channel();
     let mut ctrl_c_handle = tokio::spawn(async move {
@@ -104,7 +98,7 @@ async fn main() -> Result<(), anyhow::Error> {
         }
     };
 
-    let prev_snapshot = snap_stream.read_snapshot_data().await?;
+    let prev_snapshot = snap_stream.full_snapshot().await?;
     for card_snapshot in prev_snapshot.iter_cards() {
         display_card_stats(&pool, card_snapshot, None).await?;
     }
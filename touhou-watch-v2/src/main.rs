@@ -1,9 +1,12 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Result as IOResult};
+use std::path::{Path, PathBuf};
 
 use set_track::Metrics;
-use tauri::Window;
+use tauri::{AppHandle, Manager, RunEvent, Window};
 
 mod event_serialize;
 // mod persist;
@@ -76,6 +79,15 @@ fn get_locations(game_id: GameId) -> Result<&'static [LocationInfo], &'static st
     }
 }
 
+#[tauri::command]
+fn get_supported_games() -> Vec<GameId> {
+    GameId::all()
+        .iter()
+        .copied()
+        .filter(|&game_id| game_id.supports_memory() && get_locations(game_id).is_ok())
+        .collect()
+}
+
 #[tauri::command]
 fn start_tracking(
     game_id: GameId,
@@ -100,16 +112,56 @@ fn end_tracking() {
     lock.th10_mut().end_tracking();
 }
 
+/// Gets the path that tracked practice data is saved to between sessions.
+fn metrics_path(app: &AppHandle) -> Option<PathBuf> {
+    app.path_resolver()
+        .app_data_dir()
+        .map(|dir| dir.join("metrics.json"))
+}
+
+fn load_metrics(path: &Path) -> IOResult<()> {
+    let src = File::open(path).map(BufReader::new)?;
+    Metrics::get().lock().load_from(src)
+}
+
+fn save_metrics(path: &Path) -> IOResult<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    let dest = File::create(path).map(BufWriter::new)?;
+    Metrics::get().lock().save_to(dest)
+}
+
 fn main() {
     tauri::Builder::default()
+        .setup(|app| {
+            if let Some(path) = metrics_path(&app.app_handle()).filter(|path| path.is_file()) {
+                if let Err(e) = load_metrics(&path) {
+                    eprintln!("could not load saved practice data: {}", e);
+                }
+            }
+
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             start_watcher,
             load_spellcard_data,
             get_practice_data,
             start_tracking,
             end_tracking,
-            get_locations
+            get_locations,
+            get_supported_games
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                if let Some(path) = metrics_path(app) {
+                    if let Err(e) = save_metrics(&path) {
+                        eprintln!("could not save practice data: {}", e);
+                    }
+                }
+            }
+        });
 }
@@ -1,7 +1,10 @@
 use core::panic;
 use std::collections::{HashMap, HashSet};
+use std::io::{Error as IOError, ErrorKind, Read, Result as IOResult, Write};
 use std::sync::{LockResult, Mutex, MutexGuard, OnceLock};
 
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use touhou::memory::GameLocation;
 use touhou::th07::Location as Th07Location;
 use touhou::th08::Location as Th08Location;
@@ -11,8 +14,25 @@ use touhou::{
     AllIterable, GameValue, HasLocations, Location, SpellCard, Touhou10, Touhou7, Touhou8,
 };
 
+use super::tracking::SavedSets;
 use super::SetTracker;
 
+/// The current version of the saved metrics format.
+///
+/// Bump this whenever the serialized form of [`SavedMetrics`] (including the set and event types within it) changes,
+/// and add a corresponding upgrade step to [`migrate_saved`].
+const SAVE_FORMAT_VERSION: u64 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedMetrics {
+    /// Data saved before versioning was introduced has no version tag, and is treated as version 0.
+    #[serde(default)]
+    version: u64,
+    th07: SavedSets<Touhou7>,
+    th08: SavedSets<Touhou8>,
+    th10: SavedSets<Touhou10>,
+}
+
 #[derive(Debug, Default)]
 pub struct Metrics {
     th07: SetTracker<Touhou7>,
@@ -54,6 +74,70 @@ impl Metrics {
     pub fn th10_mut(&mut self) -> &mut SetTracker<Touhou10> {
         &mut self.th10
     }
+
+    /// Writes the recorded attempts for every game to `dest` as JSON.
+    pub fn save_to<W: Write>(&self, dest: W) -> IOResult<()> {
+        let saved = SavedMetrics {
+            version: SAVE_FORMAT_VERSION,
+            th07: self.th07.to_saved(),
+            th08: self.th08.to_saved(),
+            th10: self.th10.to_saved(),
+        };
+
+        serde_json::to_writer(dest, &saved).map_err(|e| IOError::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Replaces the recorded attempts for every game with ones previously written by [`Self::save_to`].
+    ///
+    /// Any active tracking ranges are left as-is.
+    pub fn load_from<R: Read>(&mut self, src: R) -> IOResult<()> {
+        let data: Value =
+            serde_json::from_reader(src).map_err(|e| IOError::new(ErrorKind::InvalidData, e))?;
+        let saved: SavedMetrics = serde_json::from_value(migrate_saved(data)?)
+            .map_err(|e| IOError::new(ErrorKind::InvalidData, e))?;
+
+        self.th07.restore_saved(saved.th07);
+        self.th08.restore_saved(saved.th08);
+        self.th10.restore_saved(saved.th10);
+        Ok(())
+    }
+}
+
+/// Upgrades saved metrics data written by an older version of this program to the current format.
+fn migrate_saved(mut data: Value) -> IOResult<Value> {
+    let Some(fields) = data.as_object_mut() else {
+        return Err(IOError::new(
+            ErrorKind::InvalidData,
+            "saved metrics data is not an object",
+        ));
+    };
+
+    let mut version = fields.get("version").and_then(Value::as_u64).unwrap_or(0);
+    if version > SAVE_FORMAT_VERSION {
+        return Err(IOError::new(
+            ErrorKind::InvalidData,
+            format!(
+                "saved metrics use format version {} (expected {} or older)",
+                version, SAVE_FORMAT_VERSION
+            ),
+        ));
+    }
+
+    while version < SAVE_FORMAT_VERSION {
+        match version {
+            // unversioned data has the same layout as version 1
+            0 => {}
+            _ => unreachable!(
+                "no migration defined from saved metrics version {}",
+                version
+            ),
+        }
+
+        version += 1;
+    }
+
+    fields.insert("version".into(), version.into());
+    Ok(data)
 }
 
 #[derive(Debug, Clone)]
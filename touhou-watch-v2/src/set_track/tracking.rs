@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
+use serde::{Deserialize, Serialize};
 use time::Duration;
 use touhou::memory::{Location, PauseState, PlayerData, RunData, SpellState, StageData};
 use touhou::{Difficulty, ShotType};
@@ -224,6 +225,15 @@ impl<G: TrackedGame> Drop for ActiveGame<G> {
     }
 }
 
+/// The persisted form of a [`SetTracker`].
+///
+/// Set keys aren't strings, so the attempt map is stored as a list of pairs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "G: TrackedGame")]
+pub(super) struct SavedSets<G: TrackedGame> {
+    attempts: Vec<(SetKey<G>, Vec<Attempt>)>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SetTracker<G: TrackedGame> {
     attempts: HashMap<SetKey<G>, Vec<Attempt>>,
@@ -272,4 +282,18 @@ impl<G: TrackedGame> SetTracker<G> {
             self.attempts.entry(key).or_default().push(attempt);
         }
     }
+
+    pub(super) fn to_saved(&self) -> SavedSets<G> {
+        SavedSets {
+            attempts: self
+                .attempts
+                .iter()
+                .map(|(key, attempts)| (*key, attempts.clone()))
+                .collect(),
+        }
+    }
+
+    pub(super) fn restore_saved(&mut self, saved: SavedSets<G>) {
+        self.attempts = saved.attempts.into_iter().collect();
+    }
 }
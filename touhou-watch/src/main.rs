@@ -6,9 +6,10 @@ use std::time::Duration;
 
 use sysinfo::{ProcessRefreshKind, System, SystemExt};
 use tauri::Window;
-use touhou::th07::memory::{GameMemory, GameState};
+use touhou::memory::{retry_read, ProcessStatus};
+use touhou::th07::memory::{GameMemory, GameState, ReadResult};
 use touhou::th07::SpellId;
-use touhou::types::SpellCardInfo;
+use touhou::types::{Game, SpellCardInfo};
 use touhou::Touhou7;
 
 mod run;
@@ -23,7 +24,93 @@ enum WatcherState {
     InGame(Box<GameMemory>, Box<ActiveRun>),
 }
 
-fn update_watcher_state(state: WatcherState, window: &Window, system: &mut System) -> WatcherState {
+/// Timing settings for the watcher loop.
+#[derive(Debug, Clone, Copy)]
+struct WatcherConfig {
+    /// How often to poll while detached or waiting for a game to start.
+    detached_poll_interval: Duration,
+    /// How often to poll the game state while a run is in progress.
+    in_game_poll_interval: Duration,
+    /// How long to wait after a game starts before reading the initial run state.
+    first_read_delay: Duration,
+    /// How many times to attempt each game state read before reporting an error.
+    read_attempts: u32,
+}
+
+impl WatcherConfig {
+    /// Loads settings from the environment, using the defaults for any that aren't set.
+    ///
+    /// Each interval is given as a number of milliseconds, via the `THWATCH_DETACHED_POLL_MS`,
+    /// `THWATCH_IN_GAME_POLL_MS`, and `THWATCH_FIRST_READ_DELAY_MS` variables.
+    /// The number of read attempts is given by `THWATCH_READ_ATTEMPTS`.
+    fn from_env() -> Self {
+        fn read_millis(key: &str, default: Duration) -> Duration {
+            env::var(key)
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(default)
+        }
+
+        let default = Self::default();
+        Self {
+            detached_poll_interval: read_millis(
+                "THWATCH_DETACHED_POLL_MS",
+                default.detached_poll_interval,
+            ),
+            in_game_poll_interval: read_millis(
+                "THWATCH_IN_GAME_POLL_MS",
+                default.in_game_poll_interval,
+            ),
+            first_read_delay: read_millis("THWATCH_FIRST_READ_DELAY_MS", default.first_read_delay),
+            read_attempts: env::var("THWATCH_READ_ATTEMPTS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(default.read_attempts),
+        }
+    }
+}
+
+impl Default for WatcherConfig {
+    fn default() -> Self {
+        Self {
+            detached_poll_interval: Duration::from_millis(100),
+            in_game_poll_interval: Duration::from_millis(50),
+            first_read_delay: Duration::from_millis(1000),
+            read_attempts: 3,
+        }
+    }
+}
+
+/// Checks that the attached game process is still alive, emitting an event if it isn't.
+///
+/// If the game was closed and relaunched under the same PID, this emits `game-restarted` instead of `game-detached`,
+/// so that the frontend can treat the old run as abandoned rather than silently continuing it.
+fn check_attached(proc: &mut GameMemory, window: &Window) -> bool {
+    match proc.status() {
+        ProcessStatus::Running => true,
+        ProcessStatus::Exited => {
+            window.emit("game-detached", ()).unwrap();
+            false
+        }
+        ProcessStatus::Replaced => {
+            window.emit("game-restarted", proc.pid()).unwrap();
+            false
+        }
+    }
+}
+
+/// Reads the current game state, retrying transient read failures such as those during scene transitions.
+fn read_game_state(proc: &GameMemory, config: &WatcherConfig) -> ReadResult<GameState> {
+    retry_read(config.read_attempts, || GameState::new(proc))
+}
+
+fn update_watcher_state(
+    state: WatcherState,
+    config: &WatcherConfig,
+    window: &Window,
+    system: &mut System,
+) -> WatcherState {
     match state {
         WatcherState::Detached => loop {
             system.refresh_processes_specifics(ProcessRefreshKind::new());
@@ -36,32 +123,30 @@ fn update_watcher_state(state: WatcherState, window: &Window, system: &mut Syste
                 return WatcherState::WaitingForGame(Box::new(proc));
             }
 
-            sleep(Duration::from_millis(100));
+            sleep(config.detached_poll_interval);
         },
-        WatcherState::WaitingForGame(proc) => loop {
-            if !proc.is_running(system) {
-                window.emit("game-detached", ()).unwrap();
+        WatcherState::WaitingForGame(mut proc) => loop {
+            if !check_attached(&mut proc, window) {
                 return WatcherState::Detached;
             }
 
-            match GameState::new(&proc) {
+            match read_game_state(&proc, config) {
                 Err(e) => window.emit("error", e.to_string()).unwrap(),
                 Ok(GameState::InGame { .. }) => {
-                    std::thread::sleep(Duration::from_millis(1000));
+                    sleep(config.first_read_delay);
                     return WatcherState::WaitingForFirstRead(proc);
                 }
                 _ => {}
             }
 
-            std::thread::sleep(Duration::from_millis(100))
+            sleep(config.detached_poll_interval)
         },
-        WatcherState::WaitingForFirstRead(proc) => loop {
-            if !proc.is_running(system) {
-                window.emit("game-detached", ()).unwrap();
+        WatcherState::WaitingForFirstRead(mut proc) => loop {
+            if !check_attached(&mut proc, window) {
                 return WatcherState::Detached;
             }
 
-            match GameState::new(&proc) {
+            match read_game_state(&proc, config) {
                 Err(e) => window.emit("error", e.to_string()).unwrap(),
                 Ok(GameState::InGame {
                     practice,
@@ -79,18 +164,17 @@ fn update_watcher_state(state: WatcherState, window: &Window, system: &mut Syste
                 _ => return WatcherState::WaitingForGame(proc),
             }
 
-            std::thread::sleep(Duration::from_millis(50));
+            sleep(config.in_game_poll_interval);
         },
-        WatcherState::InGame(proc, active) => {
+        WatcherState::InGame(mut proc, active) => {
             let mut active = { *active };
 
             loop {
-                if !proc.is_running(system) {
-                    window.emit("game-detached", ()).unwrap();
+                if !check_attached(&mut proc, window) {
                     return WatcherState::Detached;
                 }
 
-                match GameState::new(&proc) {
+                match read_game_state(&proc, config) {
                     Err(e) => window.emit("error", e.to_string()).unwrap(),
                     Ok(s) => {
                         let result = active.update(s);
@@ -109,30 +193,28 @@ fn update_watcher_state(state: WatcherState, window: &Window, system: &mut Syste
                     }
                 }
 
-                std::thread::sleep(Duration::from_millis(50));
+                sleep(config.in_game_poll_interval);
             }
         }
     }
 }
 
 fn watcher(window: Window) {
+    let config = WatcherConfig::from_env();
     let mut system = System::new();
     let mut cur_state = WatcherState::Detached;
 
     window.emit("game-detached", ()).unwrap();
 
     loop {
-        cur_state = update_watcher_state(cur_state, &window, &mut system);
+        cur_state = update_watcher_state(cur_state, &config, &window, &mut system);
     }
 }
 
 #[tauri::command]
 fn load_spellcard_data() -> Vec<SpellCardInfo<Touhou7>> {
-    (1..=141)
-        .map(|i| {
-            let id: SpellId = i.try_into().unwrap();
-            id.card_info()
-        })
+    Touhou7::spell_id_range()
+        .filter_map(SpellId::try_card_info)
         .copied()
         .collect()
 }
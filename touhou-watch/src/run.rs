@@ -1,3 +1,5 @@
+#[cfg(test)]
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::fmt::Display;
 use std::ops::{Deref, DerefMut};
@@ -14,11 +16,38 @@ use touhou::types::{ShotType, SpellCard};
 #[repr(transparent)]
 pub struct EventTime(OffsetDateTime);
 
+#[cfg(test)]
+thread_local! {
+    static FIXED_TIME: Cell<Option<OffsetDateTime>> = Cell::new(None);
+}
+
 impl EventTime {
+    /// Gets the current time.
     pub fn new() -> Self {
+        #[cfg(test)]
+        if let Some(time) = FIXED_TIME.with(Cell::get) {
+            return Self(time);
+        }
+
         Self(OffsetDateTime::now_local().unwrap_or_else(|_| OffsetDateTime::now_utc()))
     }
 
+    /// Overrides the time returned by [`EventTime::new`] on the current thread.
+    ///
+    /// Passing `None` restores the wall clock.
+    #[cfg(test)]
+    fn set_fixed_time(time: Option<OffsetDateTime>) {
+        FIXED_TIME.with(|cell| cell.set(time));
+    }
+
+    /// Advances the fixed time set by [`EventTime::set_fixed_time`] on the current thread.
+    ///
+    /// This does nothing if no fixed time is set.
+    #[cfg(test)]
+    fn advance_fixed_time(by: Duration) {
+        FIXED_TIME.with(|cell| cell.set(cell.get().map(|time| time + by)));
+    }
+
     pub fn now_local() -> Result<Self, IndeterminateOffset> {
         OffsetDateTime::now_local().map(Self)
     }
@@ -121,6 +150,8 @@ pub enum GameEvent {
         bombs: u8,
         power: u8,
         continues: u8,
+        #[serde(default)]
+        graze: u32,
     },
     Miss {
         time: EventTime,
@@ -157,6 +188,12 @@ pub enum GameEvent {
         cleared: bool,
         retrying: bool,
     },
+    /// The game reported a state that isn't recognized yet.
+    UnknownState {
+        time: EventTime,
+        state_id: u32,
+        mode: u8,
+    },
 }
 
 impl GameEvent {
@@ -173,6 +210,7 @@ impl GameEvent {
             Self::BorderEnd { .. } => 8,
             Self::StageCleared { .. } => 9,
             Self::EndGame { .. } => 10,
+            Self::UnknownState { .. } => 11,
         };
 
         EventKey(self.time(), type_key)
@@ -190,7 +228,8 @@ impl GameEvent {
             | Self::BorderStart { time, .. }
             | Self::BorderEnd { time, .. }
             | Self::Pause { time, .. }
-            | Self::Unpause { time, .. } => *time,
+            | Self::Unpause { time, .. }
+            | Self::UnknownState { time, .. } => *time,
         }
     }
 }
@@ -298,6 +337,9 @@ impl Display for GameEvent {
             }
             Self::Pause { .. } => f.write_str("Paused game"),
             Self::Unpause { .. } => f.write_str("Unpaused game"),
+            Self::UnknownState { state_id, mode, .. } => {
+                write!(f, "Observed unknown game state {}/{}", state_id, mode)
+            }
         }
     }
 }
@@ -314,8 +356,11 @@ pub struct Run {
     bombs: Vec<(EventTime, StageLocation)>,
     breaks: Vec<(EventTime, StageLocation)>,
     locations_seen: HashSet<StageLocation>,
-    score: u32,
+    score: u64,
     continues: u8,
+    /// The player's lives, bombs, and power over the course of the run, recorded whenever any of them change.
+    #[serde(default)]
+    resource_timeline: Vec<(EventTime, u8, u8, u8)>,
     #[serde(deserialize_with = "Run::deserialize_sorted_events")]
     events: Vec<GameEvent>,
 }
@@ -329,6 +374,67 @@ impl Run {
         self.events.insert(index, event);
     }
 
+    fn record_resources(&mut self, time: EventTime, player: &PlayerState) {
+        let entry = (time, player.lives(), player.bombs(), player.power());
+        let changed = self
+            .resource_timeline
+            .last()
+            .map_or(true, |&(_, lives, bombs, power)| {
+                (lives, bombs, power) != (entry.1, entry.2, entry.3)
+            });
+
+        if changed {
+            self.resource_timeline.push(entry);
+        }
+    }
+
+    /// Computes how long the player spent in each section of this run, in the order the sections were entered.
+    ///
+    /// Sections are delimited by the start of the run, [`GameEvent::EnterSection`] events, and the final
+    /// [`GameEvent::EndGame`] event. Time spent paused is not counted towards any section.
+    pub fn location_durations(&self) -> Vec<(StageLocation, Duration)> {
+        fn elapsed(start: EventTime, end: EventTime) -> Duration {
+            Duration::try_from(*end - *start).unwrap_or_default()
+        }
+
+        let mut ret = Vec::new();
+        let mut current: Option<(StageLocation, EventTime, Duration)> = None;
+        let mut paused_at: Option<EventTime> = None;
+
+        for event in &self.events {
+            let time = event.time();
+            let next_location = match event {
+                GameEvent::StartGame { location, .. }
+                | GameEvent::EnterSection { location, .. } => Some(*location),
+                GameEvent::EndGame { .. } => None,
+                GameEvent::Pause { .. } => {
+                    paused_at.get_or_insert(time);
+                    continue;
+                }
+                GameEvent::Unpause { .. } => {
+                    if let (Some(start), Some((_, _, paused))) = (paused_at.take(), &mut current) {
+                        *paused += elapsed(start, time);
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            if let Some((location, start, mut paused)) = current.take() {
+                if let Some(pause_start) = paused_at.as_mut() {
+                    paused += elapsed(*pause_start, time);
+                    *pause_start = time;
+                }
+
+                ret.push((location, elapsed(start, time).saturating_sub(paused)));
+            }
+
+            current = next_location.map(|location| (location, time, Duration::ZERO));
+        }
+
+        ret
+    }
+
     fn deserialize_sorted_events<'de, D>(deserializer: D) -> Result<Vec<GameEvent>, D::Error>
     where
         D: Deserializer<'de>,
@@ -365,15 +471,58 @@ impl UpdateResult {
     }
 }
 
+/// Controls how supernatural borders ending during a run are classified as broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BorderConfig {
+    /// Borders that end within this long after being activated are counted as broken.
+    pub break_threshold: Duration,
+    /// Whether to ignore borders that end right before a boss fight.
+    pub ignore_pre_boss: bool,
+    /// Whether to ignore borders that end after the final spell of a stage has finished.
+    pub ignore_after_boss: bool,
+}
+
+impl BorderConfig {
+    /// The default threshold for border breaks, which is slightly less than the full duration of a border.
+    pub const DEFAULT_BREAK_THRESHOLD: Duration = Duration::from_millis(8925);
+
+    /// Returns whether a border that lasted for the given duration counts as broken under this configuration.
+    ///
+    /// `pre_boss` is set if the border ended in the section right before a boss fight, and `boss_finished`
+    /// is set if it ended after the final spell of the stage.
+    fn is_broken(&self, duration: Duration, pre_boss: bool, boss_finished: bool) -> bool {
+        (duration <= self.break_threshold)
+            && !(self.ignore_pre_boss && pre_boss)
+            && !(self.ignore_after_boss && boss_finished)
+    }
+
+    /// Gets how long a border that started at `start` and ended at `end` lasted.
+    fn border_duration(start: EventTime, end: EventTime) -> Option<Duration> {
+        (end.0 - start.0).max(time::Duration::ZERO).try_into().ok()
+    }
+}
+
+impl Default for BorderConfig {
+    fn default() -> Self {
+        Self {
+            break_threshold: Self::DEFAULT_BREAK_THRESHOLD,
+            ignore_pre_boss: true,
+            ignore_after_boss: true,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ActiveRun {
     run: Run,
     update_time: EventTime,
     last_border_start: Option<EventTime>,
+    border_config: BorderConfig,
     paused: bool,
     player_state: PlayerState,
     stage_state: StageState,
     prev_update_events: usize,
+    last_unknown_state: Option<(u32, u8)>,
 }
 
 impl ActiveRun {
@@ -411,8 +560,9 @@ impl ActiveRun {
             misses: Vec::new(),
             bombs: Vec::new(),
             breaks: Vec::new(),
-            score: player.score(),
+            score: player.score().into(),
             continues: player.continues(),
+            resource_timeline: vec![(start_time, player.lives(), player.bombs(), player.power())],
             events: vec![init_event],
         };
 
@@ -420,10 +570,12 @@ impl ActiveRun {
             run,
             update_time: EventTime::new(),
             last_border_start: None,
+            border_config: BorderConfig::default(),
             paused: false,
             player_state: player,
             stage_state: stage,
             prev_update_events: 0,
+            last_unknown_state: None,
         };
 
         if player.border_active() {
@@ -437,6 +589,12 @@ impl ActiveRun {
         Some(ret)
     }
 
+    /// Sets how borders that end during this run are classified as broken.
+    pub fn with_border_config(mut self, border_config: BorderConfig) -> Self {
+        self.border_config = border_config;
+        self
+    }
+
     pub fn current_location(&self) -> StageLocation {
         self.run.location
     }
@@ -481,17 +639,17 @@ impl ActiveRun {
                     location: self.current_location(),
                 });
             }
-        } else if let Some(duration) = self.last_border_start.take().and_then(|start| {
-            (self.update_time.0 - start.0)
-                .max(time::Duration::ZERO)
-                .try_into()
-                .ok()
-        }) {
-            // don't treat border as broken if it happens at end of stage or before a boss fight
-            let duration: Duration = duration;
-            let broken = (duration <= Duration::from_millis(8925))
-                && (self.current_location().section() != StageSection::PreBoss)
-                && !self.boss_finished();
+        } else if let Some(duration) = self
+            .last_border_start
+            .take()
+            .and_then(|start| BorderConfig::border_duration(start, self.update_time))
+        {
+            // by default, don't treat border as broken if it happens at end of stage or before a boss fight
+            let broken = self.border_config.is_broken(
+                duration,
+                self.current_location().section() == StageSection::PreBoss,
+                self.boss_finished(),
+            );
 
             if broken {
                 self.run
@@ -529,6 +687,8 @@ impl ActiveRun {
         let prev_player_state = std::mem::replace(&mut self.player_state, player_state);
         let prev_stage_state = std::mem::replace(&mut self.stage_state, stage_state);
 
+        self.run.record_resources(self.update_time, &player_state);
+
         if let Some(location) = stage_state.location() {
             if location != self.run.location {
                 self.run.locations_seen.insert(location);
@@ -539,6 +699,7 @@ impl ActiveRun {
                     bombs: player_state.bombs(),
                     power: player_state.power(),
                     continues: player_state.continues(),
+                    graze: player_state.graze(),
                 })
             }
 
@@ -575,13 +736,13 @@ impl ActiveRun {
             .boss_state()
             .as_ref()
             .and_then(|x| x.active_spell())
-            .map(|x| (SpellCard::new((x.0 + 1).try_into().unwrap()), x.1));
+            .and_then(|x| SpellCard::from_raw_game_id(x.0).ok().map(|card| (card, x.1)));
 
         let prev_boss_spell = prev_stage_state
             .boss_state()
             .as_ref()
             .and_then(|x| x.active_spell())
-            .map(|x| (SpellCard::new((x.0 + 1).try_into().unwrap()), x.1));
+            .and_then(|x| SpellCard::from_raw_game_id(x.0).ok().map(|card| (card, x.1)));
 
         match (prev_boss_spell, cur_boss_spell) {
             (Some(prev), Some(cur)) => {
@@ -631,6 +792,13 @@ impl ActiveRun {
     }
 
     pub fn update(mut self, state: GameState) -> UpdateResult {
+        let unknown_state = if let GameState::Unknown { state_id, mode } = state {
+            Some((state_id, mode))
+        } else {
+            None
+        };
+        let prev_unknown_state = std::mem::replace(&mut self.last_unknown_state, unknown_state);
+
         match state {
             GameState::InGame {
                 paused,
@@ -657,7 +825,18 @@ impl ActiveRun {
                 UpdateResult::Finished(run, prev_update_events)
             }
             GameState::Unknown { state_id, mode } => {
-                eprintln!("observed unknown state {}/{}", state_id, mode);
+                self.update_time = EventTime::new();
+                self.prev_update_events = self.events().len();
+
+                /* Only log unknown states when they first show up, rather than on every poll. */
+                if prev_unknown_state != unknown_state {
+                    self.push_event(GameEvent::UnknownState {
+                        time: self.update_time,
+                        state_id,
+                        mode,
+                    });
+                }
+
                 UpdateResult::Continuing(self)
             }
             _ => {
@@ -675,3 +854,122 @@ impl ActiveRun {
         &self.run
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start_time() -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap()
+    }
+
+    #[test]
+    fn fixed_time_overrides_clock() {
+        EventTime::set_fixed_time(Some(start_time()));
+        let first = EventTime::new();
+        EventTime::advance_fixed_time(Duration::from_millis(1500));
+        let second = EventTime::new();
+        EventTime::set_fixed_time(None);
+
+        assert_eq!(*first, start_time());
+        assert_eq!(*second - *first, time::Duration::milliseconds(1500));
+        assert_eq!(serde_json::to_string(&second).unwrap(), "1700000001500");
+    }
+
+    #[test]
+    fn event_times_round_trip() {
+        EventTime::set_fixed_time(Some(start_time()));
+        EventTime::advance_fixed_time(Duration::from_millis(250));
+        let event = GameEvent::Pause {
+            time: EventTime::new(),
+        };
+        EventTime::set_fixed_time(None);
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(json, r#"{"event":"pause","time":1700000000250}"#);
+
+        let parsed: GameEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.key(), event.key());
+    }
+
+    #[test]
+    fn simultaneous_events_sort_by_kind() {
+        EventTime::set_fixed_time(Some(start_time()));
+        let unpause = GameEvent::Unpause {
+            time: EventTime::new(),
+        };
+        let pause = GameEvent::Pause {
+            time: EventTime::new(),
+        };
+        EventTime::advance_fixed_time(Duration::from_millis(50));
+        let later_pause = GameEvent::Pause {
+            time: EventTime::new(),
+        };
+        EventTime::set_fixed_time(None);
+
+        assert!(pause.key() < unpause.key());
+        assert!(unpause.key() < later_pause.key());
+    }
+
+    /* Times a border the same way `ActiveRun::update_border` does, using the fixed clock. */
+    fn timed_border(length: Duration) -> Duration {
+        EventTime::set_fixed_time(Some(start_time()));
+        let start = EventTime::new();
+        EventTime::advance_fixed_time(length);
+        let end = EventTime::new();
+        EventTime::set_fixed_time(None);
+
+        BorderConfig::border_duration(start, end).unwrap()
+    }
+
+    #[test]
+    fn short_border_is_broken() {
+        let config = BorderConfig::default();
+        let duration = timed_border(Duration::from_millis(4000));
+
+        assert_eq!(duration, Duration::from_millis(4000));
+        assert!(config.is_broken(duration, false, false));
+
+        let duration = timed_border(BorderConfig::DEFAULT_BREAK_THRESHOLD);
+        assert!(config.is_broken(duration, false, false));
+    }
+
+    #[test]
+    fn full_length_border_is_not_broken() {
+        let config = BorderConfig::default();
+        let length = BorderConfig::DEFAULT_BREAK_THRESHOLD + Duration::from_millis(50);
+        let duration = timed_border(length);
+        assert!(!config.is_broken(duration, false, false));
+    }
+
+    #[test]
+    fn border_end_before_time_start_is_zero_length() {
+        EventTime::set_fixed_time(Some(start_time()));
+        let end = EventTime::new();
+        EventTime::advance_fixed_time(Duration::from_millis(100));
+        let start = EventTime::new();
+        EventTime::set_fixed_time(None);
+
+        assert_eq!(
+            BorderConfig::border_duration(start, end),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn pre_boss_and_finished_boss_borders_are_excluded() {
+        let duration = timed_border(Duration::from_millis(4000));
+
+        let config = BorderConfig::default();
+        assert!(!config.is_broken(duration, true, false));
+        assert!(!config.is_broken(duration, false, true));
+
+        let config = BorderConfig {
+            ignore_pre_boss: false,
+            ignore_after_boss: false,
+            ..BorderConfig::default()
+        };
+        assert!(config.is_broken(duration, true, false));
+        assert!(config.is_broken(duration, false, true));
+    }
+}
@@ -42,6 +42,7 @@ export class GameEvent {
             case "border_end": return new BorderEndEvent(src);
             case "pause": return new PauseEvent(src);
             case "unpause": return new UnpauseEvent(src);
+            case "unknown_state": return new UnknownStateEvent(src);
             default: throw new TypeError("Invalid event type " + src.event);
             }
         } finally {
@@ -320,6 +321,9 @@ export class EnterSectionEvent extends GameEvent {
     /** @type {number} */
     #continues;
 
+    /** @type {number} */
+    #graze;
+
     constructor (src) {
         if (!DESERIALIZE_INTERNAL_FLAG) {
             throw new TypeError("EnterSectionEvent instances cannot be constructed directly");
@@ -344,6 +348,15 @@ export class EnterSectionEvent extends GameEvent {
         if ((typeof src.continues !== "number") || !Number.isInteger(src.continues)) throw new TypeError("continues is not an integer (got " + src.continues + ")");
         if (src.continues < 0) throw new TypeError("invalid continue count (got " + src.continues + ", expected non-negative integer)");
         this.#continues = src.continues;
+
+        // older saved runs don't include graze counts
+        if (src.graze === undefined) {
+            this.#graze = 0;
+        } else if ((typeof src.graze !== "number") || !Number.isInteger(src.graze) || src.graze < 0) {
+            throw new TypeError("invalid graze count (got " + src.graze + ", expected non-negative integer)");
+        } else {
+            this.#graze = src.graze;
+        }
     }
 
     /** @returns {StageLocation} */
@@ -371,6 +384,11 @@ export class EnterSectionEvent extends GameEvent {
         return this.#continues;
     }
 
+    /** @returns {number} */
+    get graze() {
+        return this.#graze;
+    }
+
     /** @returns {string} */
     toString() {
         var ret = "Entering " + this.#location + " with ";
@@ -553,4 +571,37 @@ export class UnpauseEvent extends GameEvent {
     toString() {
         return "Game unpaused";
     }
-}
\ No newline at end of file
+}
+
+export class UnknownStateEvent extends GameEvent {
+    /** @type {number} */
+    #state_id;
+
+    /** @type {number} */
+    #mode;
+
+    constructor (src) {
+        if (!DESERIALIZE_INTERNAL_FLAG) {
+            throw new TypeError("UnknownStateEvent instances cannot be constructed directly");
+        }
+        
+        super(src.time);
+        this.#state_id = src.state_id;
+        this.#mode = src.mode;
+    }
+
+    /** @returns {number} */
+    get state_id() {
+        return this.#state_id;
+    }
+
+    /** @returns {number} */
+    get mode() {
+        return this.#mode;
+    }
+
+    /** @returns {string} */
+    toString() {
+        return "Observed unknown game state " + this.#state_id + "/" + this.#mode;
+    }
+}
@@ -58,6 +58,13 @@ export class Main {
             this.#metricsDisplay.gameRunning = false;
         });
 
+        this.#registerEventHandler("game-restarted", (ev) => {
+            this.#logDisplay.logMessage("Game process " + ev.payload + " was restarted, waiting for PCB...");
+            this.#gameList.forceEndCurrentGame();
+            Main.#gameAttached = false;
+            this.#metricsDisplay.gameRunning = false;
+        });
+
         this.#registerEventHandler("run-update", (ev) => {
             let finished = ev.payload[0];
             let run = Game.deserialize(ev.payload[1]);
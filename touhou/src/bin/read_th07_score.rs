@@ -8,7 +8,7 @@ use touhou::th07::ScoreFile;
 fn main() -> Result<(), std::io::Error> {
     let file_path = env::args().nth(1).map(PathBuf::from).unwrap();
 
-    let score_file = File::open(file_path).and_then(ScoreFile::new)?;
+    let score_file = File::open(&file_path).and_then(ScoreFile::new)?;
     for data in score_file.spell_cards() {
         let card = data.card();
         for shot in data.shot_types() {
@@ -36,5 +36,10 @@ fn main() -> Result<(), std::io::Error> {
         );
     }
 
+    let full_file = File::open(&file_path).and_then(ScoreFile::read_full)?;
+    if let Some(play_time) = full_file.total_play_time() {
+        println!("Total play time: {}", play_time);
+    }
+
     Ok(())
 }
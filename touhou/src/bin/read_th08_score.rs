@@ -3,7 +3,8 @@ use std::fs::File;
 use std::path::PathBuf;
 
 use touhou::score::{PracticeRecord, ScoreFile as ScoreFileTrait, SpellCardRecord};
-use touhou::th08::ScoreFile;
+use touhou::th08::{ScoreFile, ShotType};
+use touhou::types::{AllIterable, StageProgress};
 
 fn main() -> Result<(), std::io::Error> {
     let file_path = env::args().nth(1).map(PathBuf::from).unwrap();
@@ -36,5 +37,12 @@ fn main() -> Result<(), std::io::Error> {
         );
     }
 
+    for shot in ShotType::iter_all() {
+        let progress = score_file.furthest_progress(shot);
+        if progress != StageProgress::NotStarted {
+            println!("{} - furthest progress: {}", shot, progress);
+        }
+    }
+
     Ok(())
 }
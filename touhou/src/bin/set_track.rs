@@ -1,14 +1,13 @@
 use std::fmt::Display;
 use std::thread;
-use std::thread::sleep;
 use std::time::{Duration, SystemTime};
 
 use touhou::memory::{GameMemory, MemoryReadError};
 use touhou::th07::{GameMemory as Th07Memory, Touhou7Event};
 use touhou::th10::GameMemory as Th10Memory;
 use touhou::tracking::{
-    Event, EventTime, GameTracker, IntoGameTracker, TrackGame, TrackRun, TrackSpellPractice,
-    TrackStagePractice, TrackableGame, UpdateTracker,
+    Event, EventTime, GameWatcher, IntoGameTracker, TrackGame, TrackRun, TrackSpellPractice,
+    TrackStagePractice, TrackableGame, UpdateTracker, WatcherEvent,
 };
 use touhou::{Difficulty, HasLocations, Location, ShotType, Touhou10, Touhou7};
 
@@ -187,6 +186,8 @@ impl<'a> UpdateTracker<Touhou7> for SetTrackerUpdate<'a, Touhou7> {
                     current.success = false;
                 }
             }
+            // don't count attempts that were abandoned by retrying
+            Event::RunEnd { retried: true, .. } => self.tracker.current = None,
             _ => {}
         }
     }
@@ -223,6 +224,7 @@ impl<'a> UpdateTracker<Touhou10> for SetTrackerUpdate<'a, Touhou10> {
                     current.success = false;
                 }
             }
+            Event::RunEnd { retried: true, .. } => self.tracker.current = None,
             _ => {}
         }
     }
@@ -363,7 +365,18 @@ where
     }
 }
 
-fn track_loop<G, M, F>(mut new_memory: F) -> Result<(), MemoryReadError<G>>
+fn print_attempts<G: TrackableGame>(attempts: Vec<(SetKey<G>, Attempt)>) {
+    println!(
+        "[{0}] Finished {1} game:",
+        DisplayTime::default(),
+        G::abbreviation()
+    );
+    for (key, attempt) in attempts {
+        println!("    {key}: {attempt}");
+    }
+}
+
+fn track_loop<G, M, F>(new_memory: F) -> Result<(), MemoryReadError<G>>
 where
     G: TrackableGame,
     SetTracker<G>: TrackGame<G, Output = Vec<(SetKey<G>, Attempt)>>,
@@ -371,42 +384,23 @@ where
     F: FnMut() -> Result<Option<M>, MemoryReadError<G>>,
 {
     let abbr = G::abbreviation();
+    println!("[{}] Waiting for {abbr}...", DisplayTime::default());
 
-    loop {
-        println!("[{}] Waiting for {abbr}...", DisplayTime::default());
-
-        let memory = loop {
-            if let Some(memory) = new_memory()? {
-                println!(
-                    "[{0}] Attached to {abbr} process {1}",
-                    DisplayTime::default(),
-                    memory.pid()
-                );
-                break memory;
-            }
-
-            sleep(Duration::from_millis(500));
-        };
-
-        let mut driver = memory.track_games();
-        while driver.is_running() {
-            if let Some(attempts) = driver.update()? {
-                println!("[{0}] Finished {abbr} game:", DisplayTime::default());
-                for (key, attempt) in attempts {
-                    println!("    {key}: {attempt}");
-                }
-            }
-
-            sleep(Duration::from_millis(100));
-        }
-
-        if let (_, Some(attempts)) = driver.close() {
-            println!("[{0}] Finished {abbr} game:", DisplayTime::default());
-            for (key, attempt) in attempts {
-                println!("    {key}: {attempt}");
+    for event in GameWatcher::new(new_memory) {
+        match event {
+            WatcherEvent::Attached(pid) => println!(
+                "[{0}] Attached to {abbr} process {pid}",
+                DisplayTime::default()
+            ),
+            WatcherEvent::Detached(_) | WatcherEvent::Restarted(_) => {
+                println!("[{}] Waiting for {abbr}...", DisplayTime::default())
             }
+            WatcherEvent::Finished(attempts) => print_attempts(attempts),
+            WatcherEvent::Error(e) => return Err(e),
         }
     }
+
+    Ok(())
 }
 
 fn main() -> Result<(), std::io::Error> {
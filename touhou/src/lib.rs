@@ -13,6 +13,8 @@ pub mod tracking;
 pub mod th07;
 #[cfg(feature = "th08")]
 pub mod th08;
+#[cfg(feature = "th09")]
+pub mod th09;
 #[cfg(feature = "th10")]
 pub mod th10;
 #[cfg(feature = "th15")]
@@ -27,6 +29,9 @@ pub use th07::Touhou7;
 #[cfg(feature = "th08")]
 #[doc(inline)]
 pub use th08::Touhou8;
+#[cfg(feature = "th09")]
+#[doc(inline)]
+pub use th09::Touhou9;
 #[cfg(feature = "th10")]
 #[doc(inline)]
 pub use th10::Touhou10;
@@ -4,6 +4,7 @@
 //! this module also provides support for working with player locations (for example, determining which section of a stage they're playing at the moment).
 
 use std::io;
+use std::time::Duration;
 
 use sysinfo::{Pid, PidExt, Process, ProcessExt, ProcessRefreshKind, System, SystemExt};
 
@@ -30,6 +31,33 @@ where
     val.try_into().map_err(T::Error::into)
 }
 
+/// The delay between the first and second attempts of a read made with [`retry_read`].
+///
+/// Each subsequent retry waits for one additional multiple of this delay.
+pub const RETRY_DELAY: Duration = Duration::from_millis(2);
+
+/// Runs a memory read, retrying it if it fails with a [transient](MemoryReadError::is_transient) error.
+///
+/// The read is attempted at most `max_attempts` times in total. If every attempt fails, the last error is returned.
+/// Retries are spaced out by increasing multiples of [`RETRY_DELAY`], to give the game a chance to finish
+/// whatever update caused the read to fail.
+pub fn retry_read<G, T, F>(max_attempts: u32, mut read: F) -> Result<G, T>
+where
+    G: Game,
+    F: FnMut() -> Result<G, T>,
+{
+    let mut attempts = 1;
+    loop {
+        match read() {
+            Err(e) if e.is_transient() && attempts < max_attempts => {
+                std::thread::sleep(RETRY_DELAY * attempts);
+                attempts += 1;
+            }
+            ret => return ret,
+        }
+    }
+}
+
 macro_rules! ensure_float_within_range {
     ($x:expr => $t:ty : ($lo:literal, $hi:literal, $val_name:literal)) => {{
         use crate::memory::MemoryReadError;
@@ -72,10 +100,25 @@ pub trait ProcessAttached: Sized {
     fn is_attachable_process(proc: &Process) -> bool;
 }
 
+/// The state of the process that an [`Attached`] value was attached to, as of the last check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProcessStatus {
+    /// The process is still running.
+    Running,
+    /// The process has exited.
+    Exited,
+    /// The process has exited, and its PID now belongs to a different process.
+    ///
+    /// This typically happens when the game is closed and relaunched in quick succession.
+    /// Any handles to the old process should be discarded rather than reused.
+    Replaced,
+}
+
 #[derive(Debug)]
 pub struct Attached<T> {
     system: System,
     pid: Pid,
+    start_time: Option<u64>,
     inner: T,
 }
 
@@ -97,6 +140,7 @@ impl<T: ProcessAttached> Attached<T> {
             let inner = T::from_pid(proc.pid().as_u32())?;
             Ok(Some(Self {
                 pid: proc.pid(),
+                start_time: Some(proc.start_time()),
                 system,
                 inner,
             }))
@@ -108,8 +152,61 @@ impl<T: ProcessAttached> Attached<T> {
     pub fn from_pid(pid: u32) -> io::Result<Self> {
         let mut system = System::new();
         system.refresh_processes_specifics(ProcessRefreshKind::new());
-        T::from_pid(pid).map(|inner| Self {
-            pid: Pid::from_u32(pid),
+
+        let pid = Pid::from_u32(pid);
+        let start_time = system.process(pid).map(|proc| proc.start_time());
+        T::from_pid(pid.as_u32()).map(|inner| Self {
+            pid,
+            start_time,
+            system,
+            inner,
+        })
+    }
+
+    /// Attaches to a specific process, like [`Self::from_pid`], but first checks that the process exists
+    /// and that its executable name matches the expected game.
+    ///
+    /// This is useful when multiple game instances are running, or when the PID is already known (for example,
+    /// from a launcher).
+    pub fn attach_to_pid(pid: u32) -> io::Result<Self> {
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessRefreshKind::new());
+
+        let proc = system.process(Pid::from_u32(pid)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no process with PID {pid}"),
+            )
+        })?;
+
+        if !T::is_attachable_process(proc) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("process {pid} is not a recognized game process"),
+            ));
+        }
+
+        let pid = proc.pid();
+        let start_time = Some(proc.start_time());
+        T::from_pid(pid.as_u32()).map(|inner| Self {
+            pid,
+            start_time,
+            system,
+            inner,
+        })
+    }
+
+    /// Attaches a new handle to the same process.
+    ///
+    /// The new handle keeps the original process's start time, so [`Self::status`] will still detect if the PID
+    /// has been reused by a different process. This fails if the process can no longer be opened.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let mut system = System::new();
+        system.refresh_processes_specifics(ProcessRefreshKind::new());
+
+        T::from_pid(self.pid.as_u32()).map(|inner| Self {
+            pid: self.pid,
+            start_time: self.start_time,
             system,
             inner,
         })
@@ -119,9 +216,32 @@ impl<T: ProcessAttached> Attached<T> {
         self.pid.as_u32()
     }
 
-    pub fn is_running(&mut self) -> bool {
-        self.system
+    /// Checks whether the attached process is still running.
+    ///
+    /// Along with checking that the PID is still alive, this also checks that it still refers to the same game process
+    /// that was originally attached to, by comparing the process's start time and executable name.
+    pub fn status(&mut self) -> ProcessStatus {
+        if !self
+            .system
             .refresh_process_specifics(self.pid, ProcessRefreshKind::new())
+        {
+            return ProcessStatus::Exited;
+        }
+
+        match self.system.process(self.pid) {
+            Some(proc)
+                if T::is_attachable_process(proc)
+                    && self.start_time.is_none_or(|t| t == proc.start_time()) =>
+            {
+                ProcessStatus::Running
+            }
+            Some(_) => ProcessStatus::Replaced,
+            None => ProcessStatus::Exited,
+        }
+    }
+
+    pub fn is_running(&mut self) -> bool {
+        self.status() == ProcessStatus::Running
     }
 
     pub fn access(&mut self) -> Option<&T> {
@@ -134,3 +254,54 @@ impl<T: ProcessAttached> Attached<T> {
 }
 
 pub(crate) use {define_state_struct, ensure_float_within_range};
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+    use crate::th07::Touhou7;
+
+    fn io_error(kind: ErrorKind) -> MemoryReadError<Touhou7> {
+        io::Error::new(kind, "test error").into()
+    }
+
+    #[test]
+    fn retries_transient_errors() {
+        let mut attempts = 0;
+        let ret = retry_read::<Touhou7, _, _>(3, || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(io_error(ErrorKind::InvalidData))
+            } else {
+                Ok(attempts)
+            }
+        });
+
+        assert_eq!(ret.unwrap(), 3);
+    }
+
+    #[test]
+    fn stops_after_max_attempts() {
+        let mut attempts = 0;
+        let ret = retry_read::<Touhou7, (), _>(4, || {
+            attempts += 1;
+            Err(io_error(ErrorKind::InvalidData))
+        });
+
+        assert!(ret.is_err());
+        assert_eq!(attempts, 4);
+    }
+
+    #[test]
+    fn does_not_retry_fatal_errors() {
+        let mut attempts = 0;
+        let ret = retry_read::<Touhou7, (), _>(5, || {
+            attempts += 1;
+            Err(io_error(ErrorKind::NotFound))
+        });
+
+        assert!(ret.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -4,6 +4,8 @@ use serde::de::DeserializeOwned;
 use serde::Serialize;
 
 use super::types::SpellState;
+use super::InvalidLocationData;
+use super::ProcessStatus;
 use crate::types::Game;
 use crate::{Difficulty, Location, ShotPower, ShotType, SpellCard, Stage};
 
@@ -15,6 +17,15 @@ pub trait GameMemory<G: Game>: Sized {
     fn is_running(&mut self) -> bool {
         self.access().is_some()
     }
+
+    /// Checks whether the attached game process is still running, or whether it has been replaced by a new process.
+    fn status(&mut self) -> ProcessStatus {
+        if self.is_running() {
+            ProcessStatus::Running
+        } else {
+            ProcessStatus::Exited
+        }
+    }
 }
 
 /// Top-level trait for accessing the state of an active Touhou game.
@@ -117,6 +128,7 @@ pub trait BossLifebars<G: Game>: BossData<G> {
 /// - [`BombCount`]
 /// - [`PlayerScore`]
 /// - [`ContinueCount`]
+/// - [`FaithMeter`]
 pub trait PlayerData<G: Game>: Sized {
     fn shot(&self) -> ShotType<G>;
     fn power(&self) -> ShotPower<G>;
@@ -159,6 +171,13 @@ pub trait PlayerScore<G: Game>: PlayerData<G> + Sized {
     fn score(&self) -> u64;
 }
 
+/// Trait for accessing the player's current faith value, for Touhou games with a faith-based scoring system (i.e. MoF).
+///
+/// In MoF, faith determines the value of point items collected by the player.
+pub trait FaithMeter<G: Game>: PlayerData<G> + Sized {
+    fn faith(&self) -> u32;
+}
+
 /// Trait for statelessly finding where the player currently is in an active Touhou game.
 ///
 /// This is generally implemented alongside [`RunData`] for games that support
@@ -193,7 +212,16 @@ pub trait GameLocation<G: Game>:
     fn spell(&self) -> Option<SpellCard<G>>;
     fn is_end(&self) -> bool;
     fn is_boss_start(&self) -> bool;
+
+    /// Returns whether this location is a survival card, which can only be timed out rather than captured.
+    fn is_survival(&self) -> bool {
+        false
+    }
+
     fn from_spell(spell: SpellCard<G>) -> Option<Self>;
+
+    /// Gets the location corresponding to a value returned by [`index`](GameLocation::index).
+    fn from_index(index: u64) -> Result<Self, InvalidLocationData<G>>;
 }
 
 /// Trait for games that have defined location information.
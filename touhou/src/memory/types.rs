@@ -3,6 +3,7 @@ use super::{GameLocation, HasLocations};
 
 mod error;
 mod location;
+mod snapshot;
 mod state;
 
 #[doc(inline)]
@@ -10,4 +11,6 @@ pub use error::*;
 #[doc(inline)]
 pub use location::*;
 #[doc(inline)]
+pub use snapshot::*;
+#[doc(inline)]
 pub use state::*;
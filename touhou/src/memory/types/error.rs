@@ -144,6 +144,22 @@ impl<G: Game> MemoryReadError<G> {
     pub fn new_other<T: Into<i64>>(err: InvalidGameValue<T>) -> Self {
         Self::InvalidOther(err.into_other())
     }
+
+    /// Returns whether this error is likely to go away if the read is retried.
+    ///
+    /// Games frequently reallocate their internal structures during scene transitions, so reads that land
+    /// mid-frame can fail or return garbage values. Errors indicating that the process is gone or can't be
+    /// accessed are treated as fatal, as are [`Other`](Self::Other) errors.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::IO(err) => !matches!(
+                err.kind(),
+                ErrorKind::NotFound | ErrorKind::PermissionDenied | ErrorKind::Unsupported
+            ),
+            Self::Other(_) => false,
+            _ => true,
+        }
+    }
 }
 
 impl<G: Game> From<MemoryReadError<G>> for IOError {
@@ -236,3 +252,44 @@ impl<G: Game> Error for MemoryReadError<G> {
         }
     }
 }
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use super::*;
+    use crate::th07::Touhou7;
+
+    type Error = MemoryReadError<Touhou7>;
+
+    #[test]
+    fn process_errors_are_fatal() {
+        for kind in [
+            ErrorKind::NotFound,
+            ErrorKind::PermissionDenied,
+            ErrorKind::Unsupported,
+        ] {
+            assert!(!Error::from(IOError::new(kind, "test")).is_transient());
+        }
+    }
+
+    #[test]
+    fn read_errors_are_transient() {
+        for kind in [
+            ErrorKind::InvalidData,
+            ErrorKind::UnexpectedEof,
+            ErrorKind::Other,
+        ] {
+            assert!(Error::from(IOError::new(kind, "test")).is_transient());
+        }
+    }
+
+    #[test]
+    fn invalid_values_are_transient() {
+        assert!(Error::float_out_of_range("test value", 5.0, 0.0, 1.0).is_transient());
+        assert!(Error::other_out_of_range("test value", 5, 0, 1).is_transient());
+    }
+
+    #[test]
+    fn other_errors_are_fatal() {
+        assert!(!Error::other("test").is_transient());
+    }
+}
@@ -55,9 +55,35 @@ impl<G: HasLocations> Location<G> {
         self.0.is_boss_start()
     }
 
+    pub fn is_survival(&self) -> bool {
+        self.0.is_survival()
+    }
+
     pub fn from_spell(spell: SpellCard<G>) -> Option<Self> {
         G::Location::from_spell(spell).map(Self)
     }
+
+    /// Reconstructs a location from a value returned by [`index`](Location::index).
+    ///
+    /// This allows locations to be stored as plain integers (for example, in a database).
+    pub fn from_index(index: u64) -> Result<Self, InvalidLocationData<G>> {
+        G::Location::from_index(index).map(Self)
+    }
+
+    /// Iterates over every location from this location to `end` (inclusive), in [`index`](Location::index) order.
+    ///
+    /// Spell card locations that share an index (such as the same attack on different difficulties) are all included.
+    /// This yields nothing if `end` comes before this location.
+    pub fn range_to(self, end: Self) -> impl Iterator<Item = Self>
+    where
+        G::Location: AllIterable,
+    {
+        let (start, end) = (self.index(), end.index());
+        G::Location::iter_all()
+            .map(Self)
+            .skip_while(move |location| location.index() < start)
+            .take_while(move |location| location.index() <= end)
+    }
 }
 
 impl<G: HasLocations> PartialEq for Location<G> {
@@ -176,6 +202,23 @@ impl AnyLocation {
         }
     }
 
+    /// Creates a location for the game `G` from raw values, checking that they describe a valid location in that game.
+    ///
+    /// This is useful for validating location data loaded from external sources (such as a database) up front,
+    /// rather than when it is eventually converted to a game-specific location.
+    pub fn validated<G>(
+        stage: u16,
+        index: u64,
+        spell: Option<u32>,
+    ) -> Result<Self, InvalidLocationData<G>>
+    where
+        G: HasLocations,
+        G::Location: TryFrom<Self, Error = InvalidLocationData<G>>,
+    {
+        let location = Self::new(G::GAME_ID, stage, index, spell);
+        G::Location::try_from(location).map(|_| location)
+    }
+
     pub fn game(&self) -> GameId {
         self.game
     }
@@ -205,28 +248,41 @@ impl AnyLocation {
     }
 }
 
-impl Display for AnyLocation {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        use crate::types::VisitGame;
-
-        struct Visitor<'a, 'b>(AnyLocation, &'a mut std::fmt::Formatter<'b>);
-
-        impl<'a, 'b> VisitGame for Visitor<'a, 'b> {
-            type Output = std::fmt::Result;
-
+impl AnyLocation {
+    /// Attempts to describe this location using the name of its game-specific location.
+    ///
+    /// This returns `None` if the location's game does not have a location type compiled in
+    /// or if the stored data does not correspond to a valid location in that game.
+    pub fn describe(&self) -> Option<String> {
+        match self.game {
             #[cfg(feature = "th07")]
-            fn visit_th07(self) -> Self::Output {
-                use crate::th07::Location;
-                Location::try_from(self.0).unwrap().fmt(self.1)
-            }
-
+            GameId::PCB => crate::th07::Location::try_from(*self)
+                .ok()
+                .map(|location| location.to_string()),
             #[cfg(feature = "th08")]
-            fn visit_th08(self) -> Self::Output {
-                use crate::th08::Location;
-                Location::try_from(self.0).unwrap().fmt(self.1)
-            }
+            GameId::IN => crate::th08::Location::try_from(*self)
+                .ok()
+                .map(|location| location.to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl Display for AnyLocation {
+    /// Formats the raw fields of this location; use [`AnyLocation::describe`] for a human-readable name.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} stage {} location {}",
+            self.game.abbreviation(),
+            self.stage,
+            self.index
+        )?;
+
+        if let Some(spell) = self.spell {
+            write!(f, " (spell #{})", spell)?;
         }
 
-        Visitor(*self, f).accept_id(self.game)
+        Ok(())
     }
 }
@@ -0,0 +1,148 @@
+use super::SpellState;
+use crate::memory::traits::{BossData, BossLifebars, ECLTimeline, PlayerData, RunData, StageData};
+use crate::types::Game;
+use crate::{Difficulty, ShotPower, ShotType, Stage};
+
+/// A plain-data snapshot of an active boss fight.
+#[derive(Debug, Clone, Copy)]
+pub struct BossSnapshot<G: Game> {
+    active_spell: Option<SpellState<G>>,
+    remaining_lifebars: u8,
+}
+
+impl<G: Game> BossSnapshot<G> {
+    pub const fn new(active_spell: Option<SpellState<G>>, remaining_lifebars: u8) -> Self {
+        Self {
+            active_spell,
+            remaining_lifebars,
+        }
+    }
+}
+
+impl<G: Game> BossData<G> for BossSnapshot<G> {
+    fn active_spell(&self) -> Option<SpellState<G>> {
+        self.active_spell
+    }
+}
+
+impl<G: Game> BossLifebars<G> for BossSnapshot<G> {
+    fn remaining_lifebars(&self) -> u8 {
+        self.remaining_lifebars
+    }
+}
+
+/// A plain-data snapshot of the player's shot type and power.
+#[derive(Debug, Clone, Copy)]
+pub struct PlayerSnapshot<G: Game> {
+    shot: ShotType<G>,
+    power: ShotPower<G>,
+}
+
+impl<G: Game> PlayerSnapshot<G> {
+    pub const fn new(shot: ShotType<G>, power: ShotPower<G>) -> Self {
+        Self { shot, power }
+    }
+}
+
+impl<G: Game> PlayerData<G> for PlayerSnapshot<G> {
+    fn shot(&self) -> ShotType<G> {
+        self.shot
+    }
+
+    fn power(&self) -> ShotPower<G> {
+        self.power
+    }
+}
+
+/// A plain-data snapshot of the current stage, its ECL timeline position, and any active boss.
+#[derive(Debug, Clone, Copy)]
+pub struct StageSnapshot<G: Game> {
+    stage: Stage<G>,
+    ecl_time: u32,
+    boss: Option<BossSnapshot<G>>,
+}
+
+impl<G: Game> StageSnapshot<G> {
+    pub const fn new(stage: Stage<G>, ecl_time: u32, boss: Option<BossSnapshot<G>>) -> Self {
+        Self {
+            stage,
+            ecl_time,
+            boss,
+        }
+    }
+}
+
+impl<G: Game> StageData<G> for StageSnapshot<G> {
+    type BossState = BossSnapshot<G>;
+
+    fn stage_id(&self) -> Stage<G> {
+        self.stage
+    }
+
+    fn active_boss(&self) -> Option<&Self::BossState> {
+        self.boss.as_ref()
+    }
+}
+
+impl<G: Game> ECLTimeline<G> for StageSnapshot<G> {
+    fn ecl_time(&self) -> u32 {
+        self.ecl_time
+    }
+}
+
+/// A plain-data snapshot of an in-progress run, built from previously captured or reconstructed state.
+///
+/// This implements the same state traits as the live memory readers, so that locations can be resolved
+/// without attaching to a running game process (for example, when analyzing replays offline).
+/// For games that support it, the player's location can then be found via [`ResolveLocation`](crate::memory::ResolveLocation).
+#[derive(Debug, Clone, Copy)]
+pub struct RunSnapshot<G: Game> {
+    difficulty: Difficulty<G>,
+    practice: bool,
+    player: PlayerSnapshot<G>,
+    stage: StageSnapshot<G>,
+}
+
+impl<G: Game> RunSnapshot<G> {
+    pub const fn new(
+        difficulty: Difficulty<G>,
+        practice: bool,
+        player: PlayerSnapshot<G>,
+        stage: StageSnapshot<G>,
+    ) -> Self {
+        Self {
+            difficulty,
+            practice,
+            player,
+            stage,
+        }
+    }
+}
+
+impl<G: Game> RunData<G> for RunSnapshot<G> {
+    type StageState = StageSnapshot<G>;
+    type PlayerState = PlayerSnapshot<G>;
+
+    fn difficulty(&self) -> Difficulty<G> {
+        self.difficulty
+    }
+
+    fn player(&self) -> &Self::PlayerState {
+        &self.player
+    }
+
+    fn stage(&self) -> &Self::StageState {
+        &self.stage
+    }
+
+    fn is_practice(&self) -> bool {
+        self.practice
+    }
+}
+
+#[cfg(feature = "th07")]
+impl crate::memory::ResolveLocation<crate::Touhou7> for RunSnapshot<crate::Touhou7> {
+    fn resolve_location(&self) -> Option<crate::memory::Location<crate::Touhou7>> {
+        crate::memory::Location::resolve(self)
+    }
+}
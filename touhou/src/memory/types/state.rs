@@ -1,6 +1,6 @@
 use std::ops::Deref;
 
-use crate::types::{Game, GameValue, SpellCard};
+use crate::types::{Game, GameValue, SpellCard, Stage};
 
 /// The status of a spell in a running game.
 ///
@@ -46,3 +46,23 @@ impl<G: Game> Deref for SpellState<G> {
         &self.spell
     }
 }
+
+/// The mode a game is being played in, as read from a running game.
+///
+/// Not every game supports every mode; for example, PCB has no spell practice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameMode<G: Game> {
+    /// A normal game, starting from the first stage (or the Extra or Phantasm stage).
+    Story,
+    /// Stage practice, for the given stage.
+    StagePractice(Stage<G>),
+    /// Spell practice, for the given spell card.
+    SpellPractice(SpellCard<G>),
+}
+
+impl<G: Game> GameMode<G> {
+    /// Returns `true` for both stage practice and spell practice.
+    pub fn is_practice(&self) -> bool {
+        !matches!(self, Self::Story)
+    }
+}
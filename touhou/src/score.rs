@@ -1,18 +1,94 @@
 //! Types and traits for reading score file data.
 
+mod checksum;
+mod compress;
 mod crypt;
 mod decompress;
+mod segment;
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt::{Debug, Display};
-use std::io::{self, ErrorKind, Read};
+use std::io::{self, ErrorKind, Read, Write};
 use std::str;
 use std::str::FromStr;
 
 use anyhow::anyhow;
+pub use checksum::{ChecksumDecryptor, ThCryptPrefix};
+pub use compress::StreamCompressor;
 pub use crypt::ThCrypt;
+#[cfg(test)]
+pub(crate) use crypt::encrypt;
 pub use decompress::StreamDecompressor;
+pub use segment::{ParseStatus, ScoreSegment, SegmentParser};
+use encoding_rs::SHIFT_JIS;
+use time::{Date, Month};
 
-use crate::types::{Difficulty, Game, ShotType, SpellCard, Stage};
+use crate::types::any::{AnyDifficulty, AnySpellCard};
+use crate::types::{AllIterable, Difficulty, Game, GameId, ShotType, SpellCard, Stage};
+
+/// Reads and discards exactly `N` bytes from a score file.
+#[cfg(any(feature = "th08", feature = "th09", feature = "th10", feature = "th15"))]
+pub(crate) fn skip_bytes<const N: usize, R: Read>(mut src: R) -> io::Result<()> {
+    let mut buf = [0u8; N];
+    src.read_exact(&mut buf[..])
+}
+
+/// Returns a closure that converts a value read from a score file, mapping conversion failures to [`io::Error`]s of the given kind.
+#[cfg(any(feature = "th08", feature = "th09", feature = "th10", feature = "th15"))]
+pub(crate) fn try_into_or_io_error<T, U>(kind: ErrorKind) -> impl FnOnce(T) -> io::Result<U>
+where
+    T: TryInto<U>,
+    T::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    move |val| {
+        val.try_into()
+            .map_err(move |error| io::Error::new(kind, error))
+    }
+}
+
+/// Unwraps an [`io::Result`], returning `Ok(None)` from the enclosing function if the end of the file was reached.
+macro_rules! return_none_on_eof {
+    ($x:expr) => {
+        match $x {
+            Ok(v) => v,
+            Err(e) => {
+                if e.kind() == std::io::ErrorKind::UnexpectedEof {
+                    return Ok(None);
+                } else {
+                    return Err(e.into());
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use return_none_on_eof;
+
+/// Decodes a fixed-size, NUL-padded CP932 (Shift-JIS) text buffer from a score file.
+///
+/// Decoding stops at the first NUL byte. Invalid byte sequences are replaced with
+/// U+FFFD REPLACEMENT CHARACTER instead of causing an error.
+pub fn decode_cp932(buf: &[u8]) -> Cow<'_, str> {
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    SHIFT_JIS.decode_without_bom_handling(&buf[..len]).0
+}
+
+/// Decodes a fixed-size, NUL-padded CP932 (Shift-JIS) text buffer into an existing [`String`].
+///
+/// This behaves like [`decode_cp932`], but clears and reuses `out` instead of allocating a new string,
+/// which is useful when the same text needs to be decoded repeatedly.
+pub fn decode_cp932_into(buf: &[u8], out: &mut String) {
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    let mut decoder = SHIFT_JIS.new_decoder_without_bom_handling();
+
+    out.clear();
+    if let Some(max_len) = decoder.max_utf8_buffer_length(len) {
+        out.reserve(max_len);
+    }
+
+    let _ = decoder.decode_to_string(&buf[..len], out, true);
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ShortDate {
@@ -30,6 +106,28 @@ impl ShortDate {
             .parse()
             .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
     }
+
+    pub fn write_to<W: Write>(&self, dest: &mut W) -> Result<(), io::Error> {
+        write!(dest, "{}\0", self)
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Converts this date to a full calendar date.
+    ///
+    /// Score files only store the month and day, so the year has to be supplied by the caller.
+    /// Returns `None` if the stored month and day do not form a valid date in `assumed_year`
+    /// (for example, February 29th in a non-leap year).
+    pub fn to_date(&self, assumed_year: i32) -> Option<Date> {
+        let month = Month::try_from(self.month).ok()?;
+        Date::from_calendar_date(assumed_year, month, self.day).ok()
+    }
 }
 
 impl Display for ShortDate {
@@ -81,6 +179,14 @@ pub trait SpellCardRecord<G: Game>: Sized + Debug {
             .max()
             .unwrap()
     }
+
+    /// Returns the total spell practice captures and attempts for this card, in that order.
+    ///
+    /// This returns `None` for games without a separate spell practice mode. Games that have one
+    /// should override this to return values from their [`SpellPracticeRecord`] implementation.
+    fn practice_totals(&self) -> Option<(u32, u32)> {
+        None
+    }
 }
 
 /// A type representing a spell card practice record stored within a score file, for games that have separate spell practice modes (such as Touhou 8).
@@ -121,6 +227,14 @@ pub trait PracticeRecord<G: Game>: Sized + Debug {
     fn stage(&self) -> Stage<G>;
 }
 
+/// A type representing a record of which difficulties have been cleared with a given shot type.
+pub trait ClearRecord<G: Game>: Sized + Debug {
+    fn shot_type(&self) -> ShotType<G>;
+
+    /// Returns whether the given difficulty has been cleared, either in story mode or in stage practice.
+    fn cleared(&self, difficulty: &Difficulty<G>, practice: bool) -> bool;
+}
+
 /// A type representing a loaded score file.
 pub trait ScoreFile<G: Game>: Sized + Debug {
     type SpellCardRecord: SpellCardRecord<G>;
@@ -128,4 +242,440 @@ pub trait ScoreFile<G: Game>: Sized + Debug {
 
     fn spell_cards(&self) -> &[Self::SpellCardRecord];
     fn practice_records(&self) -> &[Self::PracticeRecord];
+
+    /// Iterates over the total captures and attempts for each spell card in this file, with the game type erased.
+    ///
+    /// Since the yielded values don't depend on the game type, iterators from multiple games' score files
+    /// can be chained together to compute statistics across games.
+    fn any_card_stats(&self) -> impl Iterator<Item = AnyCardStats> + '_ {
+        self.spell_cards().iter().map(|record| {
+            let card = record.card();
+            AnyCardStats {
+                card: AnySpellCard::new::<G>(card.unwrap()),
+                difficulty: AnyDifficulty::new::<G>(card.difficulty().unwrap()),
+                captures: record.total_captures(),
+                attempts: record.total_attempts(),
+                practice: record.practice_totals(),
+            }
+        })
+    }
+
+    /// Sums up the total captures and attempts for the spell cards in each stage, in that order.
+    ///
+    /// Stages without any spell card records in this file are omitted.
+    fn stage_capture_rates(&self) -> HashMap<Stage<G>, (u32, u32)> {
+        let mut ret = HashMap::new();
+
+        for record in self.spell_cards() {
+            let entry = ret.entry(record.card().stage()).or_insert((0, 0));
+            entry.0 += record.total_captures();
+            entry.1 += record.total_attempts();
+        }
+
+        ret
+    }
+
+    /// Gets the percentage of spell cards on the given difficulty that have been captured at least once.
+    ///
+    /// A card counts as captured if its total captures across all shot types is nonzero.
+    /// Cards that have no record in this file count as uncaptured.
+    fn completion_percentage(&self, difficulty: Difficulty<G>) -> f64 {
+        completion_percentage_where(self, |card| card.difficulty() == difficulty)
+    }
+
+    /// Gets the percentage of all spell cards in the game that have been captured at least once.
+    ///
+    /// See [`Self::completion_percentage`] for more details.
+    fn completion_percentage_all(&self) -> f64 {
+        completion_percentage_where(self, |_| true)
+    }
+
+    /// Compares this file against an earlier copy of the same file, such as a backup from a previous session.
+    ///
+    /// Cards that don't appear in `prev` are treated as having no prior attempts.
+    fn diff(&self, prev: &Self) -> ScoreDiff<G> {
+        let prev_records: HashMap<_, _> = prev
+            .spell_cards()
+            .iter()
+            .map(|record| (record.card(), record))
+            .collect();
+
+        let mut cards = Vec::new();
+        for record in self.spell_cards() {
+            let prev_record = prev_records.get(&record.card());
+
+            for shot in record.shot_types() {
+                let (prev_captures, prev_attempts) = prev_record
+                    .map(|r| (r.captures(shot), r.attempts(shot)))
+                    .unwrap_or((0, 0));
+
+                let captures = record.captures(shot);
+                let attempts = record.attempts(shot);
+
+                if (captures, attempts) != (prev_captures, prev_attempts) {
+                    cards.push(CardDiff {
+                        card: record.card(),
+                        shot_type: *shot,
+                        prev_captures,
+                        prev_attempts,
+                        captures,
+                        attempts,
+                    });
+                }
+            }
+        }
+
+        ScoreDiff { cards }
+    }
+}
+
+fn completion_percentage_where<G, S, F>(file: &S, filter: F) -> f64
+where
+    G: Game,
+    S: ScoreFile<G>,
+    F: Fn(&SpellCard<G>) -> bool,
+{
+    let captured: HashSet<_> = file
+        .spell_cards()
+        .iter()
+        .filter(|record| record.total_captures() > 0)
+        .map(|record| record.card())
+        .filter(&filter)
+        .collect();
+
+    let total = G::SpellID::iter_all()
+        .map(SpellCard::new)
+        .filter(&filter)
+        .count();
+
+    if total == 0 {
+        0.0
+    } else {
+        ((captured.len() as f64) / (total as f64)) * 100.0
+    }
+}
+
+/// The change in capture statistics for a single spell card and shot type between two copies of a score file.
+///
+/// Values of this type are produced by [`ScoreFile::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CardDiff<G: Game> {
+    card: SpellCard<G>,
+    shot_type: ShotType<G>,
+    prev_captures: u32,
+    prev_attempts: u32,
+    captures: u32,
+    attempts: u32,
+}
+
+impl<G: Game> CardDiff<G> {
+    pub fn card(&self) -> SpellCard<G> {
+        self.card
+    }
+
+    pub fn shot_type(&self) -> ShotType<G> {
+        self.shot_type
+    }
+
+    pub fn prev_captures(&self) -> u32 {
+        self.prev_captures
+    }
+
+    pub fn prev_attempts(&self) -> u32 {
+        self.prev_attempts
+    }
+
+    pub fn captures(&self) -> u32 {
+        self.captures
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Gets the number of attempts made between the two files.
+    pub fn new_attempts(&self) -> u32 {
+        self.attempts.saturating_sub(self.prev_attempts)
+    }
+
+    /// Gets the number of captures made between the two files.
+    pub fn new_captures(&self) -> u32 {
+        self.captures
+            .saturating_sub(self.prev_captures)
+            .min(self.new_attempts())
+    }
+
+    /// Returns whether this card was captured for the first time with this shot type between the two files.
+    pub fn is_first_capture(&self) -> bool {
+        self.prev_captures == 0 && self.captures > 0
+    }
+
+    /// Gets the change in overall capture rate for this card and shot type, in percentage points.
+    ///
+    /// A card with no attempts is treated as having a capture rate of 0%.
+    pub fn capture_rate_change(&self) -> f64 {
+        let rate = |captures: u32, attempts: u32| {
+            if attempts == 0 {
+                0.0
+            } else {
+                ((captures as f64) / (attempts as f64)) * 100.0
+            }
+        };
+
+        rate(self.captures, self.attempts) - rate(self.prev_captures, self.prev_attempts)
+    }
+}
+
+/// The differences in spell card statistics between two copies of a score file.
+///
+/// Values of this type are produced by [`ScoreFile::diff`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScoreDiff<G: Game> {
+    cards: Vec<CardDiff<G>>,
+}
+
+impl<G: Game> ScoreDiff<G> {
+    /// Returns whether the two files had identical spell card statistics.
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// Iterates over every card and shot type whose statistics changed between the two files.
+    pub fn changed_cards(&self) -> impl Iterator<Item = &CardDiff<G>> + '_ {
+        self.cards.iter()
+    }
+
+    /// Iterates over cards that were captured for the first time with a given shot type.
+    pub fn first_captures(&self) -> impl Iterator<Item = &CardDiff<G>> + '_ {
+        self.cards.iter().filter(|diff| diff.is_first_capture())
+    }
+
+    /// Iterates over cards that were attempted at least once between the two files.
+    pub fn attempted_cards(&self) -> impl Iterator<Item = &CardDiff<G>> + '_ {
+        self.cards.iter().filter(|diff| diff.new_attempts() > 0)
+    }
+}
+
+/// Spell card capture totals from a score file, with the game type erased.
+///
+/// Values of this type are yielded by [`ScoreFile::any_card_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AnyCardStats {
+    card: AnySpellCard,
+    difficulty: AnyDifficulty,
+    captures: u32,
+    attempts: u32,
+    practice: Option<(u32, u32)>,
+}
+
+impl AnyCardStats {
+    pub fn game_id(&self) -> GameId {
+        self.card.game_id()
+    }
+
+    pub fn card(&self) -> AnySpellCard {
+        self.card
+    }
+
+    /// Gets the difficulty that this card appears on.
+    pub fn difficulty(&self) -> AnyDifficulty {
+        self.difficulty
+    }
+
+    pub fn captures(&self) -> u32 {
+        self.captures
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Gets the total number of spell practice captures for this card.
+    ///
+    /// Returns `None` if the game this card is from has no separate spell practice mode.
+    pub fn practice_captures(&self) -> Option<u32> {
+        self.practice.map(|(captures, _)| captures)
+    }
+
+    /// Gets the total number of spell practice attempts for this card.
+    ///
+    /// Returns `None` if the game this card is from has no separate spell practice mode.
+    pub fn practice_attempts(&self) -> Option<u32> {
+        self.practice.map(|(_, attempts)| attempts)
+    }
+}
+
+/// A type holding capture statistics for a single spell card and shot type, such as a stored score file snapshot.
+pub trait CardStats<G: Game> {
+    fn card(&self) -> SpellCard<G>;
+    fn shot_type(&self) -> ShotType<G>;
+    fn captures(&self) -> u32;
+    fn attempts(&self) -> u32;
+}
+
+/// Formats spell card capture statistics for display.
+///
+/// The output looks like `#001 <card name> [ReimuA  ]: 3 / 10 (30.0%)`, optionally followed by
+/// the capture rate since an earlier set of statistics for the same card (such as a snapshot from a few hours prior).
+#[derive(Debug, Clone, Copy)]
+pub struct CardStatsFormatter {
+    title_width: usize,
+}
+
+impl CardStatsFormatter {
+    pub const fn new() -> Self {
+        Self { title_width: 85 }
+    }
+
+    /// Sets the width that card titles are centered within.
+    pub const fn title_width(self, title_width: usize) -> Self {
+        Self { title_width }
+    }
+
+    /// Formats a capture percentage, or a placeholder if there have been no attempts.
+    fn percentage(captures: u32, attempts: u32) -> String {
+        if attempts > 0 {
+            format!("{:^5.1}%", ((captures as f64) / (attempts as f64)) * 100.0)
+        } else {
+            format!("{:^6}", "-")
+        }
+    }
+
+    /// Formats the given statistics, including recent progress relative to `previous` if it is provided.
+    ///
+    /// Recent progress is omitted if no attempts have been made since `previous`.
+    pub fn format<G: Game, S: CardStats<G>>(&self, current: &S, previous: Option<&S>) -> String {
+        let card = current.card();
+        let title = format!("#{:03} {}", card.id(), card.name());
+
+        let mut ret = format!(
+            "{:^width$} [{:<8}]: {:>4} / {:<4} ({}",
+            title,
+            current.shot_type().to_string(),
+            current.captures(),
+            current.attempts(),
+            Self::percentage(current.captures(), current.attempts()),
+            width = self.title_width
+        );
+
+        if let Some(previous) = previous {
+            let d_attempts = current.attempts().saturating_sub(previous.attempts());
+            let d_captures = current
+                .captures()
+                .saturating_sub(previous.captures())
+                .min(d_attempts);
+
+            if d_attempts > 0 {
+                ret += &format!(
+                    ", recent {} / {} = {}",
+                    d_captures,
+                    d_attempts,
+                    Self::percentage(d_captures, d_attempts)
+                );
+            }
+        }
+
+        ret.push(')');
+        ret
+    }
+}
+
+impl Default for CardStatsFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use super::*;
+    use crate::th07::{ShotType as Th07Shot, SpellId, Touhou7};
+
+    struct Stats {
+        captures: u32,
+        attempts: u32,
+    }
+
+    impl CardStats<Touhou7> for Stats {
+        fn card(&self) -> SpellCard<Touhou7> {
+            SpellCard::new(SpellId::new(1).unwrap())
+        }
+
+        fn shot_type(&self) -> ShotType<Touhou7> {
+            ShotType::new(Th07Shot::ReimuA)
+        }
+
+        fn captures(&self) -> u32 {
+            self.captures
+        }
+
+        fn attempts(&self) -> u32 {
+            self.attempts
+        }
+    }
+
+    fn prefix(formatter: &CardStatsFormatter) -> String {
+        let card = SpellCard::<Touhou7>::new(SpellId::new(1).unwrap());
+        format!(
+            "{:^width$} [{:<8}]: ",
+            format!("#001 {}", card.name()),
+            ShotType::<Touhou7>::new(Th07Shot::ReimuA).to_string(),
+            width = formatter.title_width
+        )
+    }
+
+    #[test]
+    fn formats_card_stats() {
+        let formatter = CardStatsFormatter::new().title_width(40);
+        let current = Stats {
+            captures: 3,
+            attempts: 10,
+        };
+
+        assert_eq!(
+            formatter.format(&current, None),
+            format!("{}   3 / 10   (30.0 %)", prefix(&formatter))
+        );
+    }
+
+    #[test]
+    fn formats_recent_progress() {
+        let formatter = CardStatsFormatter::new();
+        let previous = Stats {
+            captures: 2,
+            attempts: 6,
+        };
+        let current = Stats {
+            captures: 3,
+            attempts: 10,
+        };
+
+        assert_eq!(
+            formatter.format(&current, Some(&previous)),
+            format!(
+                "{}   3 / 10   (30.0 %, recent 1 / 4 = 25.0 %)",
+                prefix(&formatter)
+            )
+        );
+
+        /* no attempts since the previous stats, so recent progress is left out */
+        assert_eq!(
+            formatter.format(&current, Some(&current)),
+            format!("{}   3 / 10   (30.0 %)", prefix(&formatter))
+        );
+    }
+
+    #[test]
+    fn formats_unattempted_cards() {
+        let formatter = CardStatsFormatter::new();
+        let current = Stats {
+            captures: 0,
+            attempts: 0,
+        };
+
+        assert_eq!(
+            formatter.format(&current, None),
+            format!("{}   0 / 0    (  -   )", prefix(&formatter))
+        );
+    }
 }
@@ -0,0 +1,223 @@
+use std::io::{self, Read};
+
+use super::ThCrypt;
+
+/// Decrypts the rolling XOR cipher used by older Touhou games' score files, while verifying their checksum.
+///
+/// PCB, IN, and PoFV all encrypt their score files with this cipher, and compute the file checksum over the decrypted data.
+/// Later games (IN onwards) additionally encrypt the start of the file with [`ThCrypt`], which can be handled
+/// by wrapping the source in a [`ThCryptPrefix`] first.
+#[derive(Debug)]
+pub struct ChecksumDecryptor<R> {
+    src: R,
+    key: u8,
+    checksum: u16,
+    target_checksum: u16,
+    at_eof: bool,
+}
+
+impl<R: Read> ChecksumDecryptor<R> {
+    /// Create a new instance wrapping an underlying [`Read`] type.
+    ///
+    /// This reads the 4-byte header containing the initial key and the expected checksum from `src`.
+    pub fn new(mut src: R) -> io::Result<Self> {
+        let mut init_bytes = [0u8; 4];
+        src.read_exact(&mut init_bytes[..])?;
+
+        let mut key = init_bytes[1].rotate_left(3);
+
+        init_bytes[2] ^= key;
+        key = key.wrapping_add(init_bytes[2]).rotate_left(3);
+
+        init_bytes[3] ^= key;
+        key = key.wrapping_add(init_bytes[3]).rotate_left(3);
+
+        Ok(Self {
+            src,
+            key,
+            checksum: 0,
+            target_checksum: u16::from_le_bytes([init_bytes[2], init_bytes[3]]),
+            at_eof: false,
+        })
+    }
+
+    /// Gets whether the checksum computed so far matches the one stored in the file.
+    pub fn valid_checksum(&self) -> bool {
+        self.checksum == self.target_checksum
+    }
+
+    /// Gets whether the computed checksum matches the one stored in the file.
+    ///
+    /// Returns `None` if the end of the underlying reader has not been reached yet.
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.at_eof.then(|| self.valid_checksum())
+    }
+
+    /// Gets the checksum of the data decrypted so far.
+    pub fn checksum(&self) -> u16 {
+        self.checksum
+    }
+
+    /// Gets the checksum stored in the file.
+    pub fn target_checksum(&self) -> u16 {
+        self.target_checksum
+    }
+}
+
+impl<R: Read> Read for ChecksumDecryptor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.src.read(buf)?;
+        if n == 0 && !buf.is_empty() {
+            self.at_eof = true;
+        }
+
+        for x in &mut buf[..n] {
+            *x ^= self.key;
+            self.key = self.key.wrapping_add(*x).rotate_left(3);
+            self.checksum = self.checksum.wrapping_add((*x) as u16);
+        }
+
+        Ok(n)
+    }
+}
+
+#[derive(Debug)]
+enum PrefixState<R> {
+    ThcryptActive(ThCrypt<R>),
+    ReadRest(R),
+    Working,
+}
+
+/// Decrypts a limited number of bytes at the start of a stream with [`ThCrypt`], and passes the rest through unchanged.
+#[derive(Debug)]
+pub struct ThCryptPrefix<R> {
+    state: PrefixState<R>,
+}
+
+impl<R: Read> ThCryptPrefix<R> {
+    /// Create a new instance wrapping an underlying [`Read`] type.
+    ///
+    /// The `key`, `step`, and `block_sz` parameters are passed to [`ThCrypt::new`], and `limit` is the number of bytes to decrypt.
+    pub fn new(src: R, key: u8, step: u8, block_sz: usize, limit: usize) -> Self {
+        Self {
+            state: PrefixState::ThcryptActive(ThCrypt::new(src, key, step, block_sz, Some(limit))),
+        }
+    }
+}
+
+impl<R: Read> Read for ThCryptPrefix<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match std::mem::replace(&mut self.state, PrefixState::Working) {
+            PrefixState::ThcryptActive(mut crypt) => {
+                let n = crypt.read(buf)?;
+                if crypt.at_limit() {
+                    self.state = PrefixState::ReadRest(crypt.unwrap());
+                } else {
+                    self.state = PrefixState::ThcryptActive(crypt);
+                }
+                Ok(n)
+            }
+            PrefixState::ReadRest(mut src) => {
+                let n = src.read(buf)?;
+                self.state = PrefixState::ReadRest(src);
+                Ok(n)
+            }
+            PrefixState::Working => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::score::encrypt;
+
+    /* Inverse of `ChecksumDecryptor`: the checksum is stored in bytes 2-3 and encrypted along with the body. */
+    fn encrypt_checksummed(seed: u8, body: &[u8]) -> Vec<u8> {
+        let checksum = body.iter().fold(0u16, |acc, x| acc.wrapping_add(*x as u16));
+
+        let mut plain = vec![0, seed];
+        plain.extend_from_slice(&checksum.to_le_bytes());
+        plain.extend_from_slice(body);
+
+        let mut key = seed.rotate_left(3);
+        let mut out = plain[..2].to_vec();
+        for x in &plain[2..] {
+            out.push(*x ^ key);
+            key = key.wrapping_add(*x).rotate_left(3);
+        }
+
+        out
+    }
+
+    #[test]
+    fn checksum_decryptor_round_trip() {
+        let body: Vec<u8> = (0..=255).chain(0..100).collect();
+        let data = encrypt_checksummed(0x5A, &body);
+
+        let mut reader = ChecksumDecryptor::new(Cursor::new(data)).unwrap();
+        assert_eq!(reader.checksum_valid(), None);
+
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+
+        assert_eq!(decrypted, body);
+        assert_eq!(reader.checksum(), reader.target_checksum());
+        assert_eq!(reader.checksum_valid(), Some(true));
+    }
+
+    #[test]
+    fn checksum_decryptor_detects_corruption() {
+        let body = b"Touhou Youyoumu score data".to_vec();
+        let mut data = encrypt_checksummed(0x13, &body);
+        *data.last_mut().unwrap() ^= 0xFF;
+
+        let mut reader = ChecksumDecryptor::new(Cursor::new(data)).unwrap();
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+
+        assert_ne!(decrypted, body);
+        assert_eq!(reader.checksum_valid(), Some(false));
+    }
+
+    #[test]
+    fn thcrypt_prefix_round_trip() {
+        const BLOCK_SZ: usize = 0x40;
+
+        let prefix: Vec<u8> = (0..BLOCK_SZ as u8).collect();
+        let rest = b"unencrypted trailing data".to_vec();
+
+        let mut data = encrypt(&prefix, 0x59, 0x79, BLOCK_SZ);
+        assert_ne!(data, prefix);
+        data.extend_from_slice(&rest);
+
+        let mut reader = ThCryptPrefix::new(Cursor::new(data), 0x59, 0x79, BLOCK_SZ, BLOCK_SZ);
+        let mut decrypted = Vec::new();
+        reader.read_to_end(&mut decrypted).unwrap();
+
+        assert_eq!(&decrypted[..BLOCK_SZ], &prefix[..]);
+        assert_eq!(&decrypted[BLOCK_SZ..], &rest[..]);
+    }
+
+    #[test]
+    fn thcrypt_prefix_matches_thcrypt_within_limit() {
+        const BLOCK_SZ: usize = 0x10;
+
+        let plain: Vec<u8> = (0..4 * BLOCK_SZ as u8).map(|x| x.wrapping_mul(7)).collect();
+        let data = encrypt(&plain, 0x12, 0x34, plain.len());
+
+        let mut expected = Vec::new();
+        ThCrypt::new(Cursor::new(&data), 0x12, 0x34, data.len(), None)
+            .read_to_end(&mut expected)
+            .unwrap();
+        assert_eq!(expected, plain);
+
+        let mut decrypted = Vec::new();
+        ThCryptPrefix::new(Cursor::new(&data), 0x12, 0x34, data.len(), data.len())
+            .read_to_end(&mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, plain);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+const DICT_SIZE: usize = 0x2000;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 18;
+const MAX_CANDIDATES: usize = 256;
+
+#[derive(Debug)]
+struct BitWriter {
+    out: Vec<u8>,
+    cur_byte: u8,
+    cur_bit: u8,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self {
+            out: Vec::new(),
+            cur_byte: 0,
+            cur_bit: 0x80,
+        }
+    }
+
+    fn write_bit(&mut self, bit: bool) {
+        if bit {
+            self.cur_byte |= self.cur_bit;
+        }
+
+        if self.cur_bit == 1 {
+            self.out.push(self.cur_byte);
+            self.cur_byte = 0;
+            self.cur_bit = 0x80;
+        } else {
+            self.cur_bit >>= 1;
+        }
+    }
+
+    fn write_bits<const N: usize>(&mut self, value: u16) {
+        debug_assert!(N <= 16);
+
+        for i in (0..N).rev() {
+            self.write_bit(((value >> i) & 1) != 0);
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.cur_bit != 0x80 {
+            self.out.push(self.cur_byte);
+        }
+
+        self.out
+    }
+}
+
+/// Compresses data into the LZ77-based format used by modern Touhou games' score files.
+///
+/// This is the inverse of [`StreamDecompressor`](`super::StreamDecompressor`).
+/// Written data is buffered internally; the compressed stream is only written to the
+/// underlying [`Write`] type once [`finish`](`Self::finish`) is called.
+#[derive(Debug)]
+pub struct StreamCompressor<W> {
+    dest: W,
+    input: Vec<u8>,
+}
+
+impl<W: Write> StreamCompressor<W> {
+    /// Create a new compressor wrapping an underlying [`Write`] type.
+    pub fn new(dest: W) -> Self {
+        Self {
+            dest,
+            input: Vec::new(),
+        }
+    }
+
+    /// Compress all buffered data into the underlying writer, then return it.
+    pub fn finish(mut self) -> io::Result<W> {
+        let compressed = compress(&self.input);
+        self.dest.write_all(&compressed)?;
+        Ok(self.dest)
+    }
+}
+
+impl<W: Write> Write for StreamCompressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.input.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Gets the dictionary index that the decompressor uses for the byte at output position `pos`.
+fn dict_index(pos: usize) -> usize {
+    (pos + 1) & (DICT_SIZE - 1)
+}
+
+fn compress(input: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::new();
+    let mut chains: HashMap<[u8; MIN_MATCH], Vec<usize>> = HashMap::new();
+    let mut pos = 0;
+
+    while pos < input.len() {
+        let mut best = (0, 0);
+
+        if let Some(prefix) = input.get(pos..(pos + MIN_MATCH)) {
+            let candidates = chains
+                .get(prefix)
+                .map(|v| &v[..])
+                .unwrap_or_default()
+                .iter()
+                .rev()
+                .take(MAX_CANDIDATES)
+                .take_while(|&&start| (pos - start) < DICT_SIZE);
+
+            for &start in candidates {
+                /* an index of 0 is used to mark the end of the stream */
+                if dict_index(start) == 0 {
+                    continue;
+                }
+
+                let len = input[pos..]
+                    .iter()
+                    .take(MAX_MATCH)
+                    .zip(&input[start..])
+                    .take_while(|(a, b)| a == b)
+                    .count();
+
+                if len > best.1 {
+                    best = (start, len);
+                    if len == MAX_MATCH {
+                        break;
+                    }
+                }
+            }
+        }
+
+        let advance = if best.1 >= MIN_MATCH {
+            writer.write_bit(false);
+            writer.write_bits::<13>(dict_index(best.0) as u16);
+            writer.write_bits::<4>((best.1 - MIN_MATCH) as u16);
+            best.1
+        } else {
+            writer.write_bit(true);
+            writer.write_bits::<8>(input[pos] as u16);
+            1
+        };
+
+        for i in pos..(pos + advance) {
+            if let Some(prefix) = input.get(i..(i + MIN_MATCH)) {
+                chains
+                    .entry(prefix.try_into().unwrap())
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        pos += advance;
+    }
+
+    /* end-of-stream marker */
+    writer.write_bit(false);
+    writer.write_bits::<13>(0);
+
+    writer.finish()
+}
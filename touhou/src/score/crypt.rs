@@ -171,3 +171,32 @@ impl<R: Read> Read for ThCrypt<R> {
         Ok(n)
     }
 }
+
+/// Encrypts data so that reading it through [`ThCrypt`] with the same parameters yields the original bytes.
+#[cfg(test)]
+pub(crate) fn encrypt(data: &[u8], mut key: u8, step: u8, block_sz: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+
+    for block in data.chunks(block_sz) {
+        /* Mirrors the handling of trailing partial blocks in `ThCrypt::decrypt_next_block`. */
+        let (block, last) = if block.len() < block_sz / 4 {
+            (&[][..], block)
+        } else if block.len() % 2 == 1 {
+            let (last, block) = block.split_last().unwrap();
+            (block, std::slice::from_ref(last))
+        } else {
+            (block, &[][..])
+        };
+
+        for i in 0..2 {
+            for idx in (0..block.len()).rev().skip(i).step_by(2) {
+                out.push(block[idx] ^ key);
+                key = key.wrapping_add(step);
+            }
+        }
+
+        out.extend_from_slice(last);
+    }
+
+    out
+}
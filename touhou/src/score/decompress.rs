@@ -20,6 +20,7 @@ pub struct StreamDecompressor<R> {
     dict_pos: usize,
     dict: Box<[u8]>,
     state: Option<DecompressorState>,
+    produced: usize,
 }
 
 impl<R: ReadBytesExt> StreamDecompressor<R> {
@@ -33,9 +34,27 @@ impl<R: ReadBytesExt> StreamDecompressor<R> {
             dict_pos: 1,
             dict,
             state: Some(DecompressorState::Init),
+            produced: 0,
         }
     }
 
+    /// Gets a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.src
+    }
+
+    /// Gets the total number of decompressed bytes that have been read from this decompressor so far.
+    pub fn bytes_produced(&self) -> usize {
+        self.produced
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading directly from the underlying reader will desynchronize the decompressor's state.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.src
+    }
+
     fn ensure_next_byte(&mut self) -> io::Result<Option<u8>> {
         if self.cur_byte.is_none() {
             self.cur_byte = match self.src.read_u8() {
@@ -173,6 +192,7 @@ impl<R: ReadBytesExt> Read for StreamDecompressor<R> {
             }
         }
 
+        self.produced += n;
         Ok(n)
     }
 }
@@ -0,0 +1,254 @@
+use std::io;
+use std::marker::PhantomData;
+
+/// A segment type used within older Touhou games' (decompressed) score files.
+///
+/// Each segment starts with an 8-byte header consisting of a 4-byte signature and two little-endian `u16` sizes,
+/// the first of which is the total size of the segment (including the header).
+pub trait ScoreSegment: Sized {
+    /// Parses a segment from its signature, sizes, and body (not including the header).
+    fn from_parts(
+        signature: [u8; 4],
+        size1: usize,
+        size2: usize,
+        data: Vec<u8>,
+    ) -> io::Result<Self>;
+}
+
+/// The result of trying to parse the next segment from a [`SegmentParser`].
+#[derive(Debug)]
+pub enum ParseStatus<S> {
+    /// A complete segment was parsed.
+    Segment(S),
+    /// Not enough data has been buffered to parse the next segment yet.
+    NeedMoreData,
+    /// The end of the segment data was reached; no further segments will be parsed.
+    Finished,
+}
+
+/// Incrementally parses segments from decompressed score file data as it becomes available.
+///
+/// Unlike the per-game `ScoreReader` types, which treat running out of input as the end of the file, this
+/// distinguishes between a segment that has only been partially received and the actual end of the segment data.
+/// This allows segments to be parsed from data that is still being written or received.
+#[derive(Debug, Clone)]
+pub struct SegmentParser<S> {
+    buf: Vec<u8>,
+    finished: bool,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S: ScoreSegment> SegmentParser<S> {
+    const HEADER_SIZE: usize = 8;
+
+    pub fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            finished: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds more decompressed data to be parsed.
+    pub fn feed(&mut self, data: &[u8]) {
+        if !self.finished {
+            self.buf.extend_from_slice(data);
+        }
+    }
+
+    /// Gets the number of bytes that have been buffered but not yet parsed.
+    pub fn buffered_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Gets whether the end of the segment data has been reached.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Tries to parse the next segment from the buffered data.
+    ///
+    /// Bytes are only consumed from the buffer once a complete segment is available, so if this returns
+    /// [`ParseStatus::NeedMoreData`], it can simply be called again after more data has been [fed](Self::feed) in.
+    pub fn next_segment(&mut self) -> io::Result<ParseStatus<S>> {
+        loop {
+            if self.finished {
+                return Ok(ParseStatus::Finished);
+            }
+
+            let Some(header) = self.buf.get(..Self::HEADER_SIZE) else {
+                return Ok(ParseStatus::NeedMoreData);
+            };
+
+            let signature = [header[0], header[1], header[2], header[3]];
+            let size1 = u16::from_le_bytes([header[4], header[5]]) as usize;
+            let size2 = u16::from_le_bytes([header[6], header[7]]) as usize;
+
+            /* sizes include the 8-byte segment header, so anything smaller can only be garbage or padding */
+            if size1 < Self::HEADER_SIZE {
+                self.finished = true;
+                self.buf = Vec::new();
+                return Ok(ParseStatus::Finished);
+            }
+
+            if self.buf.len() < size1 {
+                return Ok(ParseStatus::NeedMoreData);
+            }
+
+            let data = self.buf[Self::HEADER_SIZE..size1].to_vec();
+            self.buf.drain(..size1);
+
+            if size1 > Self::HEADER_SIZE {
+                return S::from_parts(signature, size1, size2, data).map(ParseStatus::Segment);
+            }
+        }
+    }
+}
+
+impl<S: ScoreSegment> Default for SegmentParser<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct RawSegment {
+        signature: [u8; 4],
+        size2: usize,
+        data: Vec<u8>,
+    }
+
+    impl ScoreSegment for RawSegment {
+        fn from_parts(
+            signature: [u8; 4],
+            size1: usize,
+            size2: usize,
+            data: Vec<u8>,
+        ) -> io::Result<Self> {
+            assert_eq!(size1, data.len() + 8);
+            Ok(Self {
+                signature,
+                size2,
+                data,
+            })
+        }
+    }
+
+    fn segment(signature: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let size = (data.len() + 8) as u16;
+        let mut ret = signature.to_vec();
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret.extend_from_slice(&(size + 1).to_le_bytes());
+        ret.extend_from_slice(data);
+        ret
+    }
+
+    fn expect_segment(parser: &mut SegmentParser<RawSegment>, signature: &[u8; 4], data: &[u8]) {
+        match parser.next_segment().unwrap() {
+            ParseStatus::Segment(segment) => assert_eq!(
+                segment,
+                RawSegment {
+                    signature: *signature,
+                    size2: data.len() + 9,
+                    data: data.to_vec(),
+                }
+            ),
+            other => panic!("expected segment, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn waits_for_partial_header() {
+        let raw = segment(b"ABCD", &[1, 2, 3]);
+        let mut parser = SegmentParser::new();
+
+        parser.feed(&raw[..5]);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::NeedMoreData
+        ));
+        assert_eq!(parser.buffered_len(), 5);
+
+        parser.feed(&raw[5..]);
+        expect_segment(&mut parser, b"ABCD", &[1, 2, 3]);
+        assert_eq!(parser.buffered_len(), 0);
+    }
+
+    #[test]
+    fn waits_for_partial_body() {
+        let raw = segment(b"ABCD", &[1, 2, 3, 4, 5]);
+        let mut parser = SegmentParser::new();
+
+        parser.feed(&raw[..10]);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::NeedMoreData
+        ));
+        assert_eq!(parser.buffered_len(), 10);
+
+        parser.feed(&raw[10..]);
+        expect_segment(&mut parser, b"ABCD", &[1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn skips_empty_segments() {
+        let mut parser = SegmentParser::new();
+        parser.feed(&segment(b"PAD0", &[]));
+        parser.feed(&segment(b"PAD1", &[]));
+        parser.feed(&segment(b"ABCD", &[9]));
+
+        expect_segment(&mut parser, b"ABCD", &[9]);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::NeedMoreData
+        ));
+    }
+
+    #[test]
+    fn finishes_on_undersized_segment() {
+        let mut parser = SegmentParser::new();
+        parser.feed(&segment(b"ABCD", &[1]));
+        parser.feed(&[0; 8]);
+        parser.feed(&segment(b"EFGH", &[2]));
+
+        expect_segment(&mut parser, b"ABCD", &[1]);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::Finished
+        ));
+        assert!(parser.is_finished());
+        assert_eq!(parser.buffered_len(), 0);
+
+        /* data fed after the end is ignored */
+        parser.feed(&segment(b"IJKL", &[3]));
+        assert_eq!(parser.buffered_len(), 0);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::Finished
+        ));
+    }
+
+    #[test]
+    fn parses_multiple_segments_per_feed() {
+        let mut raw = segment(b"ABCD", &[1, 2]);
+        raw.extend(segment(b"EFGH", &[3, 4, 5]));
+        raw.extend(segment(b"IJKL", &[6]));
+
+        let mut parser = SegmentParser::new();
+        parser.feed(&raw[..raw.len() - 2]);
+
+        expect_segment(&mut parser, b"ABCD", &[1, 2]);
+        expect_segment(&mut parser, b"EFGH", &[3, 4, 5]);
+        assert!(matches!(
+            parser.next_segment().unwrap(),
+            ParseStatus::NeedMoreData
+        ));
+
+        parser.feed(&raw[raw.len() - 2..]);
+        expect_segment(&mut parser, b"IJKL", &[6]);
+    }
+}
@@ -61,8 +61,11 @@ define_game! {
             Three: "Stage 3",
             Four: "Stage 4",
             Five: "Stage 5",
+            #[final_stage]
             Six: "Stage 6",
+            #[extra]
             Extra: "Extra Stage",
+            #[extra]
             Phantasm: "Phantasm Stage",
         }
     }
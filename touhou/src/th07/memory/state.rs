@@ -4,8 +4,8 @@ use super::location::Location;
 use super::process::MemoryAccess;
 use crate::memory::traits::*;
 use crate::memory::{
-    define_state_struct, ensure_float_within_range, try_into_or_mem_error, MemoryReadError,
-    ResolveLocation, SpellState,
+    define_state_struct, ensure_float_within_range, try_into_or_mem_error, GameMode,
+    MemoryReadError, ResolveLocation, SpellState,
 };
 use crate::th07::{SpellId, Touhou7};
 use crate::types::{Difficulty, ShotPower, ShotType, Stage};
@@ -23,6 +23,7 @@ define_state_struct! {
         total_bombs: u32,
         border_active: bool,
         score: u32,
+        graze: u32,
         cherry: u32,
         cherry_max: u32,
         cherry_plus: u32
@@ -63,6 +64,7 @@ impl PlayerState {
             total_bombs: proc.player_bombs_used()? as u32,
             border_active: proc.border_state()? != 0,
             score: proc.score()?,
+            graze: proc.graze()?,
             cherry_max,
             cherry: proc.cherry()?.saturating_sub(cherry_base).min(cherry_max),
             cherry_plus: proc.cherry_plus()?.saturating_sub(cherry_base).min(50000),
@@ -300,6 +302,37 @@ impl GameState {
             && !replay)
     }
 
+    /// Gets the state of the run in progress, if there is one.
+    pub fn run(&self) -> Option<&RunState> {
+        match self {
+            Self::InGame { run } | Self::InReplay { run, .. } | Self::GameOver { run, .. } => {
+                Some(run)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gets whether the current run (or the menu being viewed) is for stage practice.
+    pub fn is_practice(&self) -> bool {
+        match self {
+            Self::PracticeStartMenu => true,
+            _ => self.run().is_some_and(|run| run.practice),
+        }
+    }
+
+    /// Gets the mode of the run in progress, if there is one.
+    ///
+    /// PCB has no spell practice mode, so this never returns [`GameMode::SpellPractice`].
+    pub fn game_mode(&self) -> Option<GameMode<Touhou7>> {
+        self.run().map(|run| {
+            if run.practice {
+                GameMode::StagePractice(run.stage.stage)
+            } else {
+                GameMode::Story
+            }
+        })
+    }
+
     pub fn new(proc: &MemoryAccess) -> ReadResult<Self> {
         let mode = proc.game_mode()?;
         let practice = (mode & 0x01) != 0;
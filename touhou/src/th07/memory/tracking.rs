@@ -166,11 +166,13 @@ where
         self.prev_state = new_state;
     }
 
-    fn finish(mut self, cleared: bool, end_state: Option<RunState>) -> T::Output {
+    fn finish(mut self, cleared: bool, retried: bool, end_state: Option<RunState>) -> T::Output {
         if let Some(end_state) = end_state {
             self.update_state(end_state);
         }
 
+        self.tracker.push_run_end(self.prev_state, cleared, retried);
+
         if self.tracker.tracking_type() == TrackingType::StagePractice {
             self.tracker.finish_stage_practice(self.prev_state)
         } else if cleared {
@@ -211,15 +213,18 @@ where
                 Ok(UpdateStatus::Continuing(self))
             }
             GameState::LoadingStage => Ok(UpdateStatus::Continuing(self)),
-            GameState::GameOver { cleared, run } => {
-                Ok(UpdateStatus::Finished(self.finish(cleared, Some(run))))
-            }
-            _ => Ok(UpdateStatus::Finished(self.finish(false, None))),
+            GameState::GameOver { cleared, run } => Ok(UpdateStatus::Finished(self.finish(
+                cleared,
+                false,
+                Some(run),
+            ))),
+            GameState::RetryingGame => Ok(UpdateStatus::Finished(self.finish(false, true, None))),
+            _ => Ok(UpdateStatus::Finished(self.finish(false, false, None))),
         }
     }
 
     fn terminate(self) -> T::Output {
-        self.finish(false, None)
+        self.finish(false, false, None)
     }
 }
 
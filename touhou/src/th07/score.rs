@@ -1,16 +1,21 @@
+use std::borrow::Cow;
 use std::convert::TryInto;
 use std::fmt::Debug;
-use std::io::{self, Cursor, ErrorKind, Read};
+use std::io::{self, Cursor, ErrorKind, Read, Write};
 use std::str;
+use std::time::Duration;
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{ReadBytesExt, WriteBytesExt};
 
-use super::{Difficulty, ShotType as Th07Shot, Stage, Touhou7};
+use super::{Difficulty, ShotType as Th07Shot, SpellId, Stage, Touhou7};
 use crate::score::*;
 use crate::types::{
     Difficulty as DifficultyWrapper, ShotType, SpellCard, Stage as StageWrapper, StageProgress,
 };
 
+/// The byte order used for multi-byte values in this game's score files.
+type FileOrder = byteorder::LittleEndian;
+
 macro_rules! impl_getters {
     { $t:ty, $( $field:ident : $field_type:ty ),+ } => {
         impl $t {
@@ -73,6 +78,14 @@ macro_rules! read_array {
     }
 }
 
+macro_rules! write_array {
+    ($dest:expr, $arr:expr, $write_fn:ident) => {
+        for elem in $arr.iter() {
+            $dest.$write_fn::<FileOrder>(*elem)?;
+        }
+    };
+}
+
 macro_rules! read_try_into {
     ($t1:ty as $t2:ty : $x:expr) => {{
         use std::io::{self, ErrorKind};
@@ -80,21 +93,6 @@ macro_rules! read_try_into {
     }};
 }
 
-macro_rules! return_none_on_eof {
-    ($x:expr) => {
-        match $x {
-            Ok(v) => v,
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                    return Ok(None);
-                } else {
-                    return Err(e.into());
-                }
-            }
-        }
-    };
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct StoredTime {
     hours: u32,
@@ -106,12 +104,41 @@ pub struct StoredTime {
 impl StoredTime {
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
         Ok(StoredTime {
-            hours: src.read_u32::<LittleEndian>()?,
-            minutes: src.read_u32::<LittleEndian>()?,
-            seconds: src.read_u32::<LittleEndian>()?,
-            milliseconds: src.read_u32::<LittleEndian>()?,
+            hours: src.read_u32::<FileOrder>()?,
+            minutes: src.read_u32::<FileOrder>()?,
+            seconds: src.read_u32::<FileOrder>()?,
+            milliseconds: src.read_u32::<FileOrder>()?,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.hours)?;
+        dest.write_u32::<FileOrder>(self.minutes)?;
+        dest.write_u32::<FileOrder>(self.seconds)?;
+        dest.write_u32::<FileOrder>(self.milliseconds)
+    }
+
+    /// Converts this time into a [`Duration`].
+    pub fn as_duration(&self) -> Duration {
+        let secs = (self.hours as u64) * 3600 + (self.minutes as u64) * 60 + (self.seconds as u64);
+        Duration::from_secs(secs) + Duration::from_millis(self.milliseconds as u64)
+    }
+}
+
+impl std::fmt::Display for StoredTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}:{:02}.{:03}",
+            self.hours, self.minutes, self.seconds, self.milliseconds
+        )
+    }
+}
+
+impl From<StoredTime> for Duration {
+    fn from(value: StoredTime) -> Self {
+        value.as_duration()
+    }
 }
 
 impl_getters! {
@@ -135,14 +162,23 @@ pub struct PlayCount {
 impl PlayCount {
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
         Ok(PlayCount {
-            total_attempts: src.read_u32::<LittleEndian>()?,
-            attempts: read_array![src.read_u32::<LittleEndian>()?; 6],
-            retries: src.read_u32::<LittleEndian>()?,
-            clears: src.read_u32::<LittleEndian>()?,
-            continues: src.read_u32::<LittleEndian>()?,
-            practices: src.read_u32::<LittleEndian>()?,
+            total_attempts: src.read_u32::<FileOrder>()?,
+            attempts: read_array![src.read_u32::<FileOrder>()?; 6],
+            retries: src.read_u32::<FileOrder>()?,
+            clears: src.read_u32::<FileOrder>()?,
+            continues: src.read_u32::<FileOrder>()?,
+            practices: src.read_u32::<FileOrder>()?,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.total_attempts)?;
+        write_array!(dest, self.attempts, write_u32);
+        dest.write_u32::<FileOrder>(self.retries)?;
+        dest.write_u32::<FileOrder>(self.clears)?;
+        dest.write_u32::<FileOrder>(self.continues)?;
+        dest.write_u32::<FileOrder>(self.practices)
+    }
 }
 
 access_by_shot! {
@@ -161,6 +197,7 @@ impl_getters! {
 
 #[derive(Debug, Clone)]
 pub struct HighScore {
+    unknown: u32,
     score: u32,
     slow: f32,
     shot_type: Th07Shot,
@@ -168,6 +205,7 @@ pub struct HighScore {
     progress: StageProgress<Touhou7>,
     name: [u8; 9],
     date: ShortDate,
+    date_padding: u8,
     continues: u16,
 }
 
@@ -177,10 +215,9 @@ impl HighScore {
     }
 
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
-        src.read_u32::<LittleEndian>()?;
-
-        let score = src.read_u32::<LittleEndian>()?;
-        let slow = src.read_f32::<LittleEndian>()?;
+        let unknown = src.read_u32::<FileOrder>()?;
+        let score = src.read_u32::<FileOrder>()?;
+        let slow = src.read_f32::<FileOrder>()?;
         let shot_type = read_try_into!(u8 as Th07Shot : src.read_u8()?)?;
         let difficulty = read_try_into!(u8 as Difficulty : src.read_u8()?)?;
 
@@ -206,10 +243,14 @@ impl HighScore {
         let mut name = [0; 9];
         src.read_exact(&mut name)?;
 
-        let date = ShortDate::read_from(src)?;
-        let continues = src.read_u16::<LittleEndian>()?;
+        let mut date = [0; 6];
+        src.read_exact(&mut date)?;
+        let date_padding = date[5];
+        let date = ShortDate::read_from(&mut &date[..])?;
+        let continues = src.read_u16::<FileOrder>()?;
 
         Ok(HighScore {
+            unknown,
             score,
             slow,
             shot_type,
@@ -217,9 +258,35 @@ impl HighScore {
             progress,
             name,
             date,
+            date_padding,
             continues,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        let progress = match self.progress {
+            StageProgress::NotStarted => 0,
+            StageProgress::LostAt(stage) => u8::from(stage.unwrap()) + 1,
+            StageProgress::AllClear => 99,
+            StageProgress::StageCleared(_) => {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("cannot store stage progress value {}", self.progress),
+                ));
+            }
+        };
+
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        dest.write_u32::<FileOrder>(self.score)?;
+        dest.write_f32::<FileOrder>(self.slow)?;
+        dest.write_u8(self.shot_type.into())?;
+        dest.write_u8(self.difficulty.into())?;
+        dest.write_u8(progress)?;
+        dest.write_all(&self.name)?;
+        write!(dest, "{}", self.date)?;
+        dest.write_u8(self.date_padding)?;
+        dest.write_u16::<FileOrder>(self.continues)
+    }
 }
 
 impl_getters! {
@@ -235,9 +302,11 @@ impl_getters! {
 
 #[derive(Debug, Clone, Copy)]
 pub struct ClearData {
+    unknown: u32,
     story_flags: [u8; 6],
     practice_flags: [u8; 6],
     shot_type: Th07Shot,
+    shot_type_padding: [u8; 3],
 }
 
 impl ClearData {
@@ -245,17 +314,30 @@ impl ClearData {
         let mut story_flags = [0; 6];
         let mut practice_flags = [0; 6];
 
-        src.read_u32::<LittleEndian>()?;
+        let mut shot_type_padding = [0; 3];
+
+        let unknown = src.read_u32::<FileOrder>()?;
         src.read_exact(&mut story_flags)?;
         src.read_exact(&mut practice_flags)?;
-        let shot_type = read_try_into!(u8 as Th07Shot : src.read_u32::<LittleEndian>()? as u8)?;
+        let shot_type = read_try_into!(u8 as Th07Shot : src.read_u8()?)?;
+        src.read_exact(&mut shot_type_padding)?;
 
         Ok(ClearData {
+            unknown,
             story_flags,
             practice_flags,
             shot_type,
+            shot_type_padding,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        dest.write_all(&self.story_flags)?;
+        dest.write_all(&self.practice_flags)?;
+        dest.write_u8(self.shot_type.into())?;
+        dest.write_all(&self.shot_type_padding)
+    }
 }
 
 impl_getters! {
@@ -265,10 +347,28 @@ impl_getters! {
 
 access_by_difficulty! { ClearData, story_flags: u8, practice_flags: u8 }
 
+impl ClearRecord<Touhou7> for ClearData {
+    fn shot_type(&self) -> ShotType<Touhou7> {
+        ShotType::new(self.shot_type)
+    }
+
+    fn cleared(&self, difficulty: &DifficultyWrapper<Touhou7>, practice: bool) -> bool {
+        let flag = if practice {
+            self.practice_flags(difficulty)
+        } else {
+            self.story_flags(difficulty)
+        };
+
+        flag != 0
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct SpellCardData {
+    unknown: u32,
     max_bonuses: [u32; 7],
     card_id: u16,
+    padding: [u8; 2],
     card_name: [u8; 0x30],
     attempts: [u16; 7],
     captures: [u16; 7],
@@ -279,6 +379,11 @@ impl SpellCardData {
         &self.card_name
     }
 
+    /// Gets the spell card this record is for, or `None` if its stored ID is not a valid card.
+    pub fn try_card(&self) -> Option<SpellCard<Touhou7>> {
+        SpellId::new(self.card_id).ok().map(SpellCard::new)
+    }
+
     pub fn total_max_bonus(&self) -> u32 {
         self.max_bonuses[6]
     }
@@ -291,37 +396,84 @@ impl SpellCardData {
         self.captures[6]
     }
 
-    pub fn total_capture_rate(&self) -> f64 {
-        (self.total_captures() as f64) / (self.total_attempts() as f64)
+    /// Gets the capture rate for this card across all shot types, or `None` if it has never been attempted.
+    pub fn total_capture_rate(&self) -> Option<f64> {
+        let attempts = self.total_attempts();
+        (attempts > 0).then(|| (self.total_captures() as f64) / (attempts as f64))
     }
 
-    pub fn capture_rate(&self, key: &Th07Shot) -> f64 {
-        (self.captures(key) as f64) / (self.attempts(key) as f64)
+    /// Gets the capture rate for this card with the given shot type, or `None` if it has never been attempted.
+    pub fn capture_rate(&self, key: &Th07Shot) -> Option<f64> {
+        let attempts = self.attempts(key);
+        (attempts > 0).then(|| (self.captures(key) as f64) / (attempts as f64))
+    }
+
+    fn shot_index(key: &Th07Shot) -> usize {
+        u8::from(key) as usize
+    }
+
+    /// Sets the number of attempts on this card for a shot type, updating the overall total to match.
+    pub fn set_attempts(&mut self, key: &Th07Shot, value: u16) {
+        let idx = Self::shot_index(key);
+        self.attempts[6] = self.attempts[6].saturating_sub(self.attempts[idx]).saturating_add(value);
+        self.attempts[idx] = value;
+    }
+
+    /// Sets the number of captures of this card for a shot type, updating the overall total to match.
+    pub fn set_captures(&mut self, key: &Th07Shot, value: u16) {
+        let idx = Self::shot_index(key);
+        self.captures[6] = self.captures[6].saturating_sub(self.captures[idx]).saturating_add(value);
+        self.captures[idx] = value;
+    }
+
+    /// Sets the maximum bonus obtained on this card for a shot type, updating the overall maximum to match.
+    pub fn set_max_bonus(&mut self, key: &Th07Shot, value: u32) {
+        self.max_bonuses[Self::shot_index(key)] = value;
+        self.max_bonuses[6] = self.max_bonuses[..6].iter().copied().max().unwrap();
     }
 
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
         let mut card_name = [0; 0x30];
+        let mut padding = [0; 2];
 
-        src.read_u32::<LittleEndian>()?;
+        let unknown = src.read_u32::<FileOrder>()?;
 
-        let max_bonuses = read_array![src.read_u32::<LittleEndian>()?; 7];
-        let card_id: u16 = src.read_u16::<LittleEndian>()? + 1;
+        let max_bonuses = read_array![src.read_u32::<FileOrder>()?; 7];
+        let card_id = src
+            .read_u16::<FileOrder>()?
+            .checked_add(1)
+            .filter(|&id| SpellId::new(id).is_ok())
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "invalid spell card ID"))?;
 
-        src.read_u8()?;
+        padding[0] = src.read_u8()?;
         src.read_exact(&mut card_name)?;
-        src.read_u8()?;
+        padding[1] = src.read_u8()?;
 
-        let attempts = read_array![src.read_u16::<LittleEndian>()?; 7];
-        let captures = read_array![src.read_u16::<LittleEndian>()?; 7];
+        let attempts = read_array![src.read_u16::<FileOrder>()?; 7];
+        let captures = read_array![src.read_u16::<FileOrder>()?; 7];
 
         Ok(SpellCardData {
+            unknown,
             max_bonuses,
             card_id,
+            padding,
             card_name,
             attempts,
             captures,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        write_array!(dest, self.max_bonuses, write_u32);
+        dest.write_u16::<FileOrder>(self.card_id - 1)?;
+        dest.write_u8(self.padding[0])?;
+        dest.write_all(&self.card_name)?;
+        dest.write_u8(self.padding[1])?;
+        write_array!(dest, self.attempts, write_u16);
+        write_array!(dest, self.captures, write_u16);
+        Ok(())
+    }
 }
 
 impl_getters! {
@@ -342,7 +494,9 @@ impl SpellCardRecord<Touhou7> for SpellCardData {
     }
 
     fn card(&self) -> SpellCard<Touhou7> {
-        SpellCard::new((self.card_id as u32).try_into().unwrap())
+        // read_from only accepts valid card IDs
+        self.try_card()
+            .expect("spell card record should have a valid ID")
     }
 
     fn attempts(&self, shot: &ShotType<Touhou7>) -> u32 {
@@ -372,11 +526,13 @@ impl SpellCardRecord<Touhou7> for SpellCardData {
 
 #[derive(Debug, Clone, Copy)]
 pub struct PracticeData {
+    unknown: u32,
     attempts: u32,
     high_score: u32,
     shot_type: Th07Shot,
     difficulty: Difficulty,
     stage: Stage,
+    padding: u8,
 }
 
 impl_getters! {
@@ -390,22 +546,34 @@ impl_getters! {
 
 impl PracticeData {
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
-        src.read_u32::<LittleEndian>()?;
-        let attempts = src.read_u32::<LittleEndian>()?;
-        let high_score = src.read_u32::<LittleEndian>()?;
+        let unknown = src.read_u32::<FileOrder>()?;
+        let attempts = src.read_u32::<FileOrder>()?;
+        let high_score = src.read_u32::<FileOrder>()?;
         let shot_type = read_try_into!(u8 as Th07Shot : src.read_u8()?)?;
         let difficulty = read_try_into!(u8 as Difficulty : src.read_u8()?)?;
         let stage = read_try_into!(u8 as Stage : src.read_u8()?)?;
-        src.read_u8()?;
+        let padding = src.read_u8()?;
 
         Ok(PracticeData {
+            unknown,
             attempts,
             high_score,
             shot_type,
             difficulty,
             stage,
+            padding,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        dest.write_u32::<FileOrder>(self.attempts)?;
+        dest.write_u32::<FileOrder>(self.high_score)?;
+        dest.write_u8(self.shot_type.into())?;
+        dest.write_u8(self.difficulty.into())?;
+        dest.write_u8(self.stage.into())?;
+        dest.write_u8(self.padding)
+    }
 }
 
 impl PracticeRecord<Touhou7> for PracticeData {
@@ -432,6 +600,7 @@ impl PracticeRecord<Touhou7> for PracticeData {
 
 #[derive(Debug, Clone)]
 pub struct PlayData {
+    unknown: u32,
     running_time: StoredTime,
     play_time: StoredTime,
     play_counts: Box<[PlayCount; 7]>,
@@ -454,8 +623,7 @@ impl PlayData {
     }
 
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
-        src.read_u32::<LittleEndian>()?;
-
+        let unknown = src.read_u32::<FileOrder>()?;
         let running_time = StoredTime::read_from(src)?;
         let play_time = StoredTime::read_from(src)?;
         let mut play_counts = Box::new([PlayCount::default(); 7]);
@@ -465,11 +633,24 @@ impl PlayData {
         }
 
         Ok(PlayData {
+            unknown,
             running_time,
             play_time,
             play_counts,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        self.running_time.write_to(dest)?;
+        self.play_time.write_to(dest)?;
+
+        for play_count in self.play_counts.iter() {
+            play_count.write_to(dest)?;
+        }
+
+        Ok(())
+    }
 }
 
 impl_getters! {
@@ -478,69 +659,15 @@ impl_getters! {
     play_time: StoredTime
 }
 
-#[derive(Debug)]
-pub struct Decryptor<R> {
-    src: R,
-    key: u8,
-    checksum: u16,
-    target_checksum: u16,
-}
-
-impl<R: ReadBytesExt> Decryptor<R> {
-    pub fn new(mut src: R) -> Result<Self, io::Error> {
-        src.read_u8()?;
-
-        let mut key = src.read_u8()?.rotate_left(3);
-        let mut target_checksum = [0; 2];
-        src.read_exact(&mut target_checksum)?;
-
-        target_checksum[0] ^= key;
-        key = key.wrapping_add(target_checksum[0]).rotate_left(3);
-
-        target_checksum[1] ^= key;
-        key = key.wrapping_add(target_checksum[1]).rotate_left(3);
-
-        let target_checksum = u16::from_le_bytes(target_checksum);
-
-        Ok(Self {
-            src,
-            key,
-            checksum: 0,
-            target_checksum,
-        })
-    }
-
-    pub fn is_valid(&self) -> bool {
-        self.checksum == self.target_checksum
-    }
-
-    pub fn checksum(&self) -> u16 {
-        self.checksum
-    }
-
-    pub fn target_checksum(&self) -> u16 {
-        self.target_checksum
-    }
-}
-
-impl<R: Read> Read for Decryptor<R> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let n = self.src.read(buf)?;
-
-        for x in &mut buf[..n] {
-            *x ^= self.key;
-            self.key = self.key.wrapping_add(*x).rotate_left(3);
-            self.checksum = self.checksum.wrapping_add((*x) as u16);
-        }
-
-        Ok(n)
-    }
-}
+/// Decrypts PCB score files; see [`ChecksumDecryptor`].
+pub type Decryptor<R> = ChecksumDecryptor<R>;
 
 #[derive(Debug, Clone, Copy)]
 pub struct FileHeader {
     version: u16,
+    unknown1: u16,
     header_sz: u32,
+    unknown2: u32,
     decomp_full_sz: usize,
     decomp_body_sz: usize,
     encoded_body_sz: usize,
@@ -548,24 +675,36 @@ pub struct FileHeader {
 
 impl FileHeader {
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
-        let version = src.read_u16::<LittleEndian>()?;
-        src.read_u16::<LittleEndian>()?;
+        let version = src.read_u16::<FileOrder>()?;
+        let unknown1 = src.read_u16::<FileOrder>()?;
 
-        let header_sz = src.read_u32::<LittleEndian>()?;
-        src.read_u32::<LittleEndian>()?;
+        let header_sz = src.read_u32::<FileOrder>()?;
+        let unknown2 = src.read_u32::<FileOrder>()?;
 
-        let decomp_full_sz = src.read_u32::<LittleEndian>()? as usize;
-        let decomp_body_sz = src.read_u32::<LittleEndian>()? as usize;
-        let encoded_body_sz = src.read_u32::<LittleEndian>()? as usize;
+        let decomp_full_sz = src.read_u32::<FileOrder>()? as usize;
+        let decomp_body_sz = src.read_u32::<FileOrder>()? as usize;
+        let encoded_body_sz = src.read_u32::<FileOrder>()? as usize;
 
         Ok(Self {
             version,
+            unknown1,
             header_sz,
+            unknown2,
             decomp_full_sz,
             decomp_body_sz,
             encoded_body_sz,
         })
     }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u16::<FileOrder>(self.version)?;
+        dest.write_u16::<FileOrder>(self.unknown1)?;
+        dest.write_u32::<FileOrder>(self.header_sz)?;
+        dest.write_u32::<FileOrder>(self.unknown2)?;
+        dest.write_u32::<FileOrder>(self.decomp_full_sz as u32)?;
+        dest.write_u32::<FileOrder>(self.decomp_body_sz as u32)?;
+        dest.write_u32::<FileOrder>(self.encoded_body_sz as u32)
+    }
 }
 
 impl_getters! {
@@ -577,23 +716,84 @@ impl_getters! {
     encoded_body_sz: usize
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct LastNameData {
+    unknown: u32,
+    name: [u8; 12],
+}
+
+impl LastNameData {
+    /// Gets the last name entered by the player, decoded from CP932.
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
+    pub fn raw_name(&self) -> &[u8; 12] {
+        &self.name
+    }
+
+    pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
+        let mut name = [0; 12];
+        let unknown = src.read_u32::<FileOrder>()?;
+        src.read_exact(&mut name)?;
+        Ok(Self { unknown, name })
+    }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_u32::<FileOrder>(self.unknown)?;
+        dest.write_all(&self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VersionData {
+    unknown: [u8; 4],
+    version: [u8; 6],
+    trailing: [u8; 10],
+}
+
+impl VersionData {
+    pub fn version(&self) -> &[u8; 6] {
+        &self.version
+    }
+
+    pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
+        let mut ret = Self {
+            unknown: [0; 4],
+            version: [0; 6],
+            trailing: [0; 10],
+        };
+
+        src.read_exact(&mut ret.unknown)?;
+        src.read_exact(&mut ret.version)?;
+        src.read_exact(&mut ret.trailing)?;
+        Ok(ret)
+    }
+
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        dest.write_all(&self.unknown)?;
+        dest.write_all(&self.version)?;
+        dest.write_all(&self.trailing)
+    }
+}
+
 #[derive(Clone)]
 pub enum Segment {
-    Header,
+    Header(Box<[u8]>),
     HighScore(HighScore),
     Clear(ClearData),
     SpellCard(SpellCardData),
     PracticeScore(PracticeData),
     PlayStatus(PlayData),
-    LastName([u8; 12]),
-    Version([u8; 6]),
+    LastName(LastNameData),
+    Version(VersionData),
     Unknown([u8; 4], usize, usize, Box<[u8]>),
 }
 
 impl Segment {
     pub fn signature(&self) -> &[u8; 4] {
         match self {
-            Self::Header => b"TH7K",
+            Self::Header(_) => b"TH7K",
             Self::HighScore(_) => b"HSCR",
             Self::Clear(_) => b"CLRD",
             Self::SpellCard(_) => b"CATK",
@@ -606,59 +806,104 @@ impl Segment {
     }
 
     pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Option<Self>, io::Error> {
-        let mut signature = [0; 4];
-        return_none_on_eof!(src.read_exact(&mut signature));
-        let size1 = return_none_on_eof!(src.read_u16::<LittleEndian>()) as usize;
-        let size2 = return_none_on_eof!(src.read_u16::<LittleEndian>()) as usize;
+        let (signature, size1, size2, data) = loop {
+            let mut signature = [0; 4];
+            return_none_on_eof!(src.read_exact(&mut signature));
+            let size1 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+            let size2 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+
+            /* sizes include the 8-byte segment header, so anything smaller can only be garbage or padding */
+            if size1 < 8 {
+                return Ok(None);
+            } else if size1 == 8 {
+                continue;
+            }
+
+            let mut data = vec![0u8; size1 - 8];
+            return_none_on_eof!(src.read_exact(&mut data));
+            break (signature, size1, size2, data);
+        };
 
-        if size1 <= 8 {
-            return Ok(None);
+        Self::from_parts(signature, size1, size2, data).map(Some)
+    }
+
+    /// Serializes this segment, including its signature and size fields.
+    ///
+    /// Fields that are not exposed by the parsed segment types are kept from the original file,
+    /// so writing an unmodified segment reproduces the bytes it was read from.
+    pub fn write_to<W: WriteBytesExt>(&self, dest: &mut W) -> Result<(), io::Error> {
+        if let Self::Unknown(sig, size1, size2, data) = self {
+            dest.write_all(sig)?;
+            dest.write_u16::<FileOrder>(*size1 as u16)?;
+            dest.write_u16::<FileOrder>(*size2 as u16)?;
+            return dest.write_all(data);
         }
 
-        let mut data = vec![0u8; size1 - 8];
-        return_none_on_eof!(src.read_exact(&mut data));
-
-        let mut reader = Cursor::new(data);
-        match &signature {
-            b"TH7K" => Ok(Self::Header),
-            b"HSCR" => HighScore::read_from(&mut reader).map(Self::HighScore),
-            b"CLRD" => ClearData::read_from(&mut reader).map(Self::Clear),
-            b"CATK" => SpellCardData::read_from(&mut reader).map(Self::SpellCard),
-            b"PSCR" => PracticeData::read_from(&mut reader).map(Self::PracticeScore),
-            b"PLST" => PlayData::read_from(&mut reader).map(Self::PlayStatus),
-            b"LSNM" => {
-                let mut name = [0; 12];
-                src.read_u32::<LittleEndian>()?;
-                src.read_exact(&mut name)?;
-                Ok(Self::LastName(name))
-            }
-            b"VRSM" => {
-                let mut version = [0; 6];
-                src.read_u16::<LittleEndian>()?;
-                src.read_u16::<LittleEndian>()?;
-                src.read_exact(&mut version)?;
-                src.read_u32::<LittleEndian>()?;
-                src.read_u32::<LittleEndian>()?;
-                src.read_u16::<LittleEndian>()?;
-                Ok(Self::Version(version))
-            }
-            _ => Ok(Self::Unknown(
-                signature,
-                size1,
-                size2,
-                reader.into_inner().into(),
-            )),
+        let mut data = Vec::new();
+        match self {
+            Self::Header(raw) => data.write_all(raw)?,
+            Self::HighScore(d) => d.write_to(&mut data)?,
+            Self::Clear(d) => d.write_to(&mut data)?,
+            Self::SpellCard(d) => d.write_to(&mut data)?,
+            Self::PracticeScore(d) => d.write_to(&mut data)?,
+            Self::PlayStatus(d) => d.write_to(&mut data)?,
+            Self::LastName(d) => d.write_to(&mut data)?,
+            Self::Version(d) => d.write_to(&mut data)?,
+            Self::Unknown(..) => unreachable!(),
+        }
+
+        let size: u16 = (data.len() + 8).try_into().map_err(|e| {
+            io::Error::new(ErrorKind::InvalidInput, e)
+        })?;
+
+        dest.write_all(self.signature())?;
+        dest.write_u16::<FileOrder>(size)?;
+        dest.write_u16::<FileOrder>(size)?;
+        dest.write_all(&data)
+    }
+}
+
+impl ScoreSegment for Segment {
+    fn from_parts(
+        signature: [u8; 4],
+        size1: usize,
+        size2: usize,
+        data: Vec<u8>,
+    ) -> io::Result<Self> {
+        /* parsed segments are written back with matching sizes, so keep anything else verbatim */
+        if size1 != size2 {
+            return Ok(Self::Unknown(signature, size1, size2, data.into()));
+        }
+
+        let mut reader = Cursor::new(&data[..]);
+        let segment = match &signature {
+            b"TH7K" => return Ok(Self::Header(data.into())),
+            b"HSCR" => HighScore::read_from(&mut reader).map(Self::HighScore)?,
+            b"CLRD" => ClearData::read_from(&mut reader).map(Self::Clear)?,
+            b"CATK" => SpellCardData::read_from(&mut reader).map(Self::SpellCard)?,
+            b"PSCR" => PracticeData::read_from(&mut reader).map(Self::PracticeScore)?,
+            b"PLST" => PlayData::read_from(&mut reader).map(Self::PlayStatus)?,
+            b"LSNM" => LastNameData::read_from(&mut reader).map(Self::LastName)?,
+            b"VRSM" => VersionData::read_from(&mut reader).map(Self::Version)?,
+            _ => return Ok(Self::Unknown(signature, size1, size2, data.into())),
+        };
+
+        /* likewise for segments with trailing data that the parsed types don't cover */
+        if (reader.position() as usize) < data.len() {
+            Ok(Self::Unknown(signature, size1, size2, data.into()))
+        } else {
+            Ok(segment)
         }
-        .map(Some)
     }
 }
 
 impl Debug for Segment {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Header => f
+            Self::Header(d) => f
                 .debug_struct("Segment::Header")
                 .field("signature", self.signature())
+                .field("data", &format!("[{} bytes]", d.len()))
                 .finish(),
             Self::HighScore(d) => f
                 .debug_struct("Segment::HighScore")
@@ -723,13 +968,144 @@ impl<R: Read> ScoreReader<R> {
     pub fn header(&self) -> &FileHeader {
         &self.header
     }
+
+    /// Gets whether the score file's checksum matched its contents.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.src.get_ref().checksum_valid()
+    }
+
+    /// Gets whether the amount of decompressed data matched the size declared in the file header.
+    ///
+    /// Files that are truncated or otherwise corrupted will often decompress to fewer bytes than expected,
+    /// which would otherwise only show up as missing segments.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn decompressed_size_valid(&self) -> Option<bool> {
+        self.src
+            .get_ref()
+            .checksum_valid()
+            .map(|_| self.src.bytes_produced() == self.header.decomp_body_sz())
+    }
 }
 
 impl<R: Read> Iterator for ScoreReader<R> {
     type Item = Result<Segment, io::Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Segment::read_from(&mut self.src).transpose()
+        match Segment::read_from(&mut self.src).transpose() {
+            None => {
+                /* consume any trailing data so that the checksum covers the entire file */
+                io::copy(self.src.get_mut(), &mut io::sink())
+                    .err()
+                    .map(Err)
+            }
+            ret => ret,
+        }
+    }
+}
+
+/// Serializes segments into an encrypted and compressed Touhou 7 score file.
+///
+/// Segments are buffered in memory as they're written; the actual score file is only produced
+/// once [`finish`](`Self::finish`) is called, since the file header and checksum depend on the
+/// entire contents of the file.
+#[derive(Debug)]
+pub struct ScoreWriter<W: Write> {
+    dest: W,
+    version: u16,
+    unknown1: u16,
+    unknown2: u32,
+    key: u8,
+    body: Vec<u8>,
+}
+
+impl<W: Write> ScoreWriter<W> {
+    const HEADER_SIZE: u32 = 0x1C;
+
+    /// Create a new writer that will output a score file with the given version number.
+    ///
+    /// When rewriting an existing file, the version number can be taken from [`FileHeader::version`].
+    pub fn new(dest: W, version: u16) -> Self {
+        Self {
+            dest,
+            version,
+            unknown1: 0,
+            unknown2: 0,
+            key: 0,
+            body: Vec::new(),
+        }
+    }
+
+    /// Create a new writer that reuses the version number and unknown fields from an existing file's header.
+    ///
+    /// The size fields of `header` are ignored, since they are recomputed from the written segments.
+    pub fn from_header(dest: W, header: &FileHeader) -> Self {
+        Self {
+            unknown1: header.unknown1,
+            unknown2: header.unknown2,
+            ..Self::new(dest, header.version)
+        }
+    }
+
+    /// Sets the initial encryption key used for the output file.
+    pub fn with_key(mut self, key: u8) -> Self {
+        self.key = key;
+        self
+    }
+
+    pub fn write_segment(&mut self, segment: &Segment) -> Result<(), io::Error> {
+        segment.write_to(&mut self.body)
+    }
+
+    pub fn write_segments<'a, I>(&mut self, segments: I) -> Result<(), io::Error>
+    where
+        I: IntoIterator<Item = &'a Segment>,
+    {
+        for segment in segments {
+            self.write_segment(segment)?;
+        }
+
+        Ok(())
+    }
+
+    /// Compresses and encrypts all written segments, writes the complete file, and returns the underlying writer.
+    pub fn finish(mut self) -> Result<W, io::Error> {
+        let mut compressor = StreamCompressor::new(Vec::new());
+        compressor.write_all(&self.body)?;
+        let encoded_body = compressor.finish()?;
+
+        let header = FileHeader {
+            version: self.version,
+            unknown1: self.unknown1,
+            header_sz: Self::HEADER_SIZE,
+            unknown2: self.unknown2,
+            decomp_full_sz: (Self::HEADER_SIZE as usize) + self.body.len(),
+            decomp_body_sz: self.body.len(),
+            encoded_body_sz: encoded_body.len(),
+        };
+
+        let mut plain = Vec::with_capacity(encoded_body.len() + 24);
+        header.write_to(&mut plain)?;
+        plain.extend_from_slice(&encoded_body);
+
+        let checksum = plain
+            .iter()
+            .fold(0u16, |acc, x| acc.wrapping_add(*x as u16));
+
+        let mut out = Vec::with_capacity(plain.len() + 4);
+        out.push(0);
+        out.push(self.key);
+
+        let mut key = self.key.rotate_left(3);
+        for x in checksum.to_le_bytes().into_iter().chain(plain) {
+            out.push(x ^ key);
+            key = key.wrapping_add(x).rotate_left(3);
+        }
+
+        self.dest.write_all(&out)?;
+        Ok(self.dest)
     }
 }
 
@@ -737,23 +1113,146 @@ impl<R: Read> Iterator for ScoreReader<R> {
 pub struct ScoreFile {
     cards: Vec<SpellCardData>,
     practices: Vec<PracticeData>,
+    clears: Vec<ClearData>,
 }
 
 impl ScoreFile {
     pub fn new<R: Read>(src: R) -> Result<Self, io::Error> {
+        Self::new_with_progress(src, |_| {})
+    }
+
+    /// Like [`Self::new`], but calls `on_segment` after each segment of the file is parsed.
+    ///
+    /// The callback receives the total number of segments read so far, which can be used to report progress.
+    pub fn new_with_progress<R: Read, F: FnMut(usize)>(
+        src: R,
+        mut on_segment: F,
+    ) -> Result<Self, io::Error> {
         let mut cards = Vec::with_capacity(141);
         let mut practices = Vec::new();
+        let mut clears = Vec::new();
 
-        for segment in ScoreReader::new(src)? {
+        for (count, segment) in ScoreReader::new(src)?.enumerate() {
             match segment {
                 Ok(Segment::SpellCard(data)) => cards.push(data),
                 Ok(Segment::PracticeScore(data)) => practices.push(data),
-                Ok(_) => continue,
+                Ok(Segment::Clear(data)) => clears.push(data),
+                Ok(_) => {}
                 Err(e) => return Err(e),
             }
+
+            on_segment(count + 1);
+        }
+
+        Ok(Self {
+            cards,
+            practices,
+            clears,
+        })
+    }
+
+    /// Gets the clear flags recorded for each shot type.
+    pub fn clear_records(&self) -> &[ClearData] {
+        &self.clears[..]
+    }
+
+    /// Reads every segment from a score file, rather than just spell card and practice records.
+    pub fn read_full<R: Read>(src: R) -> Result<FullScoreFile, io::Error> {
+        let mut ret = FullScoreFile {
+            high_scores: Vec::new(),
+            clears: Vec::new(),
+            cards: Vec::with_capacity(141),
+            practices: Vec::new(),
+            play_data: None,
+            last_name: None,
+            version: None,
+            unknown: Vec::new(),
+        };
+
+        for segment in ScoreReader::new(src)? {
+            match segment? {
+                Segment::Header(_) => continue,
+                Segment::HighScore(data) => ret.high_scores.push(data),
+                Segment::Clear(data) => ret.clears.push(data),
+                Segment::SpellCard(data) => ret.cards.push(data),
+                Segment::PracticeScore(data) => ret.practices.push(data),
+                Segment::PlayStatus(data) => ret.play_data = Some(data),
+                Segment::LastName(data) => ret.last_name = Some(*data.raw_name()),
+                Segment::Version(data) => ret.version = Some(*data.version()),
+                other @ Segment::Unknown(..) => ret.unknown.push(other),
+            }
         }
 
-        Ok(Self { cards, practices })
+        Ok(ret)
+    }
+}
+
+/// The full contents of a score file, as read by [`ScoreFile::read_full`].
+#[derive(Debug, Clone)]
+pub struct FullScoreFile {
+    high_scores: Vec<HighScore>,
+    clears: Vec<ClearData>,
+    cards: Vec<SpellCardData>,
+    practices: Vec<PracticeData>,
+    play_data: Option<PlayData>,
+    last_name: Option<[u8; 12]>,
+    version: Option<[u8; 6]>,
+    unknown: Vec<Segment>,
+}
+
+impl FullScoreFile {
+    pub fn high_scores(&self) -> &[HighScore] {
+        &self.high_scores[..]
+    }
+
+    /// Gets the clear flags recorded for each shot type.
+    pub fn clear_data(&self) -> &[ClearData] {
+        &self.clears[..]
+    }
+
+    /// Gets the clear flags recorded for a specific shot type, if any.
+    pub fn clear_data_for(&self, shot: &Th07Shot) -> Option<&ClearData> {
+        self.clears.iter().find(|data| data.shot_type() == *shot)
+    }
+
+    pub fn play_data(&self) -> Option<&PlayData> {
+        self.play_data.as_ref()
+    }
+
+    /// Gets the total time spent playing the game, if the file contains play status data.
+    pub fn total_play_time(&self) -> Option<StoredTime> {
+        self.play_data.as_ref().map(PlayData::play_time)
+    }
+
+    /// Gets the last name entered by the player, decoded from CP932.
+    pub fn last_name(&self) -> Option<Cow<'_, str>> {
+        self.last_name.as_ref().map(|name| decode_cp932(name))
+    }
+
+    pub fn raw_last_name(&self) -> Option<&[u8; 12]> {
+        self.last_name.as_ref()
+    }
+
+    pub fn version(&self) -> Option<&[u8; 6]> {
+        self.version.as_ref()
+    }
+
+    /// Gets any segments with signatures that weren't recognized.
+    pub fn unknown_segments(&self) -> &[Segment] {
+        &self.unknown[..]
+    }
+}
+
+impl crate::score::ScoreFile<Touhou7> for FullScoreFile {
+    type SpellCardRecord = SpellCardData;
+    type PracticeRecord = PracticeData;
+
+    fn spell_cards(&self) -> &[SpellCardData] {
+        &self.cards[..]
+    }
+
+    fn practice_records(&self) -> &[PracticeData] {
+        &self.practices[..]
     }
 }
 
@@ -769,3 +1268,155 @@ impl crate::score::ScoreFile<Touhou7> for ScoreFile {
         &self.practices[..]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(signature: &[u8; 4], size2_delta: u16, body: &[u8]) -> Vec<u8> {
+        let size = (body.len() + 8) as u16;
+        let mut ret = signature.to_vec();
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret.extend_from_slice(&(size + size2_delta).to_le_bytes());
+        ret.extend_from_slice(body);
+        ret
+    }
+
+    /// Builds one of each segment type, filling every field the parsers don't interpret with distinctive junk.
+    fn sample_segments() -> Vec<Vec<u8>> {
+        let mut hscr = vec![0xA1, 0xA2, 0xA3, 0xA4];
+        hscr.extend_from_slice(&123_456_780u32.to_le_bytes());
+        hscr.extend_from_slice(&1.25f32.to_le_bytes());
+        hscr.extend_from_slice(&[2, 3, 99]);
+        hscr.extend_from_slice(b"PLAYER  \0");
+        hscr.extend_from_slice(b"07/21\xEE");
+        hscr.extend_from_slice(&3u16.to_le_bytes());
+
+        let mut clrd = vec![0xB1, 0xB2, 0xB3, 0xB4];
+        clrd.extend_from_slice(&[1, 1, 1, 0, 0, 0]);
+        clrd.extend_from_slice(&[1, 0, 1, 0, 1, 0]);
+        clrd.extend_from_slice(&[4, 0xB5, 0xB6, 0xB7]);
+
+        let mut catk = vec![0xC1, 0xC2, 0xC3, 0xC4];
+        for i in 0..7u32 {
+            catk.extend_from_slice(&(1000 * i).to_le_bytes());
+        }
+        catk.extend_from_slice(&4u16.to_le_bytes());
+        catk.push(0xC5);
+        catk.extend_from_slice(&[0x41; 0x30]);
+        catk.push(0xC6);
+        for i in 0..14u16 {
+            catk.extend_from_slice(&i.to_le_bytes());
+        }
+
+        let mut pscr = vec![0xD1, 0xD2, 0xD3, 0xD4];
+        pscr.extend_from_slice(&5u32.to_le_bytes());
+        pscr.extend_from_slice(&9_876_540u32.to_le_bytes());
+        pscr.extend_from_slice(&[1, 2, 3, 0xD5]);
+
+        let mut plst = vec![0xE1, 0xE2, 0xE3, 0xE4];
+        for i in 0..(8 + 7 * 11u32) {
+            plst.extend_from_slice(&i.to_le_bytes());
+        }
+
+        let mut lsnm = vec![0xF1, 0xF2, 0xF3, 0xF4];
+        lsnm.extend_from_slice(b"NAME\0\0\0\0\0\0\0\0");
+
+        let mut vrsm = vec![0x01, 0x02, 0x03, 0x04];
+        vrsm.extend_from_slice(b"1.00b\0");
+        vrsm.extend_from_slice(&[0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E]);
+
+        vec![
+            segment(b"TH7K", 0, &[0x10, 0x00, 0x00, 0x00, 0x7F, 0x7E]),
+            segment(b"HSCR", 0, &hscr),
+            segment(b"CLRD", 0, &clrd),
+            segment(b"CATK", 0, &catk),
+            segment(b"PSCR", 0, &pscr),
+            segment(b"PLST", 0, &plst),
+            segment(b"LSNM", 0, &lsnm),
+            segment(b"VRSM", 0, &vrsm),
+            segment(b"ABCD", 4, &[1, 2, 3, 4]),
+        ]
+    }
+
+    #[test]
+    fn segments_round_trip() {
+        for raw in sample_segments() {
+            let segment = Segment::read_from(&mut Cursor::new(&raw)).unwrap().unwrap();
+
+            if &raw[..4] != b"ABCD" {
+                assert!(
+                    !matches!(segment, Segment::Unknown(..)),
+                    "{:?} was not parsed",
+                    segment
+                );
+            }
+
+            let mut written = Vec::new();
+            segment.write_to(&mut written).unwrap();
+            assert_eq!(written, raw, "{:?} did not round-trip", segment);
+        }
+    }
+
+    #[test]
+    fn mismatched_segments_are_kept_verbatim() {
+        let mut pscr = vec![0; 16];
+        pscr[12] = 1;
+
+        let mut with_trailing = pscr.clone();
+        with_trailing.extend_from_slice(&[0xAA, 0xBB]);
+
+        for raw in [
+            segment(b"PSCR", 2, &pscr),
+            segment(b"PSCR", 0, &with_trailing),
+        ] {
+            let segment = Segment::read_from(&mut Cursor::new(&raw)).unwrap().unwrap();
+            assert!(matches!(segment, Segment::Unknown(..)));
+
+            let mut written = Vec::new();
+            segment.write_to(&mut written).unwrap();
+            assert_eq!(written, raw);
+        }
+    }
+
+    #[test]
+    fn score_file_round_trip() {
+        let raw = sample_segments();
+        let body = raw.concat();
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&0x0Bu16.to_le_bytes());
+        header.extend_from_slice(&0x1234u16.to_le_bytes());
+        header.extend_from_slice(&0x1Cu32.to_le_bytes());
+        header.extend_from_slice(&0x89AB_CDEFu32.to_le_bytes());
+        header.extend_from_slice(&[0; 12]);
+        let header = FileHeader::read_from(&mut Cursor::new(header)).unwrap();
+
+        let segments: Vec<Segment> = raw
+            .iter()
+            .map(|raw| Segment::read_from(&mut Cursor::new(raw)).unwrap().unwrap())
+            .collect();
+
+        let mut writer = ScoreWriter::from_header(Vec::new(), &header).with_key(0x5A);
+        writer.write_segments(&segments).unwrap();
+        let file = writer.finish().unwrap();
+
+        let mut reader = ScoreReader::new(Cursor::new(file)).unwrap();
+        let reread: Vec<Segment> = reader.by_ref().collect::<Result<_, _>>().unwrap();
+        assert_eq!(reader.checksum_valid(), Some(true));
+        assert_eq!(reader.decompressed_size_valid(), Some(true));
+
+        let mut original_header = Vec::new();
+        let mut new_header = Vec::new();
+        header.write_to(&mut original_header).unwrap();
+        reader.header().write_to(&mut new_header).unwrap();
+        assert_eq!(original_header[..12], new_header[..12]);
+        assert_eq!(reader.header().decomp_body_sz(), body.len());
+
+        let mut written = Vec::new();
+        for segment in &reread {
+            segment.write_to(&mut written).unwrap();
+        }
+        assert_eq!(written, body);
+    }
+}
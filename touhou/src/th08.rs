@@ -64,10 +64,15 @@ define_game! {
             Two: "Stage 2",
             Three: "Stage 3",
             FourA: "Stage 4 Uncanny",
+            #[branch]
             FourB: "Stage 4 Powerful",
             Five: "Stage 5",
+            #[final_stage]
             FinalA,
+            #[final_stage]
+            #[branch]
             FinalB,
+            #[extra]
             Extra: "Extra Stage",
             LastWord
         }
@@ -158,13 +158,13 @@ define_locations! {
                 Nonspell,
                 Spells(164..=167),
                 Spells(168..=171),
-                LastSpell(
+                Survival(LastSpell(
                     172..=175,
                     176..=179,
                     180..=183,
                     184..=187,
                     188..=191
-                )
+                ))
             ]
         }
     }
@@ -242,3 +242,40 @@ pub(super) fn resolve(state: &super::state::RunState) -> Option<Location> {
 
     adj.or_else(|| Location::resolve(state))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{AllIterable, SpellCard};
+
+    fn spell_location(id: u16) -> Location {
+        Location::from_spell(SpellCard::new(SpellId::new(id).unwrap())).unwrap()
+    }
+
+    #[test]
+    fn end_of_imperishable_night_is_survival() {
+        for id in 172..=191 {
+            let location = spell_location(id);
+            assert!(matches!(location, Location::FinalB(_)));
+            assert!(location.is_survival(), "spell {} is not survival", id);
+        }
+    }
+
+    #[test]
+    fn other_spells_are_not_survival() {
+        for id in [1, 13, 147, 168, 171] {
+            assert!(
+                !spell_location(id).is_survival(),
+                "spell {} is survival",
+                id
+            );
+        }
+
+        assert_eq!(
+            Location::iter_all()
+                .filter(|location| location.is_survival())
+                .count(),
+            20
+        );
+    }
+}
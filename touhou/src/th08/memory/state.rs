@@ -2,7 +2,7 @@ use super::location;
 use super::process::MemoryAccess;
 use crate::memory::traits::*;
 use crate::memory::{
-    define_state_struct, ensure_float_within_range, try_into_or_mem_error,
+    define_state_struct, ensure_float_within_range, try_into_or_mem_error, GameMode,
     Location as LocationWrapper, MemoryReadError, SpellState,
 };
 use crate::th08::{SpellId, Touhou8};
@@ -272,6 +272,17 @@ pub enum GameType {
     SpellPractice(PlayerState, SpellState<Touhou8>, bool),
 }
 
+impl GameType {
+    /// Gets the mode this game is being played in.
+    pub fn game_mode(&self) -> GameMode<Touhou8> {
+        match self {
+            Self::Main(_) => GameMode::Story,
+            Self::StagePractice(state) => GameMode::StagePractice(state.stage.stage),
+            Self::SpellPractice(_, spell, _) => GameMode::SpellPractice(spell.spell()),
+        }
+    }
+}
+
 impl PauseState for GameType {
     fn paused(&self) -> bool {
         match self {
@@ -1,8 +1,10 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use std::io::{self, Cursor, ErrorKind, Read};
+use std::ops::RangeInclusive;
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::ReadBytesExt;
 
 use super::{Difficulty, ShotType, SpellId, Stage, Touhou8};
 use crate::score::*;
@@ -12,17 +14,15 @@ use crate::types::{
     Stage as StageWrapper, StageProgress,
 };
 
+/// The byte order used for multi-byte values in this game's score files.
+type FileOrder = byteorder::LittleEndian;
+
 fn read_raw_buffer<const N: usize, R: Read>(mut src: R) -> io::Result<Box<[u8]>> {
     let mut buf = vec![0u8; N];
     src.read_exact(&mut buf[..])?;
     Ok(buf.into())
 }
 
-fn skip_bytes<const N: usize, R: Read>(mut src: R) -> io::Result<()> {
-    let mut buf = [0u8; N];
-    src.read_exact(&mut buf[..])
-}
-
 macro_rules! read_then_skip_bytes {
     ($src:expr, $read:expr, $skip:literal) => {{
         let r = $read;
@@ -31,104 +31,13 @@ macro_rules! read_then_skip_bytes {
     }};
 }
 
-macro_rules! return_none_on_eof {
-    ($x:expr) => {
-        match $x {
-            Ok(v) => v,
-            Err(e) => {
-                if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                    return Ok(None);
-                } else {
-                    return Err(e.into());
-                }
-            }
-        }
-    };
-}
-
-fn try_into_or_io_error<T, U>(kind: ErrorKind) -> impl FnOnce(T) -> io::Result<U>
-where
-    T: TryInto<U>,
-    T::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
-{
-    move |val| {
-        val.try_into()
-            .map_err(move |error| io::Error::new(kind, error))
-    }
-}
-
-#[derive(Debug)]
-enum DecryptorState<R> {
-    ThcryptActive(ThCrypt<R>),
-    ReadRest(R),
-    Working,
-}
-
-#[derive(Debug)]
-pub struct Decryptor<R> {
-    crypt: DecryptorState<R>,
-    acc: u8,
-    checksum: u16,
-    target_checksum: u16,
-}
-
-impl<R: Read> Decryptor<R> {
-    fn new(src: R) -> io::Result<Self> {
-        let mut crypt = ThCrypt::new(src, 0x59, 0x79, 0x0100, Some(0x0C00));
-        let mut init_bytes = [0u8; 4];
-
-        crypt.read_exact(&mut init_bytes[..])?;
-
-        let mut acc = init_bytes[1].rotate_left(3);
-
-        init_bytes[2] ^= acc;
-        acc = acc.wrapping_add(init_bytes[2]).rotate_left(3);
-
-        init_bytes[3] ^= acc;
-        acc = acc.wrapping_add(init_bytes[3]).rotate_left(3);
-
-        Ok(Self {
-            crypt: DecryptorState::ThcryptActive(crypt),
-            acc,
-            target_checksum: u16::from_le_bytes([init_bytes[2], init_bytes[3]]),
-            checksum: 0,
-        })
-    }
-
-    pub fn valid_checksum(&self) -> bool {
-        self.checksum == self.target_checksum
-    }
-}
-
-impl<R: Read> Read for Decryptor<R> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let n = match std::mem::replace(&mut self.crypt, DecryptorState::Working) {
-            DecryptorState::ThcryptActive(mut crypt) => {
-                let n = crypt.read(buf)?;
-                if crypt.at_limit() {
-                    self.crypt = DecryptorState::ReadRest(crypt.unwrap());
-                } else {
-                    self.crypt = DecryptorState::ThcryptActive(crypt);
-                }
-                n
-            }
-            DecryptorState::ReadRest(mut src) => {
-                let n = src.read(buf)?;
-                self.crypt = DecryptorState::ReadRest(src);
-                n
-            }
-            DecryptorState::Working => unreachable!(),
-        };
-
-        for x in &mut buf[..n] {
-            *x ^= self.acc;
-            self.acc = self.acc.wrapping_add(*x).rotate_left(3);
-
-            self.checksum = self.checksum.wrapping_add((*x) as u16);
-        }
+/// Decrypts IN score files.
+///
+/// The start of the file is encrypted with [`ThCrypt`] on top of the cipher handled by [`ChecksumDecryptor`].
+pub type Decryptor<R> = ChecksumDecryptor<ThCryptPrefix<R>>;
 
-        Ok(n)
-    }
+fn decryptor<R: Read>(src: R) -> io::Result<Decryptor<R>> {
+    ChecksumDecryptor::new(ThCryptPrefix::new(src, 0x59, 0x79, 0x0100, 0x0C00))
 }
 
 #[derive(Debug, Clone)]
@@ -178,6 +87,11 @@ impl HighScore {
         &self.name[..]
     }
 
+    /// Gets the name entered for this score, decoded from CP932.
+    pub fn name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
     pub fn date(&self) -> ShortDate {
         self.date
     }
@@ -230,8 +144,8 @@ impl HighScore {
         skip_bytes::<4, &mut R>(&mut src)?;
 
         Ok(Self {
-            score: src.read_u32::<LittleEndian>()?,
-            slow: src.read_f32::<LittleEndian>()?,
+            score: src.read_u32::<FileOrder>()?,
+            slow: src.read_f32::<FileOrder>()?,
             shot_type: src
                 .read_u8()
                 .and_then(try_into_or_io_error(ErrorKind::InvalidData))?,
@@ -262,16 +176,16 @@ impl HighScore {
                 buf
             },
             date: ShortDate::read_from(&mut src)?,
-            continues: read_then_skip_bytes!(&mut src, src.read_u16::<LittleEndian>()?, 0x1C),
+            continues: read_then_skip_bytes!(&mut src, src.read_u16::<FileOrder>()?, 0x1C),
             player_num: read_then_skip_bytes!(&mut src, src.read_u8()?, 0x1F),
-            play_time: src.read_u32::<LittleEndian>()?,
-            point_item: read_then_skip_bytes!(&mut src, src.read_u32::<LittleEndian>()?, 4),
-            miss_count: src.read_u32::<LittleEndian>()?,
-            bomb_count: src.read_u32::<LittleEndian>()?,
-            last_spells: src.read_u32::<LittleEndian>()?,
-            pause_count: src.read_u32::<LittleEndian>()?,
-            time_points: src.read_u32::<LittleEndian>()?,
-            human_rate: src.read_u32::<LittleEndian>()?,
+            play_time: src.read_u32::<FileOrder>()?,
+            point_item: read_then_skip_bytes!(&mut src, src.read_u32::<FileOrder>()?, 4),
+            miss_count: src.read_u32::<FileOrder>()?,
+            bomb_count: src.read_u32::<FileOrder>()?,
+            last_spells: src.read_u32::<FileOrder>()?,
+            pause_count: src.read_u32::<FileOrder>()?,
+            time_points: src.read_u32::<FileOrder>()?,
+            human_rate: src.read_u32::<FileOrder>()?,
             card_flags: read_then_skip_bytes!(
                 &mut src,
                 {
@@ -316,8 +230,16 @@ impl SpellCardCareer {
             self.captures.0
         }
     }
+
+    /// Gets the capture rate in story mode or spell practice, or `None` if there have been no attempts.
+    pub fn capture_rate(&self, practice: bool) -> Option<f64> {
+        let attempts = self.attempts(practice);
+        (attempts > 0).then(|| (self.captures(practice) as f64) / (attempts as f64))
+    }
 }
 
+const LAST_WORD_IDS: RangeInclusive<u16> = 206..=222;
+
 #[derive(Debug, Clone)]
 pub struct SpellCardData {
     card_id: SpellId,
@@ -338,6 +260,11 @@ impl SpellCardData {
         self.difficulty
     }
 
+    /// Returns whether this record is for one of the Last Word spell cards (IDs 206 through 222).
+    pub fn is_last_word(&self) -> bool {
+        LAST_WORD_IDS.contains(&self.card_id.unwrap())
+    }
+
     pub fn card_name(&self) -> &[u8] {
         &self.card_name[..]
     }
@@ -350,9 +277,50 @@ impl SpellCardData {
         &self.comment[..]
     }
 
+    /// Gets the Japanese name of this card as stored in the score file, decoded from CP932.
+    pub fn card_name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.card_name)
+    }
+
+    /// Decodes the Japanese name of this card into `buf`, reusing its allocation; see [`decode_cp932_into`].
+    pub fn decode_card_name_into(&self, buf: &mut String) {
+        decode_cp932_into(&self.card_name, buf)
+    }
+
+    /// Gets the name of the enemy that uses this card, decoded from CP932.
+    pub fn enemy_name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.enemy_name)
+    }
+
+    /// Gets the comment text displayed alongside this card in spell practice, decoded from CP932.
+    pub fn comment_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.comment)
+    }
+
+    /// Gets the stats for this card with the given shot type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this record has no stats for the given shot type. See [`Self::try_shot_stats`]
+    /// for a non-panicking version.
     pub fn shot_stats(&self, shot: &ShotType) -> &SpellCardCareer {
+        self.try_shot_stats(shot)
+            .expect("shot type index out of range for spell card data")
+    }
+
+    /// Gets the stats for this card with the given shot type, or `None` if this record has no stats for it.
+    pub fn try_shot_stats(&self, shot: &ShotType) -> Option<&SpellCardCareer> {
         let idx: usize = shot.into();
-        &self.career_stats[idx]
+        self.career_stats.get(idx)
+    }
+
+    /// Gets the capture rate for this card with the given shot type in story mode or spell practice.
+    ///
+    /// Returns `None` if this record has no stats for the shot type, or if the card has never been attempted
+    /// with it in the given mode (for example, Last Words in story mode).
+    pub fn capture_rate(&self, shot: &ShotType, practice: bool) -> Option<f64> {
+        self.try_shot_stats(shot)
+            .and_then(|stats| stats.capture_rate(practice))
     }
 
     pub fn iter_shot_stats(&self) -> impl Iterator<Item = (ShotType, &SpellCardCareer)> + '_ {
@@ -364,10 +332,10 @@ impl SpellCardData {
     }
 
     pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
-        src.read_u32::<LittleEndian>()?;
+        src.read_u32::<FileOrder>()?;
 
         let card_id = src
-            .read_u16::<LittleEndian>()
+            .read_u16::<FileOrder>()
             .map(|x| (x as u32) + 1)
             .and_then(try_into_or_io_error(ErrorKind::InvalidData))?;
 
@@ -384,11 +352,11 @@ impl SpellCardData {
         let mut arrays = [[0u32; 13]; 6];
         for subarray in arrays.iter_mut() {
             for elem in subarray.iter_mut() {
-                *elem = src.read_u32::<LittleEndian>()?;
+                *elem = src.read_u32::<FileOrder>()?;
             }
         }
 
-        src.read_u32::<LittleEndian>()?;
+        src.read_u32::<FileOrder>()?;
 
         let mut career_stats = Vec::with_capacity(12);
         for i in 0..12 {
@@ -427,18 +395,18 @@ impl SpellCardRecord<Touhou8> for SpellCardData {
     }
 
     fn attempts(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].attempts(false)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.attempts(false))
     }
 
     fn captures(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].captures(false)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.captures(false))
     }
 
     fn max_bonus(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].max_bonus(false)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.max_bonus(false))
     }
 
     fn total_attempts(&self) -> u32 {
@@ -452,22 +420,29 @@ impl SpellCardRecord<Touhou8> for SpellCardData {
     fn total_max_bonus(&self) -> u32 {
         self.total_stats.max_bonus(false)
     }
+
+    fn practice_totals(&self) -> Option<(u32, u32)> {
+        Some((
+            self.practice_total_captures(),
+            self.practice_total_attempts(),
+        ))
+    }
 }
 
 impl SpellPracticeRecord<Touhou8> for SpellCardData {
     fn practice_attempts(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].attempts(true)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.attempts(true))
     }
 
     fn practice_captures(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].captures(true)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.captures(true))
     }
 
     fn practice_max_bonus(&self, shot: &ShotWrapper<Touhou8>) -> u32 {
-        let idx: usize = shot.unwrap().into();
-        self.career_stats[idx].max_bonus(true)
+        self.try_shot_stats(&shot.unwrap())
+            .map_or(0, |stats| stats.max_bonus(true))
     }
 
     fn practice_total_attempts(&self) -> u32 {
@@ -543,7 +518,7 @@ impl PracticeData {
 
         for arr in [&mut play_counts, &mut high_scores] {
             for elem in arr.iter_mut() {
-                *elem = src.read_u32::<LittleEndian>()?;
+                *elem = src.read_u32::<FileOrder>()?;
             }
         }
 
@@ -633,18 +608,35 @@ impl Segment {
     }
 
     pub fn read_from<R: Read>(mut src: R) -> io::Result<Option<Self>> {
-        let mut signature = [0; 4];
-        return_none_on_eof!(src.read_exact(&mut signature));
-        let size1 = return_none_on_eof!(src.read_u16::<LittleEndian>()) as usize;
-        let size2 = return_none_on_eof!(src.read_u16::<LittleEndian>()) as usize;
+        let (signature, size1, size2, data) = loop {
+            let mut signature = [0; 4];
+            return_none_on_eof!(src.read_exact(&mut signature));
+            let size1 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+            let size2 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+
+            /* sizes include the 8-byte segment header, so anything smaller can only be garbage or padding */
+            if size1 < 8 {
+                return Ok(None);
+            } else if size1 == 8 {
+                continue;
+            }
 
-        if size1 <= 8 {
-            return Self::read_from(src);
-        }
+            let mut data = vec![0u8; size1 - 8];
+            return_none_on_eof!(src.read_exact(&mut data));
+            break (signature, size1, size2, data);
+        };
 
-        let mut data = vec![0u8; size1 - 8];
-        return_none_on_eof!(src.read_exact(&mut data));
+        Self::from_parts(signature, size1, size2, data).map(Some)
+    }
+}
 
+impl ScoreSegment for Segment {
+    fn from_parts(
+        signature: [u8; 4],
+        size1: usize,
+        size2: usize,
+        data: Vec<u8>,
+    ) -> io::Result<Self> {
         let mut reader = Cursor::new(data);
         match &signature {
             b"TH8K" => Ok(Self::Header),
@@ -658,7 +650,6 @@ impl Segment {
                 reader.into_inner().into(),
             )),
         }
-        .map(Some)
     }
 }
 
@@ -703,7 +694,7 @@ pub struct ScoreReader<R> {
 
 impl<R: Read> ScoreReader<R> {
     pub fn new(src: R) -> Result<Self, io::Error> {
-        let mut decryptor = Decryptor::new(src)?;
+        let mut decryptor = decryptor(src)?;
         let header = FileHeader::read_from(&mut decryptor)
             .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
 
@@ -714,37 +705,104 @@ impl<R: Read> ScoreReader<R> {
     pub fn header(&self) -> &FileHeader {
         &self.header
     }
+
+    /// Gets whether the score file's checksum matched its contents.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.src.get_ref().checksum_valid()
+    }
+
+    /// Gets whether the amount of decompressed data matched the size declared in the file header.
+    ///
+    /// Files that are truncated or otherwise corrupted will often decompress to fewer bytes than expected,
+    /// which would otherwise only show up as missing segments.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn decompressed_size_valid(&self) -> Option<bool> {
+        self.src
+            .get_ref()
+            .checksum_valid()
+            .map(|_| self.src.bytes_produced() == self.header.decomp_body_sz())
+    }
 }
 
 impl<R: Read> Iterator for ScoreReader<R> {
     type Item = Result<Segment, io::Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Segment::read_from(&mut self.src).transpose()
+        match Segment::read_from(&mut self.src).transpose() {
+            None => {
+                /* consume any trailing data so that the checksum covers the entire file */
+                io::copy(self.src.get_mut(), &mut io::sink())
+                    .err()
+                    .map(Err)
+            }
+            ret => ret,
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct ScoreFile {
+    high_scores: Vec<HighScore>,
     cards: Vec<SpellCardData>,
     practices: Vec<PracticeScore>,
 }
 
 impl ScoreFile {
     pub fn new<R: Read>(src: R) -> Result<Self, io::Error> {
+        Self::new_with_progress(src, |_| {})
+    }
+
+    /// Like [`Self::new`], but calls `on_segment` after each segment of the file is parsed.
+    ///
+    /// The callback receives the total number of segments read so far, which can be used to report progress.
+    pub fn new_with_progress<R: Read, F: FnMut(usize)>(
+        src: R,
+        mut on_segment: F,
+    ) -> Result<Self, io::Error> {
+        let mut high_scores = Vec::new();
         let mut cards = Vec::with_capacity(141);
         let mut practices = Vec::new();
 
-        for segment in ScoreReader::new(src)? {
+        for (count, segment) in ScoreReader::new(src)?.enumerate() {
             match segment {
+                Ok(Segment::HighScore(data)) => high_scores.push(data),
                 Ok(Segment::SpellCard(data)) => cards.push(data),
                 Ok(Segment::Practice(data)) => practices.extend(data.practice_data.into_values()),
-                Ok(_) => continue,
+                Ok(_) => {}
                 Err(e) => return Err(e),
             }
+
+            on_segment(count + 1);
         }
 
-        Ok(Self { cards, practices })
+        Ok(Self {
+            high_scores,
+            cards,
+            practices,
+        })
+    }
+
+    /// Iterates over the high score entries in this file.
+    pub fn high_scores(&self) -> impl Iterator<Item = &HighScore> + '_ {
+        self.high_scores.iter()
+    }
+
+    /// Gets the furthest progress reached by the given shot type across all high scores in this file.
+    pub fn furthest_progress(&self, shot_type: ShotType) -> StageProgress<Touhou8> {
+        StageProgress::max_progress(
+            self.high_scores
+                .iter()
+                .filter(|score| score.shot_type() == shot_type)
+                .map(HighScore::progress),
+        )
+    }
+
+    /// Iterates over the records for Last Word spell cards in this file.
+    pub fn last_words(&self) -> impl Iterator<Item = &SpellCardData> + '_ {
+        self.cards.iter().filter(|card| card.is_last_word())
     }
 }
 
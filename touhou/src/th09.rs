@@ -0,0 +1,73 @@
+//! Definitions specific to Touhou 9 (*Phantasmagoria of Flower View*).
+
+use serde::{Deserialize, Serialize};
+use touhou_macros::define_game;
+
+#[cfg(feature = "score-file")]
+pub mod score;
+
+mod spellcards;
+
+#[cfg(feature = "score-file")]
+pub use score::ScoreFile;
+pub use spellcards::SpellId;
+
+use crate::types::GameId;
+
+define_game! {
+    /// The ninth game in the series: *Touhou Kaeizuka ~ Phantasmagoria of Flower View*.
+    Touhou9 {
+        type SpellID = SpellId;
+        type ShotPower = Gen1;
+        const GAME_ID = PoFV;
+
+        /// The selectable characters in Touhou 9.
+        ShotType {
+            Reimu,
+            Marisa,
+            Sakuya,
+            Youmu,
+            Reisen,
+            Cirno,
+            Lyrica,
+            Mystia,
+            Tewi,
+            Yuuka,
+            Aya,
+            Medicine,
+            Komachi,
+            Eiki,
+            Merlin,
+            Lunasa,
+        }
+
+        /// The selectable difficulty levels in Touhou 9.
+        #[derive(Serialize, Deserialize)]
+        #[serde(into = "u8", try_from = "u8")]
+        Difficulty {
+            Easy,
+            Normal,
+            Hard,
+            Lunatic,
+            Extra,
+        }
+
+        /// The stages in Touhou 9's story mode.
+        #[derive(Serialize, Deserialize)]
+        #[serde(into = "u8", try_from = "u8")]
+        Stage {
+            One: "Stage 1",
+            Two: "Stage 2",
+            Three: "Stage 3",
+            Four: "Stage 4",
+            Five: "Stage 5",
+            Six: "Stage 6",
+            Seven: "Stage 7",
+            Eight: "Stage 8",
+            #[final_stage]
+            Nine: "Stage 9",
+            #[extra]
+            Extra: "Extra Stage",
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::io::{self, Cursor, ErrorKind, Read};
+use std::time::Duration;
+
+use byteorder::ReadBytesExt;
+
+use super::{Difficulty, ShotType};
+use crate::score::*;
+use crate::th07::score::{FileHeader, StoredTime};
+
+/// The byte order used for multi-byte values in this game's score files.
+type FileOrder = byteorder::LittleEndian;
+
+fn read_array<const N: usize, R: Read>(mut src: R) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    src.read_exact(&mut buf[..])?;
+    Ok(buf)
+}
+
+/// Decrypts PoFV score files.
+///
+/// The start of the file is encrypted with [`ThCrypt`] on top of the cipher handled by [`ChecksumDecryptor`].
+pub type Decryptor<R> = ChecksumDecryptor<ThCryptPrefix<R>>;
+
+fn decryptor<R: Read>(src: R) -> io::Result<Decryptor<R>> {
+    ChecksumDecryptor::new(ThCryptPrefix::new(src, 0x3A, 0xCD, 0x0100, 0x0C00))
+}
+
+/// A story mode high score entry for a single character and difficulty.
+#[derive(Debug, Clone)]
+pub struct HighScore {
+    score: u32,
+    character: ShotType,
+    difficulty: Difficulty,
+    rank: u16,
+    name: [u8; 9],
+    date: [u8; 9],
+    continues: u8,
+}
+
+impl HighScore {
+    pub fn score(&self) -> u32 {
+        self.score
+    }
+
+    pub fn character(&self) -> ShotType {
+        self.character
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    /// Gets the position of this entry within its character and difficulty's high score table, starting from 0.
+    pub fn rank(&self) -> u16 {
+        self.rank
+    }
+
+    pub fn name(&self) -> &[u8] {
+        &self.name[..]
+    }
+
+    /// Gets the name entered for this score, decoded from CP932.
+    pub fn name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
+    /// Gets the date this score was set, as stored in the file (in `YY/MM/DD` format).
+    pub fn date(&self) -> Cow<'_, str> {
+        decode_cp932(&self.date)
+    }
+
+    pub fn continues(&self) -> u8 {
+        self.continues
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        skip_bytes::<4, _>(&mut src)?;
+        let score = src.read_u32::<FileOrder>()?;
+        skip_bytes::<4, _>(&mut src)?;
+
+        Ok(Self {
+            score,
+            character: src
+                .read_u8()
+                .and_then(try_into_or_io_error(ErrorKind::InvalidData))?,
+            difficulty: src
+                .read_u8()
+                .and_then(try_into_or_io_error(ErrorKind::InvalidData))?,
+            rank: src.read_u16::<FileOrder>()?,
+            name: read_array(&mut src)?,
+            date: read_array(&mut src)?,
+            continues: {
+                skip_bytes::<1, _>(&mut src)?;
+                src.read_u8()?
+            },
+        })
+    }
+}
+
+/// Story mode clear counts for a single character.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ClearCount([u32; 5]);
+
+impl ClearCount {
+    pub fn clears(&self, difficulty: &Difficulty) -> u32 {
+        let idx: usize = difficulty.into();
+        self.0[idx]
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let mut counts = [0u32; 5];
+        for count in counts.iter_mut() {
+            *count = src.read_u32::<FileOrder>()?;
+        }
+
+        skip_bytes::<4, _>(&mut src)?;
+        Ok(Self(counts))
+    }
+}
+
+/// Overall play data, including per-character unlock and clear flags.
+#[derive(Debug, Clone)]
+pub struct PlayStatus {
+    running_time: StoredTime,
+    play_time: StoredTime,
+    bgm_flags: [u8; 19],
+    match_flags: [u8; 16],
+    story_flags: [u8; 16],
+    extra_flags: [u8; 16],
+    clear_counts: Box<[ClearCount; 16]>,
+}
+
+impl PlayStatus {
+    /// Gets the total time the game has been running.
+    pub fn running_time(&self) -> Duration {
+        self.running_time.as_duration()
+    }
+
+    /// Gets the total time spent in gameplay.
+    pub fn play_time(&self) -> Duration {
+        self.play_time.as_duration()
+    }
+
+    pub fn bgm_flags(&self) -> &[u8] {
+        &self.bgm_flags[..]
+    }
+
+    /// Gets whether a character has been unlocked for Match mode.
+    pub fn is_match_unlocked(&self, character: &ShotType) -> bool {
+        let idx: usize = character.into();
+        self.match_flags[idx] != 0
+    }
+
+    /// Gets whether a character has cleared story mode.
+    pub fn is_story_cleared(&self, character: &ShotType) -> bool {
+        let idx: usize = character.into();
+        self.story_flags[idx] != 0
+    }
+
+    /// Gets whether a character has cleared the Extra stage.
+    pub fn is_extra_cleared(&self, character: &ShotType) -> bool {
+        let idx: usize = character.into();
+        self.extra_flags[idx] != 0
+    }
+
+    pub fn clear_counts(&self, character: &ShotType) -> &ClearCount {
+        let idx: usize = character.into();
+        &self.clear_counts[idx]
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        skip_bytes::<4, _>(&mut src)?;
+
+        let running_time = StoredTime::read_from(&mut src)?;
+        let play_time = StoredTime::read_from(&mut src)?;
+        let bgm_flags = read_array(&mut src)?;
+        skip_bytes::<13, _>(&mut src)?;
+
+        let match_flags = read_array(&mut src)?;
+        let story_flags = read_array(&mut src)?;
+        let extra_flags = read_array(&mut src)?;
+
+        let mut clear_counts = Box::new([ClearCount::default(); 16]);
+        for count in clear_counts.iter_mut() {
+            *count = ClearCount::read_from(&mut src)?;
+        }
+
+        Ok(Self {
+            running_time,
+            play_time,
+            bgm_flags,
+            match_flags,
+            story_flags,
+            extra_flags,
+            clear_counts,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub enum Segment {
+    Header,
+    HighScore(HighScore),
+    PlayStatus(PlayStatus),
+    Unknown([u8; 4], usize, usize, Box<[u8]>),
+}
+
+impl Segment {
+    pub fn signature(&self) -> &[u8; 4] {
+        match self {
+            Self::Header => b"TH9K",
+            Self::HighScore(_) => b"HSCR",
+            Self::PlayStatus(_) => b"PLST",
+            Self::Unknown(sig, _, _, _) => sig,
+        }
+    }
+
+    fn signature_string(&self) -> String {
+        let v: Vec<u8> = self
+            .signature()
+            .iter()
+            .copied()
+            .flat_map(std::ascii::escape_default)
+            .collect();
+        String::from_utf8(v).unwrap()
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Option<Self>> {
+        let (signature, size1, size2, data) = loop {
+            let mut signature = [0; 4];
+            return_none_on_eof!(src.read_exact(&mut signature));
+            let size1 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+            let size2 = return_none_on_eof!(src.read_u16::<FileOrder>()) as usize;
+
+            /* sizes include the 8-byte segment header, so anything smaller can only be garbage or padding */
+            if size1 < 8 {
+                return Ok(None);
+            } else if size1 == 8 {
+                continue;
+            }
+
+            let mut data = vec![0u8; size1 - 8];
+            return_none_on_eof!(src.read_exact(&mut data));
+            break (signature, size1, size2, data);
+        };
+
+        Self::from_parts(signature, size1, size2, data).map(Some)
+    }
+}
+
+impl ScoreSegment for Segment {
+    fn from_parts(
+        signature: [u8; 4],
+        size1: usize,
+        size2: usize,
+        data: Vec<u8>,
+    ) -> io::Result<Self> {
+        let mut reader = Cursor::new(data);
+        match &signature {
+            b"TH9K" => Ok(Self::Header),
+            b"HSCR" => HighScore::read_from(&mut reader).map(Self::HighScore),
+            b"PLST" => PlayStatus::read_from(&mut reader).map(Self::PlayStatus),
+            _ => Ok(Self::Unknown(
+                signature,
+                size1,
+                size2,
+                reader.into_inner().into(),
+            )),
+        }
+    }
+}
+
+impl Debug for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header => f
+                .debug_struct("Segment::Header")
+                .field("signature", &self.signature_string())
+                .finish(),
+            Self::HighScore(d) => f
+                .debug_struct("Segment::HighScore")
+                .field("signature", &self.signature_string())
+                .field("data", d)
+                .finish(),
+            Self::PlayStatus(d) => f
+                .debug_struct("Segment::PlayStatus")
+                .field("signature", &self.signature_string())
+                .field("data", d)
+                .finish(),
+            Self::Unknown(_, s1, s2, d) => f
+                .debug_struct("Segment::Unknown")
+                .field("signature", &self.signature_string())
+                .field("size_1", s1)
+                .field("size_2", s2)
+                .field("data", &format!("[{} bytes]", d.len()))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ScoreReader<R> {
+    header: FileHeader,
+    src: StreamDecompressor<Decryptor<R>>,
+}
+
+impl<R: Read> ScoreReader<R> {
+    pub fn new(src: R) -> Result<Self, io::Error> {
+        let mut decryptor = decryptor(src)?;
+        let header = FileHeader::read_from(&mut decryptor)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+
+        let src = StreamDecompressor::new(decryptor);
+        Ok(Self { header, src })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+
+    /// Gets whether the score file's checksum matched its contents.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn checksum_valid(&self) -> Option<bool> {
+        self.src.get_ref().checksum_valid()
+    }
+
+    /// Gets whether the amount of decompressed data matched the size declared in the file header.
+    ///
+    /// Files that are truncated or otherwise corrupted will often decompress to fewer bytes than expected,
+    /// which would otherwise only show up as missing segments.
+    ///
+    /// Returns `None` until all segments have been read from this reader.
+    pub fn decompressed_size_valid(&self) -> Option<bool> {
+        self.src
+            .get_ref()
+            .checksum_valid()
+            .map(|_| self.src.bytes_produced() == self.header.decomp_body_sz())
+    }
+}
+
+impl<R: Read> Iterator for ScoreReader<R> {
+    type Item = Result<Segment, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match Segment::read_from(&mut self.src).transpose() {
+            None => {
+                /* consume any trailing data so that the checksum covers the entire file */
+                io::copy(self.src.get_mut(), &mut io::sink()).err().map(Err)
+            }
+            ret => ret,
+        }
+    }
+}
+
+/// A loaded PoFV score file.
+///
+/// PoFV doesn't record spell card or stage practice data, so this type doesn't implement [`crate::score::ScoreFile`];
+/// instead, it exposes story mode high scores and per-character clear data.
+#[derive(Debug, Clone)]
+pub struct ScoreFile {
+    high_scores: Vec<HighScore>,
+    play_status: PlayStatus,
+}
+
+impl ScoreFile {
+    pub fn new<R: Read>(src: R) -> Result<Self, io::Error> {
+        Self::new_with_progress(src, |_| {})
+    }
+
+    /// Like [`Self::new`], but calls `on_segment` after each segment of the file is parsed.
+    ///
+    /// The callback receives the total number of segments read so far, which can be used to report progress.
+    pub fn new_with_progress<R: Read, F: FnMut(usize)>(
+        src: R,
+        mut on_segment: F,
+    ) -> Result<Self, io::Error> {
+        let mut high_scores = Vec::new();
+        let mut play_status = None;
+
+        for (count, segment) in ScoreReader::new(src)?.enumerate() {
+            match segment {
+                Ok(Segment::HighScore(data)) => high_scores.push(data),
+                Ok(Segment::PlayStatus(data)) => play_status = Some(data),
+                Ok(_) => {}
+                Err(e) => return Err(e),
+            }
+
+            on_segment(count + 1);
+        }
+
+        let play_status = play_status
+            .ok_or_else(|| io::Error::new(ErrorKind::InvalidData, "missing play status segment"))?;
+
+        Ok(Self {
+            high_scores,
+            play_status,
+        })
+    }
+
+    pub fn high_scores(&self) -> &[HighScore] {
+        &self.high_scores[..]
+    }
+
+    /// Iterates over the high scores recorded for a character on a given difficulty, in rank order.
+    pub fn high_scores_for(
+        &self,
+        character: ShotType,
+        difficulty: Difficulty,
+    ) -> impl Iterator<Item = &HighScore> + '_ {
+        let mut scores: Vec<_> = self
+            .high_scores
+            .iter()
+            .filter(|score| score.character == character && score.difficulty == difficulty)
+            .collect();
+
+        scores.sort_by_key(|score| score.rank);
+        scores.into_iter()
+    }
+
+    pub fn play_status(&self) -> &PlayStatus {
+        &self.play_status
+    }
+
+    /// Gets whether a character has cleared story mode.
+    pub fn is_story_cleared(&self, character: &ShotType) -> bool {
+        self.play_status.is_story_cleared(character)
+    }
+
+    /// Gets whether a character has cleared the Extra stage.
+    pub fn is_extra_cleared(&self, character: &ShotType) -> bool {
+        self.play_status.is_extra_cleared(character)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment_header(signature: &[u8; 4], size: u16) -> Vec<u8> {
+        let mut ret = signature.to_vec();
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret
+    }
+
+    #[test]
+    fn skips_many_empty_segments() {
+        let mut stream = Vec::new();
+        for _ in 0..100_000 {
+            stream.extend(segment_header(b"PAD0", 8));
+        }
+        stream.extend(segment_header(b"ABCD", 12));
+        stream.extend_from_slice(&[1, 2, 3, 4]);
+
+        let mut src = Cursor::new(stream);
+        match Segment::read_from(&mut src).unwrap() {
+            Some(Segment::Unknown(sig, size1, _, data)) => {
+                assert_eq!(&sig, b"ABCD");
+                assert_eq!(size1, 12);
+                assert_eq!(&data[..], &[1, 2, 3, 4]);
+            }
+            other => panic!("unexpected segment {:?}", other),
+        }
+
+        assert!(Segment::read_from(&mut src).unwrap().is_none());
+    }
+
+    #[test]
+    fn stops_on_undersized_segments() {
+        let mut stream = Vec::new();
+        for _ in 0..100_000 {
+            stream.extend(segment_header(b"\0\0\0\0", 0));
+        }
+
+        assert!(Segment::read_from(Cursor::new(stream)).unwrap().is_none());
+    }
+}
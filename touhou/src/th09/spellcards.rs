@@ -0,0 +1,25 @@
+use touhou_macros::spellcards;
+
+use super::Touhou9;
+
+spellcards! {
+    /// Identifies a spell card from Touhou 9.
+    ///
+    /// PoFV does not number its spell cards or record them in its score file, so no spells are currently defined for it.
+    Game: Touhou9,
+    Expected: 0,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AllIterable;
+
+    #[test]
+    fn has_no_valid_spell_ids() {
+        assert_eq!(SpellId::iter_all().len(), 0);
+        assert!(SpellId::new(0).is_err());
+        assert!(SpellId::new(1).is_err());
+        assert!(SpellId::try_card_info(1).is_none());
+    }
+}
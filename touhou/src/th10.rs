@@ -16,8 +16,17 @@ pub mod memory;
 #[cfg(feature = "memory")]
 pub use memory::*;
 
+#[cfg(feature = "score-file")]
+pub mod replay;
+#[cfg(feature = "score-file")]
+pub mod score;
+
 mod spellcards;
 
+#[cfg(feature = "score-file")]
+pub use replay::ReplayFile;
+#[cfg(feature = "score-file")]
+pub use score::ScoreFile;
 pub use spellcards::SpellId;
 
 use crate::types::GameId;
@@ -59,7 +68,9 @@ define_game! {
             Three: "Stage 3",
             Four: "Stage 4",
             Five: "Stage 5",
+            #[final_stage]
             Six: "Stage 6",
+            #[extra]
             Extra: "Extra Stage"
         }
     }
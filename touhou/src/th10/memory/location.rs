@@ -1,8 +1,8 @@
 use super::state::Activity;
 use super::{BossState, RunState};
-use crate::memory::GameLocation;
-use crate::th10::{Difficulty, Stage, Touhou10};
-use crate::types::{SpellCard, SpellType, Stage as StageWrapper};
+use crate::memory::{GameLocation, InvalidLocationData};
+use crate::th10::{Difficulty, SpellId, Stage, Touhou10};
+use crate::types::{AllIterable, SpellCard, SpellType, Stage as StageWrapper};
 
 macro_rules! nonspell_strings {
     {
@@ -222,6 +222,60 @@ impl GameLocation<Touhou10> for Location {
 
         Some(Self { stage, section })
     }
+
+    fn from_index(index: u64) -> Result<Self, InvalidLocationData<Touhou10>> {
+        let invalid_index = |stage: &'static str| InvalidLocationData::InvalidIndex {
+            stage,
+            index,
+            valid: 0..=((6 << 19) | (4 << 16) | 0xFFFF),
+        };
+
+        let stage = u8::try_from(index >> 19)
+            .map_err(|_| invalid_index("(any)"))
+            .and_then(|stage| Stage::try_from(stage).map_err(InvalidLocationData::InvalidStage))?;
+        let stage_idx = usize::from(stage);
+        let low_bits = index & 0xFFFF;
+
+        let boss_section = |loc_name: &'static str, midboss: bool| {
+            let invalid_spell = InvalidLocationData::InvalidSpell {
+                stage: stage.name(),
+                loc_name,
+                valid: 1..=(SpellId::iter_all().len() as u32),
+            };
+
+            u16::try_from(low_bits)
+                .ok()
+                .and_then(|id| SpellId::new(id).ok())
+                .map(SpellCard::new)
+                .filter(|spell: &SpellCard<Touhou10>| {
+                    spell.stage.unwrap() == stage
+                        && (spell.spell_type == SpellType::Midboss) == midboss
+                })
+                .map(BossSection::from_spell)
+                .ok_or(invalid_spell)
+        };
+
+        let section = match (index >> 16) & 0x7 {
+            0 if low_bits == 0 => Section::Stage,
+            1 if (low_bits as usize) < MIDBOSS_NONSPELL_STRINGS[stage_idx].len() => {
+                Section::Midboss(BossSection {
+                    seq: low_bits as u32,
+                    spell: None,
+                })
+            }
+            2 => Section::Midboss(boss_section("Midboss Spell", true)?),
+            3 if (low_bits as usize) < BOSS_NONSPELL_STRINGS[stage_idx].len() => {
+                Section::Boss(BossSection {
+                    seq: low_bits as u32,
+                    spell: None,
+                })
+            }
+            4 => Section::Boss(boss_section("Boss Spell", false)?),
+            _ => return Err(invalid_index(stage.name())),
+        };
+
+        Ok(Self { stage, section })
+    }
 }
 
 impl Default for Location {
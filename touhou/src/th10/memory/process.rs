@@ -9,6 +9,10 @@ define_memory! {
         game = Touhou10,
         access = MemoryAccess,
 
+        /// A snapshot of every value in a running Touhou 10 process's memory, read at once.
+        #[derive(Debug, Clone)]
+        snapshot = MemorySnapshot,
+
         score: u32 @ [0x0047_4C44],
         power: u16 @ [0x0047_4C48], // displayed power = this * 0.05
         faith: u32 @ [0x0047_4C4C],
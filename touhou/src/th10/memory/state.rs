@@ -92,6 +92,12 @@ impl PlayerScore<Touhou10> for PlayerState {
     }
 }
 
+impl FaithMeter<Touhou10> for PlayerState {
+    fn faith(&self) -> u32 {
+        self.faith
+    }
+}
+
 define_state_struct! {
     ActiveSpell {
         spell: SpellCard<Touhou10>,
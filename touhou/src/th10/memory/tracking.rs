@@ -69,11 +69,13 @@ where
         self.prev_state = state;
     }
 
-    fn finish(mut self, cleared: bool, end_state: Option<RunState>) -> T::Output {
+    fn finish(mut self, cleared: bool, retried: bool, end_state: Option<RunState>) -> T::Output {
         if let Some(end_state) = end_state {
             self.update_state(end_state);
         }
 
+        self.tracker.push_run_end(self.prev_state, cleared, retried);
+
         if self.tracker.tracking_type() == TrackingType::StagePractice {
             self.tracker.finish_stage_practice(self.prev_state)
         } else if cleared {
@@ -113,14 +115,18 @@ where
                 self.update_state(run);
                 Ok(UpdateStatus::Continuing(self))
             }
-            GameState::GameOver(run) => Ok(UpdateStatus::Finished(self.finish(false, Some(run)))),
-            GameState::Ending(run) => Ok(UpdateStatus::Finished(self.finish(true, Some(run)))),
-            _ => Ok(UpdateStatus::Finished(self.finish(false, None))),
+            GameState::GameOver(run) => {
+                Ok(UpdateStatus::Finished(self.finish(false, false, Some(run))))
+            }
+            GameState::Ending(run) => {
+                Ok(UpdateStatus::Finished(self.finish(true, false, Some(run))))
+            }
+            _ => Ok(UpdateStatus::Finished(self.finish(false, false, None))),
         }
     }
 
     fn terminate(self) -> T::Output {
-        self.finish(false, None)
+        self.finish(false, false, None)
     }
 }
 
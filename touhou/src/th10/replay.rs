@@ -0,0 +1,381 @@
+//! Reading support for Touhou 10 replay files (`.rpy`).
+
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::Display;
+use std::io::{self, Cursor, ErrorKind, Read};
+
+use byteorder::ReadBytesExt;
+
+use super::{Difficulty as Th10Difficulty, ShotType as Th10Shot, Stage as Th10Stage, Touhou10};
+use crate::score::{decode_cp932, StreamDecompressor, ThCrypt};
+use crate::types::{Difficulty, ShotPower, ShotType, Stage};
+
+/// The byte order used for multi-byte values in this game's replay files.
+type FileOrder = byteorder::LittleEndian;
+
+/// Size of the unencrypted header at the start of each replay file.
+const FILE_HEADER_SIZE: usize = 0x24;
+
+/// Offset of the first stage header within the decompressed replay data.
+const STAGE_DATA_OFFSET: usize = 0x64;
+
+/// Size of each stage header within the decompressed replay data, not including the stage's input data.
+const STAGE_HEADER_SIZE: usize = 0x1C4;
+
+/// Upper limit on the buffer preallocated for decompressed replay data, since the size comes from the untrusted file header.
+///
+/// Real replays are much smaller than this; larger data is still read, just without preallocating for it.
+const MAX_PREALLOC_SIZE: usize = 0x10_0000;
+
+fn read_into_or_invalid<T, U>(val: T) -> io::Result<U>
+where
+    T: TryInto<U>,
+    T::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    val.try_into()
+        .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Errors that can occur while reading a replay file.
+#[derive(Debug)]
+pub enum ReplayError {
+    /// The file did not start with the expected `t10r` signature.
+    InvalidMagic([u8; 4]),
+    /// An IO error occurred while reading or decoding the replay.
+    IO(io::Error),
+}
+
+impl Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidMagic(magic) => write!(
+                f,
+                "invalid replay signature {} (expected t10r)",
+                magic.escape_ascii()
+            ),
+            Self::IO(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidMagic(_) => None,
+            Self::IO(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(value: io::Error) -> Self {
+        Self::IO(value)
+    }
+}
+
+impl From<ReplayError> for io::Error {
+    fn from(value: ReplayError) -> Self {
+        match value {
+            ReplayError::IO(err) => err,
+            other => io::Error::new(ErrorKind::InvalidData, other),
+        }
+    }
+}
+
+/// Information stored at the start of each stage recorded within a replay.
+#[derive(Debug, Clone, Copy)]
+pub struct StageHeader {
+    stage: Stage<Touhou10>,
+    rng_seed: u16,
+    frame_count: u32,
+    score: u64,
+    power: ShotPower<Touhou10>,
+    faith: u32,
+    lives: u32,
+}
+
+impl StageHeader {
+    fn read_from<R: Read>(mut src: R) -> io::Result<(Self, usize)> {
+        let stage = src
+            .read_u16::<FileOrder>()
+            .and_then(|v| {
+                v.checked_sub(1).ok_or_else(|| {
+                    io::Error::new(ErrorKind::InvalidData, "invalid stage number 0")
+                })
+            })
+            .and_then(read_into_or_invalid::<u16, Th10Stage>)
+            .map(Stage::new)?;
+
+        let rng_seed = src.read_u16::<FileOrder>()?;
+        let frame_count = src.read_u32::<FileOrder>()?;
+        let input_size = src.read_u32::<FileOrder>()? as usize;
+        let score = (src.read_u32::<FileOrder>()? as u64) * 10;
+        let power = src
+            .read_u32::<FileOrder>()
+            .and_then(read_into_or_invalid::<u32, u16>)
+            .and_then(read_into_or_invalid::<u16, super::ShotPower>)
+            .map(ShotPower::new)?;
+        let faith = src.read_u32::<FileOrder>()?;
+        src.read_u32::<FileOrder>()?;
+        let lives = src.read_u32::<FileOrder>()?;
+
+        Ok((
+            Self {
+                stage,
+                rng_seed,
+                frame_count,
+                score,
+                power,
+                faith,
+                lives,
+            },
+            input_size,
+        ))
+    }
+
+    /// The stage that this header describes.
+    pub fn stage(&self) -> Stage<Touhou10> {
+        self.stage
+    }
+
+    /// The random number generator seed used at the start of this stage.
+    pub fn rng_seed(&self) -> u16 {
+        self.rng_seed
+    }
+
+    /// The number of frames of input recorded for this stage.
+    pub fn frame_count(&self) -> u32 {
+        self.frame_count
+    }
+
+    /// The player's score at the start of this stage.
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    /// The player's shot power at the start of this stage.
+    pub fn power(&self) -> ShotPower<Touhou10> {
+        self.power
+    }
+
+    /// The player's faith value at the start of this stage.
+    pub fn faith(&self) -> u32 {
+        self.faith
+    }
+
+    /// The player's remaining lives at the start of this stage.
+    pub fn lives(&self) -> u32 {
+        self.lives
+    }
+}
+
+/// A decoded Touhou 10 replay file.
+///
+/// Replay data is stored using the same block cipher and LZ77-based compression scheme as the game's score files,
+/// which are handled by [`ThCrypt`] and [`StreamDecompressor`] respectively.
+#[derive(Debug, Clone)]
+pub struct ReplayFile {
+    name: [u8; 12],
+    score: u64,
+    shot_type: ShotType<Touhou10>,
+    difficulty: Difficulty<Touhou10>,
+    stages: Vec<StageHeader>,
+}
+
+impl ReplayFile {
+    pub fn new<R: Read>(mut src: R) -> Result<Self, ReplayError> {
+        let mut header = [0; FILE_HEADER_SIZE];
+        src.read_exact(&mut header)?;
+
+        let magic: [u8; 4] = header[..4].try_into().unwrap();
+        if &magic != b"t10r" {
+            return Err(ReplayError::InvalidMagic(magic));
+        }
+
+        let mut header = Cursor::new(&header[0x1C..]);
+        let comp_size = header.read_u32::<FileOrder>()? as u64;
+        let decomp_size = header.read_u32::<FileOrder>()? as usize;
+
+        let body_sz = Some(comp_size as usize);
+        let crypt = ThCrypt::new(src.take(comp_size), 0x5E, 0xE1, 0x400, body_sz);
+        let crypt = ThCrypt::new(crypt, 0x7D, 0x3A, 0x100, body_sz);
+
+        let mut data = Vec::with_capacity(decomp_size.min(MAX_PREALLOC_SIZE));
+        StreamDecompressor::new(crypt)
+            .take(decomp_size as u64)
+            .read_to_end(&mut data)?;
+
+        Self::parse(&data).map_err(ReplayError::from)
+    }
+
+    fn parse(data: &[u8]) -> io::Result<Self> {
+        let mut src = Cursor::new(data);
+
+        let mut name = [0; 12];
+        src.read_exact(&mut name)?;
+
+        src.set_position(0x18);
+        let score = (src.read_u32::<FileOrder>()? as u64) * 10;
+
+        src.set_position(0x4C);
+        let n_stages = src.read_u32::<FileOrder>()?;
+        let shot_type = src
+            .read_u32::<FileOrder>()
+            .and_then(read_into_or_invalid::<u32, Th10Shot>)
+            .map(ShotType::new)?;
+        let difficulty = src
+            .read_u32::<FileOrder>()
+            .and_then(read_into_or_invalid::<u32, Th10Difficulty>)
+            .map(Difficulty::new)?;
+
+        /* every stage needs at least a full header, so a larger count can only come from a corrupt file */
+        let mut stages =
+            Vec::with_capacity((n_stages as usize).min(data.len() / STAGE_HEADER_SIZE));
+        let mut offset = STAGE_DATA_OFFSET;
+        for _ in 0..n_stages {
+            let stage_data = data.get(offset..).ok_or_else(|| {
+                io::Error::new(ErrorKind::UnexpectedEof, "replay stage data truncated")
+            })?;
+
+            let (header, input_size) = StageHeader::read_from(stage_data)?;
+            stages.push(header);
+            offset += STAGE_HEADER_SIZE + input_size;
+        }
+
+        Ok(Self {
+            name,
+            score,
+            shot_type,
+            difficulty,
+            stages,
+        })
+    }
+
+    /// Gets the player name recorded in this replay, decoded from CP932.
+    pub fn name(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
+    /// Gets the final score recorded for this replay.
+    pub fn score(&self) -> u64 {
+        self.score
+    }
+
+    /// Gets the shot type used in this replay.
+    pub fn shot_type(&self) -> ShotType<Touhou10> {
+        self.shot_type
+    }
+
+    /// Gets the difficulty this replay was played on.
+    pub fn difficulty(&self) -> Difficulty<Touhou10> {
+        self.difficulty
+    }
+
+    /// Iterates over the headers for each stage recorded in this replay, in the order they were played.
+    pub fn stages(&self) -> impl Iterator<Item = &StageHeader> + '_ {
+        self.stages.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use byteorder::WriteBytesExt;
+
+    use super::*;
+    use crate::score::{encrypt, StreamCompressor};
+
+    fn build_replay(data: &[u8]) -> Vec<u8> {
+        let mut compressed = StreamCompressor::new(Vec::new());
+        compressed.write_all(data).unwrap();
+        let compressed = compressed.finish().unwrap();
+
+        let body = encrypt(&compressed, 0x7D, 0x3A, 0x100);
+        let body = encrypt(&body, 0x5E, 0xE1, 0x400);
+
+        let mut file = vec![0; FILE_HEADER_SIZE];
+        file[..4].copy_from_slice(b"t10r");
+        let mut size_fields = &mut file[0x1C..];
+        size_fields
+            .write_u32::<FileOrder>(body.len() as u32)
+            .unwrap();
+        size_fields
+            .write_u32::<FileOrder>(data.len() as u32)
+            .unwrap();
+
+        file.extend_from_slice(&body);
+        file
+    }
+
+    fn stage_header(stage: u16, input_size: usize, score: u32, lives: u32) -> Vec<u8> {
+        let mut header = vec![0; STAGE_HEADER_SIZE];
+        let mut fields = &mut header[..];
+        fields.write_u16::<FileOrder>(stage).unwrap();
+        fields.write_u16::<FileOrder>(0xBEEF).unwrap();
+        fields.write_u32::<FileOrder>(5000).unwrap();
+        fields.write_u32::<FileOrder>(input_size as u32).unwrap();
+        fields.write_u32::<FileOrder>(score).unwrap();
+        fields.write_u32::<FileOrder>(80).unwrap();
+        fields.write_u32::<FileOrder>(50_000).unwrap();
+        fields.write_u32::<FileOrder>(0).unwrap();
+        fields.write_u32::<FileOrder>(lives).unwrap();
+        header
+    }
+
+    #[test]
+    fn decodes_encrypted_body() {
+        const INPUT_SIZE: usize = 0x1000;
+
+        let mut data = vec![0; STAGE_DATA_OFFSET];
+        data[..8].copy_from_slice(b"TESTNAME");
+        (&mut data[0x18..]).write_u32::<FileOrder>(123_456).unwrap();
+        let mut fields = &mut data[0x4C..];
+        fields.write_u32::<FileOrder>(2).unwrap();
+        fields.write_u32::<FileOrder>(2).unwrap();
+        fields.write_u32::<FileOrder>(3).unwrap();
+
+        /* Pseudorandom input data doesn't compress well, which pushes the second stage header
+         * well past the first encryption block of the compressed body. */
+        let mut state = 0x1234_5678u32;
+        data.extend(stage_header(4, INPUT_SIZE, 98_765, 2));
+        data.extend((0..INPUT_SIZE).map(|_| {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        }));
+        data.extend(stage_header(5, 0, 123_456, 1));
+
+        let file = build_replay(&data);
+        assert!(file.len() - FILE_HEADER_SIZE > 2 * 0x400);
+
+        let replay = ReplayFile::new(Cursor::new(file)).unwrap();
+        assert_eq!(replay.name(), "TESTNAME");
+        assert_eq!(replay.score(), 1_234_560);
+        assert_eq!(
+            replay.shot_type(),
+            ShotType::<Touhou10>::new(Th10Shot::ReimuC)
+        );
+        assert_eq!(
+            replay.difficulty(),
+            Difficulty::<Touhou10>::new(Th10Difficulty::Lunatic)
+        );
+
+        let stages: Vec<_> = replay.stages().collect();
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].stage(), Stage::<Touhou10>::new(Th10Stage::Four));
+        assert_eq!(stages[0].rng_seed(), 0xBEEF);
+        assert_eq!(stages[0].frame_count(), 5000);
+        assert_eq!(stages[0].score(), 987_650);
+        assert_eq!(
+            stages[0].power(),
+            ShotPower::<Touhou10>::new(super::super::ShotPower::new(80).unwrap())
+        );
+        assert_eq!(stages[0].faith(), 50_000);
+        assert_eq!(stages[0].lives(), 2);
+
+        assert_eq!(stages[1].stage(), Stage::<Touhou10>::new(Th10Stage::Five));
+        assert_eq!(stages[1].score(), 1_234_560);
+        assert_eq!(stages[1].lives(), 1);
+    }
+}
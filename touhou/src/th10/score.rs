@@ -0,0 +1,788 @@
+//! Reading support for MoF's `scoreth10.dat` score file.
+//!
+//! Unlike the first-generation Windows games, this file consists of a plain header followed by an encrypted and compressed
+//! body, which itself is split into chapters that are each identified by a two-byte signature and carry their own checksum.
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::io::{self, Cursor, ErrorKind, Read};
+
+use byteorder::ReadBytesExt;
+
+use super::{Difficulty, ShotType, SpellId, Stage, Touhou10};
+use crate::score::*;
+use crate::types::{
+    AllIterable, Difficulty as DifficultyWrapper, ShotType as ShotWrapper, SpellCard,
+    Stage as StageWrapper,
+};
+
+/// The byte order used for multi-byte values in this game's score files.
+type FileOrder = byteorder::LittleEndian;
+
+const SIGNATURE: &[u8; 4] = b"TH10";
+
+fn read_raw_buffer<const N: usize, R: Read>(mut src: R) -> io::Result<Box<[u8]>> {
+    let mut buf = vec![0u8; N];
+    src.read_exact(&mut buf[..])?;
+    Ok(buf.into())
+}
+
+/// The unencrypted header at the start of the score file.
+#[derive(Debug, Clone, Copy)]
+pub struct FileHeader {
+    encoded_full_sz: usize,
+    encoded_body_sz: usize,
+    decoded_body_sz: usize,
+}
+
+impl FileHeader {
+    const SIZE: usize = 0x18;
+
+    pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
+        let mut signature = [0; 4];
+        src.read_exact(&mut signature[..])?;
+
+        if signature != *SIGNATURE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "invalid score file signature",
+            ));
+        }
+
+        let encoded_full_sz = src.read_u32::<FileOrder>()? as usize;
+        skip_bytes::<8, _>(&mut *src)?;
+        let encoded_body_sz = src.read_u32::<FileOrder>()? as usize;
+        let decoded_body_sz = src.read_u32::<FileOrder>()? as usize;
+
+        if encoded_full_sz.checked_sub(encoded_body_sz) != Some(Self::SIZE) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "score file header sizes are inconsistent",
+            ));
+        }
+
+        Ok(Self {
+            encoded_full_sz,
+            encoded_body_sz,
+            decoded_body_sz,
+        })
+    }
+
+    pub fn encoded_full_sz(&self) -> usize {
+        self.encoded_full_sz
+    }
+
+    pub fn encoded_body_sz(&self) -> usize {
+        self.encoded_body_sz
+    }
+
+    pub fn decoded_body_sz(&self) -> usize {
+        self.decoded_body_sz
+    }
+}
+
+/// A high score entry from one of the per-difficulty rankings.
+#[derive(Debug, Clone)]
+pub struct HighScore {
+    score: u32,
+    progress: u8,
+    continues: u8,
+    name: [u8; 10],
+    timestamp: u32,
+    slow: f32,
+}
+
+impl HighScore {
+    /// Gets the score for this entry, as displayed in-game.
+    ///
+    /// MoF stores scores without their last digit, which is always the number of continues used.
+    pub fn score(&self) -> u64 {
+        (self.score as u64) * 10 + (self.continues as u64)
+    }
+
+    /// Gets the raw stage progress value for this entry.
+    pub fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    pub fn continues(&self) -> u8 {
+        self.continues
+    }
+
+    pub fn name(&self) -> &[u8] {
+        &self.name[..]
+    }
+
+    /// Gets the name entered for this score, decoded from CP932.
+    pub fn name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
+    /// Gets the time at which this score was set, as a Unix timestamp.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    pub fn slow(&self) -> f32 {
+        self.slow
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        Ok(Self {
+            score: src.read_u32::<FileOrder>()?,
+            progress: src.read_u8()?,
+            continues: src.read_u8()?,
+            name: {
+                let mut buf = [0; 10];
+                src.read_exact(&mut buf)?;
+                buf
+            },
+            timestamp: src.read_u32::<FileOrder>()?,
+            slow: src.read_f32::<FileOrder>()?,
+        })
+    }
+}
+
+/// The best score achieved in stage practice for a single stage and difficulty.
+#[derive(Debug, Clone)]
+pub struct PracticeScore {
+    shot_type: ShotType,
+    stage: Stage,
+    difficulty: Difficulty,
+    high_score: u32,
+}
+
+impl PracticeScore {
+    pub fn read_from<R: Read>(
+        mut src: R,
+        shot_type: ShotType,
+        stage: Stage,
+        difficulty: Difficulty,
+    ) -> io::Result<Self> {
+        let high_score = src.read_u32::<FileOrder>()?;
+        skip_bytes::<4, _>(&mut src)?;
+
+        Ok(Self {
+            shot_type,
+            stage,
+            difficulty,
+            high_score,
+        })
+    }
+}
+
+impl PracticeRecord<Touhou10> for PracticeScore {
+    /// Gets the high score for this stage, as displayed in-game.
+    fn high_score(&self) -> u32 {
+        self.high_score.saturating_mul(10)
+    }
+
+    /// MoF does not record how many times each stage has been practiced, so this always returns 0.
+    fn attempts(&self) -> u32 {
+        0
+    }
+
+    fn shot_type(&self) -> ShotWrapper<Touhou10> {
+        ShotWrapper::new(self.shot_type)
+    }
+
+    fn difficulty(&self) -> DifficultyWrapper<Touhou10> {
+        DifficultyWrapper::new(self.difficulty)
+    }
+
+    fn stage(&self) -> StageWrapper<Touhou10> {
+        StageWrapper::new(self.stage)
+    }
+}
+
+/// Capture statistics for a single spell card, as recorded for a single shot type.
+#[derive(Debug, Clone)]
+pub struct CardCareer {
+    card_id: SpellId,
+    card_name: Box<[u8]>, // 0x80 bytes, CP932
+    captures: u32,
+    attempts: u32,
+}
+
+impl CardCareer {
+    pub fn card_id(&self) -> SpellId {
+        self.card_id
+    }
+
+    pub fn card_name(&self) -> &[u8] {
+        &self.card_name[..]
+    }
+
+    /// Gets the Japanese name of this card as stored in the score file, decoded from CP932.
+    pub fn card_name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.card_name)
+    }
+
+    /// Decodes the Japanese name of this card into `buf`, reusing its allocation; see [`decode_cp932_into`].
+    pub fn decode_card_name_into(&self, buf: &mut String) {
+        decode_cp932_into(&self.card_name, buf)
+    }
+
+    pub fn captures(&self) -> u32 {
+        self.captures
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let card_name = read_raw_buffer::<0x80, _>(&mut src)?;
+        let captures = src.read_u32::<FileOrder>()?;
+        let attempts = src.read_u32::<FileOrder>()?;
+        let card_id = src
+            .read_u32::<FileOrder>()
+            .map(|x| x + 1)
+            .and_then(try_into_or_io_error(ErrorKind::InvalidData))?;
+        skip_bytes::<4, _>(&mut src)?;
+
+        Ok(Self {
+            card_id,
+            card_name,
+            captures,
+            attempts,
+        })
+    }
+}
+
+/// The contents of a clear data (`CR`) chapter.
+///
+/// The score file contains one of these for each shot type, plus one more containing totals across all shot types.
+#[derive(Debug, Clone)]
+pub struct ClearData {
+    shot_type: Option<ShotType>,
+    rankings: Vec<HighScore>,
+    total_play_count: u32,
+    play_time: u32,
+    clear_counts: [u32; 5],
+    practices: Vec<PracticeScore>,
+    cards: Vec<CardCareer>,
+}
+
+impl ClearData {
+    const RANKING_SIZE: usize = 10;
+
+    /// Gets the shot type for this data, or `None` if this contains totals for all shot types.
+    pub fn shot_type(&self) -> Option<ShotType> {
+        self.shot_type
+    }
+
+    /// Gets the high score rankings for a given difficulty.
+    pub fn rankings(&self, difficulty: &Difficulty) -> &[HighScore] {
+        let idx: usize = difficulty.into();
+        &self.rankings[(idx * Self::RANKING_SIZE)..((idx + 1) * Self::RANKING_SIZE)]
+    }
+
+    pub fn total_play_count(&self) -> u32 {
+        self.total_play_count
+    }
+
+    /// Gets the total play time recorded in this data, in frames.
+    pub fn play_time(&self) -> u32 {
+        self.play_time
+    }
+
+    pub fn clear_count(&self, difficulty: &Difficulty) -> u32 {
+        let idx: usize = difficulty.into();
+        self.clear_counts[idx]
+    }
+
+    /// Gets the practice high scores for this shot type.
+    ///
+    /// This is empty for the data containing totals for all shot types.
+    pub fn practices(&self) -> &[PracticeScore] {
+        &self.practices[..]
+    }
+
+    pub fn cards(&self) -> &[CardCareer] {
+        &self.cards[..]
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let shot_id = src.read_u32::<FileOrder>()?;
+        let shot_type = if (shot_id as usize) == Touhou10::SHOT_TYPES.len() {
+            None
+        } else {
+            Some(try_into_or_io_error(ErrorKind::InvalidData)(shot_id as u8)?)
+        };
+
+        let mut rankings = Vec::with_capacity(Self::RANKING_SIZE * 5);
+        for _ in 0..(Self::RANKING_SIZE * 5) {
+            rankings.push(HighScore::read_from(&mut src)?);
+        }
+
+        let total_play_count = src.read_u32::<FileOrder>()?;
+        let play_time = src.read_u32::<FileOrder>()?;
+
+        let mut clear_counts = [0; 5];
+        for count in clear_counts.iter_mut() {
+            *count = src.read_u32::<FileOrder>()?;
+        }
+
+        /* practice records are only stored for the main stages on the main difficulties */
+        let mut practices = Vec::with_capacity(24);
+        for difficulty in Difficulty::iter_all().filter(|d| *d != Difficulty::Extra) {
+            for stage in Stage::iter_all().filter(|s| *s != Stage::Extra) {
+                match shot_type {
+                    Some(shot_type) => practices.push(PracticeScore::read_from(
+                        &mut src, shot_type, stage, difficulty,
+                    )?),
+                    /* the totals chapter's practice slots don't belong to any one shot type */
+                    None => skip_bytes::<8, _>(&mut src)?,
+                }
+            }
+        }
+
+        let mut cards = Vec::with_capacity(SpellId::iter_all().len());
+        for _ in SpellId::iter_all() {
+            cards.push(CardCareer::read_from(&mut src)?);
+        }
+
+        Ok(Self {
+            shot_type,
+            rankings,
+            total_play_count,
+            play_time,
+            clear_counts,
+            practices,
+            cards,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub enum Segment {
+    Clear(Box<ClearData>),
+    Unknown([u8; 2], u16, Box<[u8]>),
+}
+
+impl Segment {
+    pub fn signature(&self) -> &[u8; 2] {
+        match self {
+            Self::Clear(_) => b"CR",
+            Self::Unknown(sig, _, _) => sig,
+        }
+    }
+
+    fn signature_string(&self) -> String {
+        let v: Vec<u8> = self
+            .signature()
+            .iter()
+            .copied()
+            .flat_map(std::ascii::escape_default)
+            .collect();
+        String::from_utf8(v).unwrap()
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Option<Self>> {
+        let mut signature = [0; 2];
+        return_none_on_eof!(src.read_exact(&mut signature));
+        let version = return_none_on_eof!(src.read_u16::<FileOrder>());
+        let checksum = return_none_on_eof!(src.read_u32::<FileOrder>());
+        let size = return_none_on_eof!(src.read_u32::<FileOrder>());
+
+        if size <= 12 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid chapter size {}", size),
+            ));
+        }
+
+        /* don't trust the chapter size for preallocation, since it hasn't been checked yet */
+        let body_sz = (size as u64) - 12;
+        let mut data = Vec::new();
+        src.by_ref().take(body_sz).read_to_end(&mut data)?;
+        if (data.len() as u64) < body_sz {
+            return Ok(None);
+        }
+
+        let computed = size
+            .to_le_bytes()
+            .iter()
+            .chain(data.iter())
+            .fold(0u32, |acc, x| acc.wrapping_add(*x as u32));
+
+        if computed != checksum {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "chapter checksum does not match",
+            ));
+        }
+
+        let mut reader = Cursor::new(data);
+        match &signature {
+            b"CR" => ClearData::read_from(&mut reader).map(|data| Self::Clear(Box::new(data))),
+            _ => Ok(Self::Unknown(
+                signature,
+                version,
+                reader.into_inner().into(),
+            )),
+        }
+        .map(Some)
+    }
+}
+
+impl Debug for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Clear(d) => f
+                .debug_struct("Segment::Clear")
+                .field("signature", &self.signature_string())
+                .field("data", d)
+                .finish(),
+            Self::Unknown(_, version, d) => f
+                .debug_struct("Segment::Unknown")
+                .field("signature", &self.signature_string())
+                .field("version", version)
+                .field("data", &format!("[{} bytes]", d.len()))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ScoreReader<R> {
+    header: FileHeader,
+    src: StreamDecompressor<ThCrypt<io::Take<R>>>,
+}
+
+impl<R: Read> ScoreReader<R> {
+    pub fn new(mut src: R) -> Result<Self, io::Error> {
+        let header = FileHeader::read_from(&mut src)?;
+        let body_sz = header.encoded_body_sz();
+        let crypt = ThCrypt::new(src.take(body_sz as u64), 0xAC, 0x35, 0x10, Some(body_sz));
+        let src = StreamDecompressor::new(crypt);
+        Ok(Self { header, src })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for ScoreReader<R> {
+    type Item = Result<Segment, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Segment::read_from(&mut self.src).transpose()
+    }
+}
+
+/// Spell card capture statistics combined across every shot type.
+#[derive(Debug, Clone)]
+pub struct SpellCardData {
+    card_id: SpellId,
+    captures: [u32; 6],
+    attempts: [u32; 6],
+}
+
+impl SpellCardData {
+    fn new(card_id: SpellId) -> Self {
+        Self {
+            card_id,
+            captures: [0; 6],
+            attempts: [0; 6],
+        }
+    }
+
+    pub fn card_id(&self) -> SpellId {
+        self.card_id
+    }
+}
+
+impl SpellCardRecord<Touhou10> for SpellCardData {
+    fn card(&self) -> SpellCard<Touhou10> {
+        SpellCard::new(self.card_id)
+    }
+
+    fn shot_types(&self) -> &[ShotWrapper<Touhou10>] {
+        &Touhou10::SHOT_TYPES[..]
+    }
+
+    fn attempts(&self, shot: &ShotWrapper<Touhou10>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.attempts[idx]
+    }
+
+    fn captures(&self, shot: &ShotWrapper<Touhou10>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.captures[idx]
+    }
+
+    /// MoF does not record maximum bonuses for spell cards, so this always returns 0.
+    fn max_bonus(&self, _shot: &ShotWrapper<Touhou10>) -> u32 {
+        0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoreFile {
+    cards: Vec<SpellCardData>,
+    practices: Vec<PracticeScore>,
+    clears: Vec<ClearData>,
+}
+
+impl ScoreFile {
+    pub fn new<R: Read>(src: R) -> Result<Self, io::Error> {
+        Self::new_with_progress(src, |_| {})
+    }
+
+    /// Like [`Self::new`], but calls `on_segment` after each segment of the file is parsed.
+    ///
+    /// The callback receives the total number of segments read so far, which can be used to report progress.
+    pub fn new_with_progress<R: Read, F: FnMut(usize)>(
+        src: R,
+        mut on_segment: F,
+    ) -> Result<Self, io::Error> {
+        let mut cards: Vec<SpellCardData> = SpellId::iter_all().map(SpellCardData::new).collect();
+        let mut practices = Vec::new();
+        let mut clears = Vec::new();
+
+        for (count, segment) in ScoreReader::new(src)?.enumerate() {
+            if let Segment::Clear(data) = segment? {
+                if let Some(shot) = data.shot_type {
+                    let idx: usize = shot.into();
+                    for career in &data.cards {
+                        let card = &mut cards[(career.card_id.unwrap() - 1) as usize];
+                        card.captures[idx] = career.captures;
+                        card.attempts[idx] = career.attempts;
+                    }
+
+                    practices.extend(data.practices.iter().cloned());
+                }
+
+                clears.push(*data);
+            }
+
+            on_segment(count + 1);
+        }
+
+        Ok(Self {
+            cards,
+            practices,
+            clears,
+        })
+    }
+
+    /// Gets the clear data chapters recorded for each shot type.
+    pub fn clear_data(&self) -> &[ClearData] {
+        &self.clears[..]
+    }
+
+    /// Gets the clear data recorded for a specific shot type, or the totals across all shot types if `shot` is `None`.
+    pub fn clear_data_for(&self, shot: Option<ShotType>) -> Option<&ClearData> {
+        self.clears.iter().find(|data| data.shot_type == shot)
+    }
+}
+
+impl crate::score::ScoreFile<Touhou10> for ScoreFile {
+    type SpellCardRecord = SpellCardData;
+    type PracticeRecord = PracticeScore;
+
+    fn spell_cards(&self) -> &[SpellCardData] {
+        &self.cards[..]
+    }
+
+    fn practice_records(&self) -> &[PracticeScore] {
+        &self.practices[..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const HIGH_SCORE_SIZE: usize = 0x18;
+    const CARD_SIZE: usize = 0x80 + 0x10;
+    const PRACTICE_SIZE: usize = 8;
+
+    fn chapter(signature: &[u8; 2], version: u16, body: &[u8]) -> Vec<u8> {
+        let size = (body.len() + 12) as u32;
+        let checksum = size
+            .to_le_bytes()
+            .iter()
+            .chain(body.iter())
+            .fold(0u32, |acc, x| acc.wrapping_add(*x as u32));
+
+        let mut ret = signature.to_vec();
+        ret.extend_from_slice(&version.to_le_bytes());
+        ret.extend_from_slice(&checksum.to_le_bytes());
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret.extend_from_slice(body);
+        ret
+    }
+
+    fn clear_data_body(shot_id: u32) -> Vec<u8> {
+        let mut body = shot_id.to_le_bytes().to_vec();
+
+        /* first Normal ranking entry; every other entry is left zeroed */
+        for i in 0..(ClearData::RANKING_SIZE * 5) {
+            let mut entry = [0; HIGH_SCORE_SIZE];
+            if i == ClearData::RANKING_SIZE {
+                entry[..4].copy_from_slice(&1234u32.to_le_bytes());
+                entry[4] = 3;
+                entry[5] = 1;
+                entry[6..10].copy_from_slice(b"TEST");
+                entry[16..20].copy_from_slice(&1_700_000_000u32.to_le_bytes());
+                entry[20..24].copy_from_slice(&0.5f32.to_le_bytes());
+            }
+            body.extend_from_slice(&entry);
+        }
+
+        body.extend_from_slice(&42u32.to_le_bytes());
+        body.extend_from_slice(&123_456u32.to_le_bytes());
+        for count in [1u32, 2, 3, 4, 5] {
+            body.extend_from_slice(&count.to_le_bytes());
+        }
+
+        /* Easy stage 1 practice score, followed by the rest of the practice slots */
+        body.extend_from_slice(&5000u32.to_le_bytes());
+        body.extend_from_slice(&[0; PRACTICE_SIZE * 24 - 4]);
+
+        for (i, _) in SpellId::iter_all().enumerate() {
+            let mut card = [0; CARD_SIZE];
+            card[0x80..0x84].copy_from_slice(&(i as u32).to_le_bytes());
+            card[0x84..0x88].copy_from_slice(&(2 * i as u32).to_le_bytes());
+            card[0x88..0x8C].copy_from_slice(&(i as u32).to_le_bytes());
+            body.extend_from_slice(&card);
+        }
+
+        body
+    }
+
+    #[test]
+    fn parses_file_header() {
+        let mut raw = SIGNATURE.to_vec();
+        raw.extend_from_slice(&0x118u32.to_le_bytes());
+        raw.extend_from_slice(&[0; 8]);
+        raw.extend_from_slice(&0x100u32.to_le_bytes());
+        raw.extend_from_slice(&0x200u32.to_le_bytes());
+
+        let header = FileHeader::read_from(&mut Cursor::new(&raw)).unwrap();
+        assert_eq!(header.encoded_full_sz(), 0x118);
+        assert_eq!(header.encoded_body_sz(), 0x100);
+        assert_eq!(header.decoded_body_sz(), 0x200);
+
+        raw[4] += 1;
+        assert!(FileHeader::read_from(&mut Cursor::new(&raw)).is_err());
+
+        raw[..4].copy_from_slice(b"TH11");
+        assert!(FileHeader::read_from(&mut Cursor::new(&raw)).is_err());
+    }
+
+    #[test]
+    fn parses_chapter_header() {
+        let raw = chapter(b"ZZ", 3, &[1, 2, 3, 4]);
+
+        match Segment::read_from(Cursor::new(raw)).unwrap() {
+            Some(Segment::Unknown(signature, version, data)) => {
+                assert_eq!(&signature, b"ZZ");
+                assert_eq!(version, 3);
+                assert_eq!(&data[..], &[1, 2, 3, 4]);
+            }
+            other => panic!("unexpected segment {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_clear_data_chapter() {
+        let raw = chapter(b"CR", 1, &clear_data_body(2));
+        let mut src = Cursor::new(raw);
+
+        let data = match Segment::read_from(&mut src).unwrap() {
+            Some(Segment::Clear(data)) => data,
+            other => panic!("unexpected segment {:?}", other),
+        };
+
+        assert_eq!(data.shot_type(), Some(ShotType::ReimuC));
+
+        let top = &data.rankings(&Difficulty::Normal)[0];
+        assert_eq!(top.score(), 12341);
+        assert_eq!(top.progress(), 3);
+        assert_eq!(top.continues(), 1);
+        assert_eq!(top.name_str(), "TEST");
+        assert_eq!(top.timestamp(), 1_700_000_000);
+        assert_eq!(top.slow(), 0.5);
+        assert_eq!(data.rankings(&Difficulty::Easy)[0].score(), 0);
+
+        assert_eq!(data.total_play_count(), 42);
+        assert_eq!(data.play_time(), 123_456);
+        assert_eq!(data.clear_count(&Difficulty::Easy), 1);
+        assert_eq!(data.clear_count(&Difficulty::Extra), 5);
+
+        assert_eq!(data.practices().len(), 24);
+        assert_eq!(data.practices()[0].high_score(), 50000);
+        assert_eq!(data.practices()[0].stage(), StageWrapper::<Touhou10>::new(Stage::One));
+
+        assert_eq!(data.cards().len(), SpellId::iter_all().len());
+        for (i, card) in data.cards().iter().enumerate() {
+            assert_eq!(card.card_id().unwrap() as usize, i + 1);
+            assert_eq!(card.captures() as usize, i);
+            assert_eq!(card.attempts() as usize, 2 * i);
+        }
+
+        assert!(Segment::read_from(&mut src).unwrap().is_none());
+    }
+
+    #[test]
+    fn totals_chapter_has_no_practices() {
+        let shot_id = Touhou10::SHOT_TYPES.len() as u32;
+        let raw = chapter(b"CR", 1, &clear_data_body(shot_id));
+
+        match Segment::read_from(Cursor::new(raw)).unwrap() {
+            Some(Segment::Clear(data)) => {
+                assert_eq!(data.shot_type(), None);
+                assert!(data.practices().is_empty());
+            }
+            other => panic!("unexpected segment {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut raw = chapter(b"CR", 1, &clear_data_body(0));
+        raw[4] ^= 1;
+        assert!(Segment::read_from(Cursor::new(raw)).is_err());
+    }
+
+    #[test]
+    fn truncated_chapter_is_ignored() {
+        let mut raw = chapter(b"CR", 1, &clear_data_body(0));
+        raw.truncate(raw.len() - 1);
+        assert!(Segment::read_from(Cursor::new(raw)).unwrap().is_none());
+    }
+
+    #[test]
+    fn reads_encrypted_score_file() {
+        let mut body = chapter(b"CR", 1, &clear_data_body(2));
+        body.extend(chapter(b"CR", 1, &clear_data_body(4)));
+
+        let mut compressed = StreamCompressor::new(Vec::new());
+        compressed.write_all(&body).unwrap();
+        let encoded = encrypt(&compressed.finish().unwrap(), 0xAC, 0x35, 0x10);
+
+        let mut raw = SIGNATURE.to_vec();
+        raw.extend_from_slice(&((encoded.len() + FileHeader::SIZE) as u32).to_le_bytes());
+        raw.extend_from_slice(&[0; 8]);
+        raw.extend_from_slice(&(encoded.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        raw.extend_from_slice(&encoded);
+
+        let file = ScoreFile::new(Cursor::new(raw)).unwrap();
+        assert_eq!(file.clear_data().len(), 2);
+        assert!(file.clear_data_for(Some(ShotType::MarisaB)).is_some());
+
+        let card = &crate::score::ScoreFile::spell_cards(&file)[5];
+        let reimu_c = ShotWrapper::new(ShotType::ReimuC);
+        assert_eq!(card.captures(&reimu_c), 5);
+        assert_eq!(card.attempts(&reimu_c), 10);
+        assert_eq!(card.attempts(&ShotWrapper::new(ShotType::ReimuA)), 0);
+    }
+}
@@ -10,6 +10,11 @@ mod spellcards;
 #[cfg(feature = "memory")]
 pub mod memory;
 
+#[cfg(feature = "score-file")]
+pub mod score;
+
+#[cfg(feature = "score-file")]
+pub use score::ScoreFile;
 pub use spellcards::SpellId;
 
 define_game! {
@@ -47,7 +52,9 @@ define_game! {
             Three: "Stage 3",
             Four: "Stage 4",
             Five: "Stage 5",
+            #[final_stage]
             Six: "Stage 6",
+            #[extra]
             Extra: "Extra Stage"
         }
     }
@@ -0,0 +1,889 @@
+//! Reading support for LoLK's `scoreth15.dat` score file.
+//!
+//! This uses the same container format as MoF's score file: a plain header followed by an encrypted and compressed body,
+//! split into checksummed chapters. Most of the data within each clear data chapter is recorded separately for
+//! Pointdevice and Legacy modes.
+
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::io::{self, Cursor, ErrorKind, Read};
+
+use byteorder::ReadBytesExt;
+
+use super::{Difficulty, ShotType, SpellId, Stage, Touhou15};
+use crate::score::*;
+use crate::types::{
+    AllIterable, Difficulty as DifficultyWrapper, ShotType as ShotWrapper, SpellCard,
+    Stage as StageWrapper,
+};
+
+/// The byte order used for multi-byte values in this game's score files.
+type FileOrder = byteorder::LittleEndian;
+
+const SIGNATURE: &[u8; 4] = b"TH51";
+
+fn read_raw_buffer<const N: usize, R: Read>(mut src: R) -> io::Result<Box<[u8]>> {
+    let mut buf = vec![0u8; N];
+    src.read_exact(&mut buf[..])?;
+    Ok(buf.into())
+}
+
+/// The unencrypted header at the start of the score file.
+#[derive(Debug, Clone, Copy)]
+pub struct FileHeader {
+    encoded_full_sz: usize,
+    encoded_body_sz: usize,
+    decoded_body_sz: usize,
+}
+
+impl FileHeader {
+    const SIZE: usize = 0x18;
+
+    pub fn read_from<R: ReadBytesExt>(src: &mut R) -> Result<Self, io::Error> {
+        let mut signature = [0; 4];
+        src.read_exact(&mut signature[..])?;
+
+        if signature != *SIGNATURE {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "invalid score file signature",
+            ));
+        }
+
+        let encoded_full_sz = src.read_u32::<FileOrder>()? as usize;
+        skip_bytes::<8, _>(&mut *src)?;
+        let encoded_body_sz = src.read_u32::<FileOrder>()? as usize;
+        let decoded_body_sz = src.read_u32::<FileOrder>()? as usize;
+
+        if encoded_full_sz.checked_sub(encoded_body_sz) != Some(Self::SIZE) {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "score file header sizes are inconsistent",
+            ));
+        }
+
+        Ok(Self {
+            encoded_full_sz,
+            encoded_body_sz,
+            decoded_body_sz,
+        })
+    }
+
+    pub fn encoded_full_sz(&self) -> usize {
+        self.encoded_full_sz
+    }
+
+    pub fn encoded_body_sz(&self) -> usize {
+        self.encoded_body_sz
+    }
+
+    pub fn decoded_body_sz(&self) -> usize {
+        self.decoded_body_sz
+    }
+}
+
+/// The two game modes available in LoLK.
+///
+/// Clear records, high scores, and spell card statistics are all tracked separately for each mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum GameMode {
+    /// Pointdevice mode, with infinite lives and checkpoint restarts.
+    Pointdevice,
+    /// Legacy mode, which plays like the older games.
+    Legacy,
+}
+
+impl GameMode {
+    /// All game modes, in the order they are stored in the score file.
+    pub const ALL: [Self; 2] = [Self::Pointdevice, Self::Legacy];
+}
+
+/// A high score entry from one of the per-difficulty rankings.
+#[derive(Debug, Clone)]
+pub struct HighScore {
+    score: u32,
+    progress: u8,
+    continues: u8,
+    name: [u8; 10],
+    timestamp: u32,
+    slow: f32,
+    retries: u32,
+}
+
+impl HighScore {
+    /// Gets the score for this entry, as displayed in-game.
+    ///
+    /// As with MoF, scores are stored without their last digit, which is always the number of continues used.
+    pub fn score(&self) -> u64 {
+        (self.score as u64) * 10 + (self.continues as u64)
+    }
+
+    /// Gets the raw stage progress value for this entry.
+    pub fn progress(&self) -> u8 {
+        self.progress
+    }
+
+    pub fn continues(&self) -> u8 {
+        self.continues
+    }
+
+    pub fn name(&self) -> &[u8] {
+        &self.name[..]
+    }
+
+    /// Gets the name entered for this score, decoded from CP932.
+    pub fn name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.name)
+    }
+
+    /// Gets the time at which this score was set, as a Unix timestamp.
+    pub fn timestamp(&self) -> u32 {
+        self.timestamp
+    }
+
+    pub fn slow(&self) -> f32 {
+        self.slow
+    }
+
+    /// Gets the number of checkpoint restarts used during this run (always 0 for Legacy mode scores).
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let score = src.read_u32::<FileOrder>()?;
+        let progress = src.read_u8()?;
+        let continues = src.read_u8()?;
+        let mut name = [0; 10];
+        src.read_exact(&mut name)?;
+        let timestamp = src.read_u32::<FileOrder>()?;
+        skip_bytes::<4, _>(&mut src)?;
+        let slow = src.read_f32::<FileOrder>()?;
+        let retries = src.read_u32::<FileOrder>()?;
+
+        Ok(Self {
+            score,
+            progress,
+            continues,
+            name,
+            timestamp,
+            slow,
+            retries,
+        })
+    }
+}
+
+/// The best score achieved in stage practice for a single stage and difficulty.
+#[derive(Debug, Clone)]
+pub struct PracticeScore {
+    shot_type: ShotType,
+    stage: Stage,
+    difficulty: Difficulty,
+    high_score: u32,
+    cleared: bool,
+    unlocked: bool,
+}
+
+impl PracticeScore {
+    pub fn read_from<R: Read>(
+        mut src: R,
+        shot_type: ShotType,
+        stage: Stage,
+        difficulty: Difficulty,
+    ) -> io::Result<Self> {
+        let high_score = src.read_u32::<FileOrder>()?;
+        let cleared = src.read_u8()? != 0;
+        let unlocked = src.read_u8()? != 0;
+        skip_bytes::<2, _>(&mut src)?;
+
+        Ok(Self {
+            shot_type,
+            stage,
+            difficulty,
+            high_score,
+            cleared,
+            unlocked,
+        })
+    }
+
+    /// Gets whether this stage has been cleared in stage practice.
+    pub fn cleared(&self) -> bool {
+        self.cleared
+    }
+
+    /// Gets whether this stage has been unlocked for stage practice.
+    pub fn unlocked(&self) -> bool {
+        self.unlocked
+    }
+}
+
+impl PracticeRecord<Touhou15> for PracticeScore {
+    /// Gets the high score for this stage, as displayed in-game.
+    fn high_score(&self) -> u32 {
+        self.high_score.saturating_mul(10)
+    }
+
+    /// LoLK does not record how many times each stage has been practiced, so this always returns 0.
+    fn attempts(&self) -> u32 {
+        0
+    }
+
+    fn shot_type(&self) -> ShotWrapper<Touhou15> {
+        ShotWrapper::new(self.shot_type)
+    }
+
+    fn difficulty(&self) -> DifficultyWrapper<Touhou15> {
+        DifficultyWrapper::new(self.difficulty)
+    }
+
+    fn stage(&self) -> StageWrapper<Touhou15> {
+        StageWrapper::new(self.stage)
+    }
+}
+
+/// Capture statistics for a single spell card, as recorded for a single shot type and game mode.
+#[derive(Debug, Clone)]
+pub struct CardCareer {
+    card_id: SpellId,
+    card_name: Box<[u8]>, // 0x80 bytes, CP932
+    captures: u32,
+    practice_captures: u32,
+    attempts: u32,
+    practice_attempts: u32,
+    practice_score: u32,
+}
+
+impl CardCareer {
+    pub fn card_id(&self) -> SpellId {
+        self.card_id
+    }
+
+    pub fn card_name(&self) -> &[u8] {
+        &self.card_name[..]
+    }
+
+    /// Gets the Japanese name of this card as stored in the score file, decoded from CP932.
+    pub fn card_name_str(&self) -> Cow<'_, str> {
+        decode_cp932(&self.card_name)
+    }
+
+    /// Decodes the Japanese name of this card into `buf`, reusing its allocation; see [`decode_cp932_into`].
+    pub fn decode_card_name_into(&self, buf: &mut String) {
+        decode_cp932_into(&self.card_name, buf)
+    }
+
+    pub fn captures(&self) -> u32 {
+        self.captures
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn practice_captures(&self) -> u32 {
+        self.practice_captures
+    }
+
+    pub fn practice_attempts(&self) -> u32 {
+        self.practice_attempts
+    }
+
+    /// Gets the high score for this card in spell practice, as displayed in-game.
+    pub fn practice_score(&self) -> u32 {
+        self.practice_score.saturating_mul(10)
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let card_name = read_raw_buffer::<0x80, _>(&mut src)?;
+        let captures = src.read_u32::<FileOrder>()?;
+        let practice_captures = src.read_u32::<FileOrder>()?;
+        let attempts = src.read_u32::<FileOrder>()?;
+        let practice_attempts = src.read_u32::<FileOrder>()?;
+        let card_id = src
+            .read_u32::<FileOrder>()
+            .map(|x| x + 1)
+            .and_then(try_into_or_io_error(ErrorKind::InvalidData))?;
+        skip_bytes::<4, _>(&mut src)?; // card difficulty
+        let practice_score = src.read_u32::<FileOrder>()?;
+
+        Ok(Self {
+            card_id,
+            card_name,
+            captures,
+            practice_captures,
+            attempts,
+            practice_attempts,
+            practice_score,
+        })
+    }
+}
+
+/// Clear data for a single shot type within a single game mode.
+#[derive(Debug, Clone)]
+pub struct ModeClearData {
+    mode: GameMode,
+    rankings: Vec<HighScore>,
+    cards: Vec<CardCareer>,
+    total_play_count: u32,
+    play_time: u32,
+    clear_counts: [u32; 5],
+    clear_flags: [u32; 5],
+}
+
+impl ModeClearData {
+    const RANKING_SIZE: usize = 10;
+
+    /// Gets the game mode this data was recorded in.
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    /// Gets the high score rankings for a given difficulty.
+    pub fn rankings(&self, difficulty: &Difficulty) -> &[HighScore] {
+        let idx: usize = difficulty.into();
+        &self.rankings[(idx * Self::RANKING_SIZE)..((idx + 1) * Self::RANKING_SIZE)]
+    }
+
+    pub fn cards(&self) -> &[CardCareer] {
+        &self.cards[..]
+    }
+
+    pub fn total_play_count(&self) -> u32 {
+        self.total_play_count
+    }
+
+    /// Gets the total play time recorded in this data, in frames.
+    pub fn play_time(&self) -> u32 {
+        self.play_time
+    }
+
+    pub fn clear_count(&self, difficulty: &Difficulty) -> u32 {
+        let idx: usize = difficulty.into();
+        self.clear_counts[idx]
+    }
+
+    /// Gets whether the game has been cleared on the given difficulty.
+    pub fn is_cleared(&self, difficulty: &Difficulty) -> bool {
+        let idx: usize = difficulty.into();
+        self.clear_flags[idx] != 0
+    }
+
+    pub fn read_from<R: Read>(mut src: R, mode: GameMode) -> io::Result<Self> {
+        /* rankings, clear counts, and clear flags each have an extra trailing slot for totals, which is unused */
+        let mut rankings = Vec::with_capacity(Self::RANKING_SIZE * 5);
+        for _ in 0..(Self::RANKING_SIZE * 5) {
+            rankings.push(HighScore::read_from(&mut src)?);
+        }
+
+        for _ in 0..Self::RANKING_SIZE {
+            HighScore::read_from(&mut src)?;
+        }
+
+        skip_bytes::<0x140, _>(&mut src)?;
+
+        let mut cards = Vec::with_capacity(SpellId::iter_all().len());
+        for _ in SpellId::iter_all() {
+            cards.push(CardCareer::read_from(&mut src)?);
+        }
+
+        let total_play_count = src.read_u32::<FileOrder>()?;
+        let play_time = src.read_u32::<FileOrder>()?;
+
+        let mut clear_counts = [0; 5];
+        for count in clear_counts.iter_mut() {
+            *count = src.read_u32::<FileOrder>()?;
+        }
+        skip_bytes::<4, _>(&mut src)?;
+
+        let mut clear_flags = [0; 5];
+        for flag in clear_flags.iter_mut() {
+            *flag = src.read_u32::<FileOrder>()?;
+        }
+        skip_bytes::<4, _>(&mut src)?;
+
+        Ok(Self {
+            mode,
+            rankings,
+            cards,
+            total_play_count,
+            play_time,
+            clear_counts,
+            clear_flags,
+        })
+    }
+}
+
+/// The contents of a clear data (`CR`) chapter.
+///
+/// The score file contains one of these for each shot type, plus one more containing totals across all shot types.
+#[derive(Debug, Clone)]
+pub struct ClearData {
+    shot_type: Option<ShotType>,
+    modes: [ModeClearData; 2],
+    practices: Vec<PracticeScore>,
+}
+
+impl ClearData {
+    /// Gets the shot type for this data, or `None` if this contains totals for all shot types.
+    pub fn shot_type(&self) -> Option<ShotType> {
+        self.shot_type
+    }
+
+    /// Gets the clear data recorded for the given game mode.
+    pub fn mode(&self, mode: GameMode) -> &ModeClearData {
+        &self.modes[mode as usize]
+    }
+
+    /// Gets the clear data recorded for each game mode.
+    pub fn modes(&self) -> &[ModeClearData] {
+        &self.modes[..]
+    }
+
+    /// Gets the stage practice records for this shot type.
+    ///
+    /// Stage practice scores are not tracked separately per game mode. This is empty for the data containing
+    /// totals for all shot types.
+    pub fn practices(&self) -> &[PracticeScore] {
+        &self.practices[..]
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Self> {
+        let shot_id = src.read_u32::<FileOrder>()?;
+        let shot_type = if (shot_id as usize) == Touhou15::SHOT_TYPES.len() {
+            None
+        } else {
+            Some(try_into_or_io_error(ErrorKind::InvalidData)(shot_id as u8)?)
+        };
+
+        let modes = [
+            ModeClearData::read_from(&mut src, GameMode::Pointdevice)?,
+            ModeClearData::read_from(&mut src, GameMode::Legacy)?,
+        ];
+
+        /* practice slots exist for every difficulty and stage (plus one unused slot per difficulty),
+         * but only the main stages on the main difficulties can actually be practiced */
+        let mut practices = Vec::with_capacity(24);
+        for difficulty in Difficulty::iter_all() {
+            for stage in Stage::iter_all() {
+                /* the totals chapter's practice slots don't belong to any one shot type */
+                match shot_type {
+                    Some(shot_type) if difficulty != Difficulty::Extra && stage != Stage::Extra => {
+                        practices.push(PracticeScore::read_from(
+                            &mut src, shot_type, stage, difficulty,
+                        )?);
+                    }
+                    _ => skip_bytes::<8, _>(&mut src)?,
+                }
+            }
+
+            skip_bytes::<8, _>(&mut src)?;
+        }
+
+        Ok(Self {
+            shot_type,
+            modes,
+            practices,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub enum Segment {
+    Clear(Box<ClearData>),
+    Unknown([u8; 2], u16, Box<[u8]>),
+}
+
+impl Segment {
+    pub fn signature(&self) -> &[u8; 2] {
+        match self {
+            Self::Clear(_) => b"CR",
+            Self::Unknown(sig, _, _) => sig,
+        }
+    }
+
+    fn signature_string(&self) -> String {
+        let v: Vec<u8> = self
+            .signature()
+            .iter()
+            .copied()
+            .flat_map(std::ascii::escape_default)
+            .collect();
+        String::from_utf8(v).unwrap()
+    }
+
+    pub fn read_from<R: Read>(mut src: R) -> io::Result<Option<Self>> {
+        let mut signature = [0; 2];
+        return_none_on_eof!(src.read_exact(&mut signature));
+        let version = return_none_on_eof!(src.read_u16::<FileOrder>());
+        let checksum = return_none_on_eof!(src.read_u32::<FileOrder>());
+        let size = return_none_on_eof!(src.read_u32::<FileOrder>());
+
+        if size <= 12 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid chapter size {}", size),
+            ));
+        }
+
+        /* don't trust the chapter size for preallocation, since it hasn't been checked yet */
+        let body_sz = (size as u64) - 12;
+        let mut data = Vec::new();
+        src.by_ref().take(body_sz).read_to_end(&mut data)?;
+        if (data.len() as u64) < body_sz {
+            return Ok(None);
+        }
+
+        let computed = size
+            .to_le_bytes()
+            .iter()
+            .chain(data.iter())
+            .fold(0u32, |acc, x| acc.wrapping_add(*x as u32));
+
+        if computed != checksum {
+            return Err(io::Error::new(
+                ErrorKind::InvalidData,
+                "chapter checksum does not match",
+            ));
+        }
+
+        let mut reader = Cursor::new(data);
+        match &signature {
+            b"CR" => ClearData::read_from(&mut reader).map(|data| Self::Clear(Box::new(data))),
+            _ => Ok(Self::Unknown(
+                signature,
+                version,
+                reader.into_inner().into(),
+            )),
+        }
+        .map(Some)
+    }
+}
+
+impl Debug for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Clear(d) => f
+                .debug_struct("Segment::Clear")
+                .field("signature", &self.signature_string())
+                .field("data", d)
+                .finish(),
+            Self::Unknown(_, version, d) => f
+                .debug_struct("Segment::Unknown")
+                .field("signature", &self.signature_string())
+                .field("version", version)
+                .field("data", &format!("[{} bytes]", d.len()))
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ScoreReader<R> {
+    header: FileHeader,
+    src: StreamDecompressor<ThCrypt<io::Take<R>>>,
+}
+
+impl<R: Read> ScoreReader<R> {
+    pub fn new(mut src: R) -> Result<Self, io::Error> {
+        let header = FileHeader::read_from(&mut src)?;
+        let body_sz = header.encoded_body_sz();
+        let crypt = ThCrypt::new(src.take(body_sz as u64), 0xAC, 0x35, 0x10, Some(body_sz));
+        let src = StreamDecompressor::new(crypt);
+        Ok(Self { header, src })
+    }
+
+    pub fn header(&self) -> &FileHeader {
+        &self.header
+    }
+}
+
+impl<R: Read> Iterator for ScoreReader<R> {
+    type Item = Result<Segment, io::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Segment::read_from(&mut self.src).transpose()
+    }
+}
+
+/// Spell card capture statistics combined across every shot type.
+///
+/// Depending on how this was obtained, this either contains statistics for a single game mode,
+/// or statistics summed across both game modes.
+#[derive(Debug, Clone)]
+pub struct SpellCardData {
+    card_id: SpellId,
+    mode: Option<GameMode>,
+    captures: [u32; 4],
+    attempts: [u32; 4],
+    practice_captures: [u32; 4],
+    practice_attempts: [u32; 4],
+    practice_scores: [u32; 4],
+}
+
+impl SpellCardData {
+    fn new(card_id: SpellId, mode: Option<GameMode>) -> Self {
+        Self {
+            card_id,
+            mode,
+            captures: [0; 4],
+            attempts: [0; 4],
+            practice_captures: [0; 4],
+            practice_attempts: [0; 4],
+            practice_scores: [0; 4],
+        }
+    }
+
+    fn add_career(&mut self, shot: ShotType, career: &CardCareer) {
+        let idx: usize = shot.into();
+        self.captures[idx] += career.captures;
+        self.attempts[idx] += career.attempts;
+        self.practice_captures[idx] += career.practice_captures;
+        self.practice_attempts[idx] += career.practice_attempts;
+        self.practice_scores[idx] = self.practice_scores[idx].max(career.practice_score());
+    }
+
+    pub fn card_id(&self) -> SpellId {
+        self.card_id
+    }
+
+    /// Gets the game mode these statistics were recorded in, or `None` if they are totals across both modes.
+    pub fn mode(&self) -> Option<GameMode> {
+        self.mode
+    }
+
+    pub fn practice_attempts(&self, shot: &ShotWrapper<Touhou15>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.practice_attempts[idx]
+    }
+
+    pub fn practice_captures(&self, shot: &ShotWrapper<Touhou15>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.practice_captures[idx]
+    }
+
+    /// Gets the best spell practice score for this card with the given shot type, as displayed in-game.
+    pub fn practice_score(&self, shot: &ShotWrapper<Touhou15>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.practice_scores[idx]
+    }
+}
+
+impl SpellCardRecord<Touhou15> for SpellCardData {
+    fn card(&self) -> SpellCard<Touhou15> {
+        SpellCard::new(self.card_id)
+    }
+
+    fn shot_types(&self) -> &[ShotWrapper<Touhou15>] {
+        &Touhou15::SHOT_TYPES[..]
+    }
+
+    fn attempts(&self, shot: &ShotWrapper<Touhou15>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.attempts[idx]
+    }
+
+    fn captures(&self, shot: &ShotWrapper<Touhou15>) -> u32 {
+        let idx: usize = shot.unwrap().into();
+        self.captures[idx]
+    }
+
+    /// LoLK does not record maximum bonuses for spell cards, so this always returns 0.
+    fn max_bonus(&self, _shot: &ShotWrapper<Touhou15>) -> u32 {
+        0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoreFile {
+    cards: Vec<SpellCardData>,
+    mode_cards: [Vec<SpellCardData>; 2],
+    practices: Vec<PracticeScore>,
+    clears: Vec<ClearData>,
+}
+
+impl ScoreFile {
+    pub fn new<R: Read>(src: R) -> Result<Self, io::Error> {
+        Self::new_with_progress(src, |_| {})
+    }
+
+    /// Like [`Self::new`], but calls `on_segment` after each segment of the file is parsed.
+    ///
+    /// The callback receives the total number of segments read so far, which can be used to report progress.
+    pub fn new_with_progress<R: Read, F: FnMut(usize)>(
+        src: R,
+        mut on_segment: F,
+    ) -> Result<Self, io::Error> {
+        let new_cards = |mode| -> Vec<SpellCardData> {
+            SpellId::iter_all()
+                .map(|id| SpellCardData::new(id, mode))
+                .collect()
+        };
+
+        let mut cards = new_cards(None);
+        let mut mode_cards = GameMode::ALL.map(|mode| new_cards(Some(mode)));
+        let mut practices = Vec::new();
+        let mut clears = Vec::new();
+
+        for (count, segment) in ScoreReader::new(src)?.enumerate() {
+            if let Segment::Clear(data) = segment? {
+                if let Some(shot) = data.shot_type {
+                    for mode_data in &data.modes {
+                        for career in &mode_data.cards {
+                            let idx = (career.card_id.unwrap() - 1) as usize;
+                            cards[idx].add_career(shot, career);
+                            mode_cards[mode_data.mode as usize][idx].add_career(shot, career);
+                        }
+                    }
+
+                    practices.extend(data.practices.iter().cloned());
+                }
+
+                clears.push(*data);
+            }
+
+            on_segment(count + 1);
+        }
+
+        Ok(Self {
+            cards,
+            mode_cards,
+            practices,
+            clears,
+        })
+    }
+
+    /// Gets spell card statistics recorded in a single game mode.
+    ///
+    /// The statistics returned by [`spell_cards`](crate::score::ScoreFile::spell_cards) are summed across both modes.
+    pub fn spell_cards_for_mode(&self, mode: GameMode) -> &[SpellCardData] {
+        &self.mode_cards[mode as usize][..]
+    }
+
+    /// Gets the clear data chapters recorded for each shot type.
+    pub fn clear_data(&self) -> &[ClearData] {
+        &self.clears[..]
+    }
+
+    /// Gets the clear data recorded for a specific shot type, or the totals across all shot types if `shot` is `None`.
+    pub fn clear_data_for(&self, shot: Option<ShotType>) -> Option<&ClearData> {
+        self.clears.iter().find(|data| data.shot_type == shot)
+    }
+}
+
+impl crate::score::ScoreFile<Touhou15> for ScoreFile {
+    type SpellCardRecord = SpellCardData;
+    type PracticeRecord = PracticeScore;
+
+    fn spell_cards(&self) -> &[SpellCardData] {
+        &self.cards[..]
+    }
+
+    fn practice_records(&self) -> &[PracticeScore] {
+        &self.practices[..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HIGH_SCORE_SIZE: usize = 0x20;
+    const CARD_SIZE: usize = 0x80 + 0x1C;
+    const PRACTICE_SIZE: usize = 8;
+
+    fn mode_size() -> usize {
+        (HIGH_SCORE_SIZE * 60) + 0x140 + (CARD_SIZE * SpellId::iter_all().len()) + 8 + 24 + 24
+    }
+
+    fn chapter(signature: &[u8; 2], version: u16, body: &[u8]) -> Vec<u8> {
+        let size = (body.len() + 12) as u32;
+        let checksum = size
+            .to_le_bytes()
+            .iter()
+            .chain(body.iter())
+            .fold(0u32, |acc, x| acc.wrapping_add(*x as u32));
+
+        let mut ret = signature.to_vec();
+        ret.extend_from_slice(&version.to_le_bytes());
+        ret.extend_from_slice(&checksum.to_le_bytes());
+        ret.extend_from_slice(&size.to_le_bytes());
+        ret.extend_from_slice(body);
+        ret
+    }
+
+    fn clear_data_body(shot_id: u32) -> Vec<u8> {
+        let n_practices = Difficulty::iter_all().len() * (Stage::iter_all().len() + 1);
+        let mut body = vec![0; 4 + (mode_size() * 2) + (n_practices * PRACTICE_SIZE)];
+        body[..4].copy_from_slice(&shot_id.to_le_bytes());
+
+        /* first Pointdevice ranking entry, and the capture count of the first card */
+        let rankings = 4;
+        body[rankings..rankings + 4].copy_from_slice(&1234u32.to_le_bytes());
+        let cards = rankings + (HIGH_SCORE_SIZE * 60) + 0x140;
+        body[cards + 0x80..cards + 0x84].copy_from_slice(&7u32.to_le_bytes());
+
+        /* total play count for Legacy mode */
+        let play_count = 4 + mode_size() + mode_size() - 56;
+        body[play_count..play_count + 4].copy_from_slice(&42u32.to_le_bytes());
+
+        /* Easy stage 1 practice score */
+        let practices = 4 + (mode_size() * 2);
+        body[practices..practices + 4].copy_from_slice(&5000u32.to_le_bytes());
+        body[practices + 4] = 1;
+
+        body
+    }
+
+    #[test]
+    fn parses_clear_data_chapter() {
+        let raw = chapter(b"CR", 1, &clear_data_body(0));
+        let mut src = Cursor::new(raw);
+
+        let data = match Segment::read_from(&mut src).unwrap() {
+            Some(Segment::Clear(data)) => data,
+            other => panic!("unexpected segment {:?}", other),
+        };
+
+        assert_eq!(data.shot_type(), Some(ShotType::Reimu));
+
+        let pointdevice = data.mode(GameMode::Pointdevice);
+        assert_eq!(pointdevice.rankings(&Difficulty::Easy)[0].score(), 12340);
+        assert_eq!(pointdevice.cards()[0].captures(), 7);
+        assert_eq!(data.mode(GameMode::Legacy).total_play_count(), 42);
+
+        assert_eq!(data.practices().len(), 24);
+        assert_eq!(data.practices()[0].high_score(), 50000);
+        assert!(data.practices()[0].cleared());
+
+        assert!(Segment::read_from(&mut src).unwrap().is_none());
+    }
+
+    #[test]
+    fn totals_chapter_has_no_practices() {
+        let shot_id = Touhou15::SHOT_TYPES.len() as u32;
+        let raw = chapter(b"CR", 1, &clear_data_body(shot_id));
+
+        match Segment::read_from(Cursor::new(raw)).unwrap() {
+            Some(Segment::Clear(data)) => {
+                assert_eq!(data.shot_type(), None);
+                assert!(data.practices().is_empty());
+            }
+            other => panic!("unexpected segment {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_bad_checksum() {
+        let mut raw = chapter(b"CR", 1, &clear_data_body(0));
+        raw[4] ^= 1;
+        assert!(Segment::read_from(Cursor::new(raw)).is_err());
+    }
+
+    #[test]
+    fn truncated_chapter_does_not_preallocate() {
+        let mut raw = b"CR\x01\x00\x00\x00\x00\x00".to_vec();
+        raw.extend_from_slice(&u32::MAX.to_le_bytes());
+        raw.extend_from_slice(&[0; 16]);
+        assert!(Segment::read_from(Cursor::new(raw)).unwrap().is_none());
+    }
+}
@@ -14,9 +14,11 @@
 //!
 //! Finally, there is the [`GameTracker`] type which can be used to connect a tracker to a driver;
 //! it provides a simple interface to poll a driver for new events and retrieve the results of finished runs from the associated tracker.
+//! The [`GameWatcher`] type builds on this by handling attaching to and detaching from game processes as well.
 
 use std::fmt::Display;
 use std::hash::Hash;
+use std::time::Duration;
 
 use crate::memory::HasLocations;
 
@@ -32,15 +34,23 @@ pub mod driver;
 
 pub mod time;
 
+pub mod export;
+
+pub mod watcher;
+
 pub(crate) use driver::{DriveTracker, UpdateStatus};
 #[doc(inline)]
 pub use driver::{GameTracker, IntoGameTracker};
 #[doc(inline)]
+pub use export::EventLog;
+#[doc(inline)]
 pub use state::LocationResolveFilter;
 #[doc(inline)]
 pub use time::{EventTime, GameTimeCounter};
 #[doc(inline)]
 pub use tracker::{TrackGame, TrackRun, TrackSpellPractice, TrackStagePractice, UpdateTracker};
+#[doc(inline)]
+pub use watcher::{GameWatcher, WatcherEvent};
 
 use crate::memory::Location;
 
@@ -58,6 +68,22 @@ pub enum Event<G: TrackableGame> {
     Miss,
     Bomb,
     Continue,
+    /// The player's life stock increased from an extend.
+    ///
+    /// This is not emitted when lives are restored by a continue.
+    ExtendLife,
+    /// The player's bomb stock increased, e.g. from a bomb item.
+    ///
+    /// This is not emitted when bombs are reset by a miss or continue.
+    BombRefill,
+    /// The current run or practice attempt has ended.
+    ///
+    /// `retried` is set if the player chose to restart from the pause or game over menus,
+    /// as opposed to the run ending via a game over, clearing the game, or quitting to the title screen.
+    RunEnd {
+        cleared: bool,
+        retried: bool,
+    },
     GameSpecific(G::Event),
 }
 
@@ -70,6 +96,34 @@ impl<G: TrackableGame> Event<G> {
             Self::Bomb => 3,
             Self::Continue => 4,
             Self::GameSpecific(_) => 5,
+            Self::RunEnd { .. } => 6,
+            Self::ExtendLife => 7,
+            Self::BombRefill => 8,
+        }
+    }
+
+    /// Gets the display name for events that aren't game-specific.
+    fn standard_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Pause => Some("Pause"),
+            Self::Unpause => Some("Unpause"),
+            Self::Miss => Some("Miss"),
+            Self::Bomb => Some("Bomb"),
+            Self::Continue => Some("Continue"),
+            Self::ExtendLife => Some("Extend"),
+            Self::BombRefill => Some("Bomb Refill"),
+            Self::RunEnd { retried: true, .. } => Some("Retry"),
+            Self::RunEnd { cleared: true, .. } => Some("Cleared"),
+            Self::RunEnd { .. } => Some("Run End"),
+            Self::GameSpecific(_) => None,
+        }
+    }
+
+    fn run_end_key(&self) -> Option<(bool, bool)> {
+        if let Self::RunEnd { cleared, retried } = self {
+            Some((*cleared, *retried))
+        } else {
+            None
         }
     }
 }
@@ -87,6 +141,12 @@ where
             Self::Pause => Self::Pause,
             Self::Unpause => Self::Unpause,
             Self::Continue => Self::Continue,
+            Self::ExtendLife => Self::ExtendLife,
+            Self::BombRefill => Self::BombRefill,
+            Self::RunEnd { cleared, retried } => Self::RunEnd {
+                cleared: *cleared,
+                retried: *retried,
+            },
         }
     }
 }
@@ -106,6 +166,8 @@ where
     fn eq(&self, other: &Self) -> bool {
         if let (Self::GameSpecific(a), Self::GameSpecific(b)) = (self, other) {
             a.eq(b)
+        } else if let (Self::RunEnd { .. }, Self::RunEnd { .. }) = (self, other) {
+            self.run_end_key() == other.run_end_key()
         } else {
             self.event_type_id() == other.event_type_id()
         }
@@ -127,6 +189,8 @@ where
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         if let (Self::GameSpecific(a), Self::GameSpecific(b)) = (self, other) {
             a.partial_cmp(b)
+        } else if let (Self::RunEnd { .. }, Self::RunEnd { .. }) = (self, other) {
+            Some(self.run_end_key().cmp(&other.run_end_key()))
         } else {
             Some(self.event_type_id().cmp(&other.event_type_id()))
         }
@@ -141,6 +205,8 @@ where
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         if let (Self::GameSpecific(a), Self::GameSpecific(b)) = (self, other) {
             a.cmp(b)
+        } else if let (Self::RunEnd { .. }, Self::RunEnd { .. }) = (self, other) {
+            self.run_end_key().cmp(&other.run_end_key())
         } else {
             self.event_type_id().cmp(&other.event_type_id())
         }
@@ -157,6 +223,7 @@ where
         if let Self::GameSpecific(data) = self {
             data.hash(state);
         }
+        self.run_end_key().hash(state);
     }
 }
 
@@ -167,12 +234,8 @@ where
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Pause => "Pause".fmt(f),
-            Self::Unpause => "Unpause".fmt(f),
-            Self::Miss => "Miss".fmt(f),
-            Self::Bomb => "Bomb".fmt(f),
-            Self::Continue => "Continue".fmt(f),
             Self::GameSpecific(inner) => inner.fmt(f),
+            _ => self.standard_name().unwrap_or_default().fmt(f),
         }
     }
 }
@@ -212,6 +275,21 @@ impl<G: TrackableGame, T, L, B, C, P> TrackerState<G, T, L, B, C, P> {
         self.time.start_time()
     }
 
+    /// Get the total time elapsed since tracking started, including time spent paused.
+    pub fn elapsed(&self) -> Duration {
+        self.time.elapsed()
+    }
+
+    /// Get the total time elapsed since tracking started, not including time spent paused.
+    pub fn elapsed_unpaused(&self) -> Duration {
+        self.time.elapsed_unpaused()
+    }
+
+    /// Get whether the game was paused as of the last update.
+    pub fn is_paused(&self) -> bool {
+        self.time.is_paused()
+    }
+
     pub fn location(&self) -> Option<Location<G>> {
         self.location_filter.actual_location()
     }
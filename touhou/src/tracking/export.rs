@@ -0,0 +1,470 @@
+//! A tracker that records every event in a game, for exporting to other tools.
+
+use std::io::{self, Write};
+use std::time::{Duration, UNIX_EPOCH};
+
+use super::tracker::{TrackGame, TrackRun, TrackSpellPractice, TrackStagePractice, UpdateTracker};
+use super::{Event, EventTime, TrackableGame, TrackingType};
+use crate::types::ShotPower;
+use crate::{Difficulty, Location, ShotType, Stage};
+
+/// Trait for tracked game states that can report the player's resources for an [`EventLog`].
+pub trait ExportState<G: TrackableGame> {
+    /// The player's current stock of lives.
+    fn lives(&self) -> Option<u8>;
+
+    /// The player's current stock of bombs, for games that have item-based bombs.
+    fn bombs(&self) -> Option<u8>;
+
+    /// The player's current shot power.
+    fn power(&self) -> ShotPower<G>;
+}
+
+#[cfg(feature = "th07")]
+impl ExportState<crate::Touhou7> for crate::th07::memory::tracking::TrackedState {
+    fn lives(&self) -> Option<u8> {
+        Some(self.player().lives())
+    }
+
+    fn bombs(&self) -> Option<u8> {
+        Some(self.player().bombs())
+    }
+
+    fn power(&self) -> ShotPower<crate::Touhou7> {
+        self.player().power()
+    }
+}
+
+#[cfg(feature = "th10")]
+impl ExportState<crate::Touhou10> for crate::th10::memory::RunState {
+    fn lives(&self) -> Option<u8> {
+        Some(self.player().lives())
+    }
+
+    fn bombs(&self) -> Option<u8> {
+        None
+    }
+
+    fn power(&self) -> ShotPower<crate::Touhou10> {
+        self.player().power()
+    }
+}
+
+/// A single row in an [`EventLog`].
+#[derive(Debug, Clone)]
+pub struct EventRecord<G: TrackableGame> {
+    time: EventTime,
+    kind: String,
+    location: Option<Location<G>>,
+    lives: Option<u8>,
+    bombs: Option<u8>,
+    power: ShotPower<G>,
+}
+
+impl<G: TrackableGame> EventRecord<G> {
+    fn new<S: ExportState<G>>(
+        time: EventTime,
+        kind: String,
+        location: Option<Location<G>>,
+        state: &S,
+    ) -> Self {
+        Self {
+            time,
+            kind,
+            location,
+            lives: state.lives(),
+            bombs: state.bombs(),
+            power: state.power(),
+        }
+    }
+
+    pub fn time(&self) -> EventTime {
+        self.time
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn location(&self) -> Option<Location<G>> {
+        self.location
+    }
+
+    pub fn lives(&self) -> Option<u8> {
+        self.lives
+    }
+
+    pub fn bombs(&self) -> Option<u8> {
+        self.bombs
+    }
+
+    pub fn power(&self) -> ShotPower<G> {
+        self.power
+    }
+}
+
+/// A tracker that keeps a log of every event and location change during a game.
+///
+/// The finished log can be written out as CSV using [`EventLog::write_csv`].
+#[derive(Debug, Clone)]
+pub struct EventLog<G: TrackableGame> {
+    tracking_type: TrackingType,
+    shot: ShotType<G>,
+    difficulty: Difficulty<G>,
+    location: Option<Location<G>>,
+    records: Vec<EventRecord<G>>,
+}
+
+impl<G> EventLog<G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    fn new(
+        tracking_type: TrackingType,
+        time: EventTime,
+        shot: ShotType<G>,
+        difficulty: Difficulty<G>,
+        location: Option<Location<G>>,
+        state: &G::State,
+    ) -> Self {
+        let kind = match tracking_type {
+            TrackingType::FullRun => "Run Start",
+            TrackingType::StagePractice => "Stage Practice Start",
+            TrackingType::SpellPractice => "Spell Practice Start",
+        };
+
+        Self {
+            tracking_type,
+            shot,
+            difficulty,
+            location,
+            records: vec![EventRecord::new(time, kind.into(), location, state)],
+        }
+    }
+
+    fn finish(mut self, time: EventTime, kind: &str, state: &G::State) -> Self {
+        self.push_record(time, kind.into(), state);
+        self
+    }
+
+    fn push_record(&mut self, time: EventTime, kind: String, state: &G::State) {
+        self.records
+            .push(EventRecord::new(time, kind, self.location, state));
+    }
+}
+
+impl<G: TrackableGame> EventLog<G> {
+    pub fn tracking_type(&self) -> TrackingType {
+        self.tracking_type
+    }
+
+    pub fn shot(&self) -> ShotType<G> {
+        self.shot
+    }
+
+    pub fn difficulty(&self) -> Difficulty<G> {
+        self.difficulty
+    }
+
+    pub fn records(&self) -> &[EventRecord<G>] {
+        &self.records
+    }
+
+    /// Writes this log as CSV, with a header row followed by one row per recorded event.
+    ///
+    /// The columns are the event's wall-clock timestamp (in seconds since the Unix epoch),
+    /// its play time since the start of the game (in seconds, not counting pauses), the event kind,
+    /// the location name, and the player's lives, bombs, and power. Lives and bombs are left empty
+    /// for games that don't track them.
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "timestamp,play_time,event,location,lives,bombs,power")?;
+
+        for record in &self.records {
+            let timestamp = record
+                .time
+                .timestamp()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO);
+
+            write!(
+                out,
+                "{:.3},{:.3},",
+                timestamp.as_secs_f64(),
+                record.time.play_time().as_secs_f64()
+            )?;
+            write_csv_field(&mut out, &record.kind)?;
+            out.write_all(b",")?;
+
+            if let Some(location) = record.location {
+                write_csv_field(&mut out, &location.to_string())?;
+            }
+
+            out.write_all(b",")?;
+            if let Some(lives) = record.lives {
+                write!(out, "{}", lives)?;
+            }
+
+            out.write_all(b",")?;
+            if let Some(bombs) = record.bombs {
+                write!(out, "{}", bombs)?;
+            }
+
+            writeln!(out, ",{}", *record.power)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_csv_field<W: Write>(out: &mut W, field: &str) -> io::Result<()> {
+    if field.contains([',', '"', '\n', '\r']) {
+        write!(out, "\"{}\"", field.replace('"', "\"\""))
+    } else {
+        out.write_all(field.as_bytes())
+    }
+}
+
+/// Gets the name recorded for an event, which matches its [`Display`](std::fmt::Display) output.
+///
+/// Game-specific events are only required to implement [`Debug`], so their debug output is used instead.
+fn event_kind<G: TrackableGame>(event: &Event<G>) -> String {
+    match event {
+        Event::GameSpecific(inner) => format!("{:?}", inner),
+        _ => event.standard_name().unwrap_or_default().into(),
+    }
+}
+
+/// Used to update an [`EventLog`].
+#[derive(Debug)]
+pub struct EventLogUpdate<'a, G: TrackableGame> {
+    log: &'a mut EventLog<G>,
+    time: EventTime,
+    state: G::State,
+}
+
+impl<'a, G> UpdateTracker<G> for EventLogUpdate<'a, G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    fn push_event(&mut self, event: Event<G>) {
+        self.log
+            .push_record(self.time, event_kind(&event), &self.state);
+    }
+
+    fn change_location(&mut self, location: Option<Location<G>>) {
+        self.log.location = location;
+        let kind = if location.is_some() {
+            "Location Change"
+        } else {
+            "Location Exit"
+        };
+
+        self.log.push_record(self.time, kind.into(), &self.state);
+    }
+}
+
+impl<G> TrackGame<G> for EventLog<G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    type Output = Self;
+    type Update<'a> = EventLogUpdate<'a, G>;
+
+    fn begin_update(&mut self, time: EventTime, state: G::State) -> Self::Update<'_> {
+        EventLogUpdate {
+            log: self,
+            time,
+            state,
+        }
+    }
+}
+
+impl<G> TrackRun<G> for EventLog<G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    fn start_run(
+        time: EventTime,
+        shot: ShotType<G>,
+        difficulty: Difficulty<G>,
+        state: G::State,
+    ) -> Self {
+        Self::new(TrackingType::FullRun, time, shot, difficulty, None, &state)
+    }
+
+    fn run_cleared(self, time: EventTime, state: G::State) -> Self::Output {
+        self.finish(time, "Run Cleared", &state)
+    }
+
+    fn run_exited(self, time: EventTime, state: G::State) -> Self::Output {
+        self.finish(time, "Run Exited", &state)
+    }
+}
+
+impl<G> TrackStagePractice<G> for EventLog<G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    fn start_stage_practice(
+        time: EventTime,
+        shot: ShotType<G>,
+        difficulty: Difficulty<G>,
+        _stage: Stage<G>,
+        state: G::State,
+    ) -> Self {
+        Self::new(
+            TrackingType::StagePractice,
+            time,
+            shot,
+            difficulty,
+            None,
+            &state,
+        )
+    }
+
+    fn finish_stage_practice(self, time: EventTime, state: G::State) -> Self::Output {
+        self.finish(time, "Stage Practice End", &state)
+    }
+}
+
+impl<G> TrackSpellPractice<G> for EventLog<G>
+where
+    G: TrackableGame,
+    G::State: ExportState<G>,
+{
+    fn start_spell_practice(
+        time: EventTime,
+        shot: ShotType<G>,
+        difficulty: Difficulty<G>,
+        location: Location<G>,
+        state: G::State,
+    ) -> Self {
+        Self::new(
+            TrackingType::SpellPractice,
+            time,
+            shot,
+            difficulty,
+            Some(location),
+            &state,
+        )
+    }
+
+    fn finish_spell_practice(self, time: EventTime, state: G::State) -> Self::Output {
+        self.finish(time, "Spell Practice End", &state)
+    }
+}
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use super::*;
+    use crate::th07::{Touhou7, Touhou7Event};
+    use crate::tracking::GameTimeCounter;
+    use crate::types::shot_power::Gen1Power;
+
+    fn log_with_kinds<I: IntoIterator<Item = String>>(kinds: I) -> EventLog<Touhou7> {
+        let counter = GameTimeCounter::new(false);
+        let power = ShotPower::new(Gen1Power::new(64).unwrap());
+
+        EventLog {
+            tracking_type: TrackingType::FullRun,
+            shot: ShotType::new(crate::th07::ShotType::ReimuA),
+            difficulty: Difficulty::new(crate::th07::Difficulty::Normal),
+            location: None,
+            records: kinds
+                .into_iter()
+                .map(|kind| EventRecord {
+                    time: counter.now(),
+                    kind,
+                    location: None,
+                    lives: Some(2),
+                    bombs: None,
+                    power,
+                })
+                .collect(),
+        }
+    }
+
+    fn csv_lines(log: &EventLog<Touhou7>) -> Vec<String> {
+        let mut out = Vec::new();
+        log.write_csv(&mut out).unwrap();
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn event_kinds_match_display() {
+        let events: [Event<Touhou7>; 10] = [
+            Event::Pause,
+            Event::Unpause,
+            Event::Miss,
+            Event::Bomb,
+            Event::Continue,
+            Event::ExtendLife,
+            Event::BombRefill,
+            Event::RunEnd {
+                cleared: false,
+                retried: true,
+            },
+            Event::RunEnd {
+                cleared: true,
+                retried: false,
+            },
+            Event::RunEnd {
+                cleared: false,
+                retried: false,
+            },
+        ];
+
+        for event in &events {
+            assert_eq!(event_kind(event), event.to_string());
+        }
+
+        let border: Event<Touhou7> = Event::GameSpecific(Touhou7Event::BorderStart);
+        assert_eq!(event_kind(&border), "BorderStart");
+    }
+
+    #[test]
+    fn writes_one_row_per_event() {
+        let kinds = [
+            "Run Start",
+            "Miss",
+            "Bomb Refill",
+            "BorderStart",
+            "Run Exited",
+        ];
+        let lines = csv_lines(&log_with_kinds(kinds.iter().map(|k| k.to_string())));
+
+        assert_eq!(
+            lines[0],
+            "timestamp,play_time,event,location,lives,bombs,power"
+        );
+        assert_eq!(lines.len(), kinds.len() + 1);
+
+        for (line, kind) in lines[1..].iter().zip(kinds) {
+            let fields: Vec<_> = line.split(',').collect();
+            assert_eq!(fields.len(), 7);
+            assert!(fields[0].parse::<f64>().is_ok());
+            assert!(fields[1].parse::<f64>().is_ok());
+            assert_eq!(&fields[2..], [kind, "", "2", "", "64 / 128"]);
+        }
+    }
+
+    #[test]
+    fn quotes_fields_with_separators() {
+        let lines = csv_lines(&log_with_kinds([
+            String::from("Border \"Break\", Stage 4"),
+            String::from("plain"),
+        ]));
+
+        assert!(lines[1].ends_with(",\"Border \"\"Break\"\", Stage 4\",,2,,64 / 128"));
+        assert!(lines[2].ends_with(",plain,,2,,64 / 128"));
+    }
+}
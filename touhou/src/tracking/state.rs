@@ -1,5 +1,6 @@
 //! Types for the different kinds of state that can be tracked using [`TrackerState`](super::TrackerState).
 
+use std::cmp::Ordering;
 use std::marker::PhantomData;
 use std::time::Duration;
 
@@ -54,10 +55,10 @@ impl CurrentLives {
         Self(state.lives())
     }
 
-    pub(super) fn update<G: Game, S: LifeStock<G>>(&mut self, state: &S) -> bool {
+    pub(super) fn update<G: Game, S: LifeStock<G>>(&mut self, state: &S) -> Ordering {
         let new = state.lives();
         let old = std::mem::replace(&mut self.0, new);
-        new < old
+        new.cmp(&old)
     }
 }
 
@@ -90,10 +91,10 @@ impl CurrentBombs {
         Self(state.bombs())
     }
 
-    pub(super) fn update<G: Game, S: BombStock<G>>(&mut self, state: &S) -> bool {
+    pub(super) fn update<G: Game, S: BombStock<G>>(&mut self, state: &S) -> Ordering {
         let new = state.bombs();
         let old = std::mem::replace(&mut self.0, new);
-        new < old
+        new.cmp(&old)
     }
 }
 
@@ -125,6 +125,43 @@ impl GameTimeCounter {
         self.pause_duration_for(Instant::now())
     }
 
+    /// Get whether this counter is currently paused.
+    pub fn is_paused(&self) -> bool {
+        self.pause_start.is_some()
+    }
+
+    /// Get the total wall-clock time elapsed since this counter started, including time spent paused.
+    ///
+    /// This is equivalent to the [`game_time`](method@EventTime::game_time) of [`now`](GameTimeCounter::now).
+    pub fn elapsed(&self) -> Duration {
+        self.game_start.0.elapsed()
+    }
+
+    /// Get the total time elapsed since this counter started, not including time spent paused.
+    ///
+    /// This is equivalent to the [`play_time`](method@EventTime::play_time) of [`now`](GameTimeCounter::now).
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use touhou::tracking::GameTimeCounter;
+    /// # use std::thread::sleep;
+    /// # use std::time::Duration;
+    /// let mut counter = GameTimeCounter::new(false);
+    /// sleep(Duration::from_millis(50));
+    /// counter.pause();
+    /// sleep(Duration::from_millis(100));
+    /// counter.unpause();
+    ///
+    /// assert!(counter.elapsed() >= Duration::from_millis(150));
+    /// assert!(counter.elapsed_unpaused() < Duration::from_millis(150));
+    /// assert!(counter.elapsed_unpaused() >= Duration::from_millis(50));
+    /// ```
+    pub fn elapsed_unpaused(&self) -> Duration {
+        let instant = Instant::now();
+        instant.duration_since(self.game_start.0) - self.pause_duration_for(instant)
+    }
+
     /// Get an [`EventTime`] representing the current time in-game.
     ///
     /// # Example
@@ -2,6 +2,7 @@
 
 #![allow(clippy::type_complexity)]
 
+use std::cmp::Ordering;
 use std::marker::PhantomData;
 
 use super::state::*;
@@ -29,6 +30,7 @@ impl<G: TrackableGame, T: TrackGame<G>, L, B, C, P> TrackerState<G, T, L, B, C,
             location_filter,
             update,
             miss: false,
+            stock: StockIncreases::default(),
             updated_location: CheckOnDrop(
                 ignore_location_update,
                 "attempted to drop update without location update",
@@ -52,6 +54,17 @@ impl<G: TrackableGame, T: TrackGame<G>, L, B, C, P> TrackerState<G, T, L, B, C,
         update.update_location(resolver);
         update
     }
+
+    /// Push an [`Event::RunEnd`] event to the tracker.
+    ///
+    /// Drivers should call this just before finishing a run or practice attempt, so that trackers
+    /// can tell retries apart from game overs and clears.
+    pub fn push_run_end(&mut self, state: G::State, cleared: bool, retried: bool) {
+        let now = self.now();
+        self.tracker
+            .begin_update(now, state)
+            .push_event(Event::RunEnd { cleared, retried });
+    }
 }
 
 impl<G: TrackableGame, T: TrackRun<G>, L, B, C, P> TrackerState<G, T, L, B, C, P> {
@@ -115,6 +128,29 @@ impl Drop for CheckOnDrop {
     }
 }
 
+/// Increases in life and bomb stock seen during a single update.
+///
+/// These are only turned into events once the update is finished, since a continue or miss
+/// seen in the same update means the stock was reset rather than extended or refilled.
+#[derive(Debug, Clone, Copy, Default)]
+struct StockIncreases {
+    lives: bool,
+    bombs: bool,
+    continued: bool,
+}
+
+impl StockIncreases {
+    fn into_events<G: TrackableGame>(self, missed: bool) -> impl Iterator<Item = Event<G>> {
+        let extend = self.lives && !self.continued;
+        let refill = self.bombs && !(self.continued || missed);
+
+        extend
+            .then_some(Event::ExtendLife)
+            .into_iter()
+            .chain(refill.then_some(Event::BombRefill))
+    }
+}
+
 /// Used to update a [`TrackerState`] instance.
 ///
 /// This type is returned from [`TrackerState::begin_update`] and can be used to
@@ -140,6 +176,7 @@ pub struct TrackerUpdate<'a, G: TrackableGame, T: TrackGame<G> + 'a, L1, L2, B1,
     location_filter: &'a mut LocationResolveFilter<G>,
     now: EventTime,
     miss: bool,
+    stock: StockIncreases,
     finished: CheckOnDrop,
     updated_location: CheckOnDrop,
     lives: &'a mut L1,
@@ -204,9 +241,12 @@ where
         mut self,
         state: &S,
     ) -> TrackerUpdate<'a, G, T, CurrentLives, CurrentLives, B1, B2, C1, C2, P1, P2> {
-        let miss = self.lives.update(state);
-        if miss {
-            self.push_event(Event::Miss);
+        let delta = self.lives.update(state);
+        let miss = delta == Ordering::Less;
+        match delta {
+            Ordering::Less => self.push_event(Event::Miss),
+            Ordering::Greater => self.stock.lives = true,
+            Ordering::Equal => {}
         }
 
         TrackerUpdate {
@@ -222,6 +262,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss,
+            stock: self.stock,
         }
     }
 }
@@ -254,6 +295,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss,
+            stock: self.stock,
         }
     }
 }
@@ -268,9 +310,10 @@ where
         mut self,
         state: &S,
     ) -> TrackerUpdate<'a, G, T, L1, L2, CurrentBombs, CurrentBombs, C1, C2, P1, P2> {
-        let bombed = self.bombs.update(state);
-        if bombed {
-            self.push_event(Event::Bomb);
+        match self.bombs.update(state) {
+            Ordering::Less => self.push_event(Event::Bomb),
+            Ordering::Greater => self.stock.bombs = true,
+            Ordering::Equal => {}
         }
 
         TrackerUpdate {
@@ -286,6 +329,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss: self.miss,
+            stock: self.stock,
         }
     }
 }
@@ -318,6 +362,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss: self.miss,
+            stock: self.stock,
         }
     }
 }
@@ -350,6 +395,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss: self.miss,
+            stock: self.stock,
         }
     }
 }
@@ -367,6 +413,7 @@ where
         let used_continue = self.continues.update(state);
         if used_continue {
             self.push_event(Event::Continue);
+            self.stock.continued = true;
         }
 
         TrackerUpdate {
@@ -382,6 +429,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss: self.miss,
+            stock: self.stock,
         }
     }
 }
@@ -423,6 +471,7 @@ where
             updated_location: self.updated_location,
             finished: self.finished,
             miss: self.miss,
+            stock: self.stock,
         }
     }
 }
@@ -433,6 +482,54 @@ where
     T: TrackGame<G>,
 {
     pub fn finish(mut self) {
+        for event in self.stock.into_events(self.miss) {
+            self.update.push_event(event);
+        }
+
         self.finished.0 = true;
     }
 }
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use super::*;
+    use crate::th07::Touhou7;
+
+    fn events(stock: StockIncreases, missed: bool) -> Vec<Event<Touhou7>> {
+        stock.into_events(missed).collect()
+    }
+
+    #[test]
+    fn stock_increases_emit_events() {
+        let stock = StockIncreases {
+            lives: true,
+            bombs: true,
+            continued: false,
+        };
+
+        assert_eq!(events(stock, false), [Event::ExtendLife, Event::BombRefill]);
+        assert!(events(StockIncreases::default(), false).is_empty());
+    }
+
+    #[test]
+    fn continue_suppresses_extend_and_refill() {
+        let stock = StockIncreases {
+            lives: true,
+            bombs: true,
+            continued: true,
+        };
+
+        assert!(events(stock, false).is_empty());
+    }
+
+    #[test]
+    fn miss_suppresses_refill() {
+        let stock = StockIncreases {
+            lives: false,
+            bombs: true,
+            continued: false,
+        };
+
+        assert!(events(stock, true).is_empty());
+    }
+}
@@ -0,0 +1,185 @@
+//! A headless watcher for attaching to game processes and tracking games within them.
+
+use std::fmt::Debug;
+use std::thread::sleep;
+use std::time::Duration;
+
+use super::{GameTracker, IntoGameTracker, TrackGame, TrackableGame};
+use crate::memory::{GameMemory, MemoryReadError, ProcessStatus};
+
+/// Events produced by a [`GameWatcher`].
+#[derive(Debug)]
+pub enum WatcherEvent<G: TrackableGame, O> {
+    /// The watcher attached to a new game process with the given PID.
+    Attached(u32),
+    /// The attached game process (with the given PID) exited.
+    Detached(u32),
+    /// The attached game process (with the given PID) was closed and replaced by a new process.
+    ///
+    /// The watcher will attempt to reattach to the new process on the next poll.
+    Restarted(u32),
+    /// A game was completed, producing the given tracker output.
+    ///
+    /// This is also emitted for games that were in progress when the attached process exited.
+    Finished(O),
+    /// An error occurred while attaching to or reading from a game process.
+    Error(MemoryReadError<G>),
+}
+
+enum WatcherState<G: TrackableGame, T: TrackGame<G>, M: IntoGameTracker<G, T>> {
+    Detached,
+    Attached(GameTracker<G, T, M::Driver>),
+}
+
+/// Watches for game processes and tracks games within them, independently of any UI.
+///
+/// This type wraps the attach/track/detach cycle around a [`GameTracker`]: it repeatedly tries to attach
+/// to a game process using a user-provided function (such as [`GameMemory::new`](crate::th07::GameMemory::new)),
+/// polls the resulting tracker while the process is running, and reattaches once it exits.
+///
+/// Events are retrieved by iterating over the watcher. Note that calls to [`next`](Iterator::next) block,
+/// sleeping between polls until an event is available; the iterator never ends on its own.
+pub struct GameWatcher<G, T, M, F>
+where
+    G: TrackableGame,
+    T: TrackGame<G>,
+    M: GameMemory<G> + IntoGameTracker<G, T>,
+    F: FnMut() -> Result<Option<M>, MemoryReadError<G>>,
+{
+    attach: F,
+    state: WatcherState<G, T, M>,
+    pending: Option<WatcherEvent<G, T::Output>>,
+    detached_poll_interval: Duration,
+    attached_poll_interval: Duration,
+}
+
+impl<G, T, M, F> GameWatcher<G, T, M, F>
+where
+    G: TrackableGame,
+    T: TrackGame<G>,
+    M: GameMemory<G> + IntoGameTracker<G, T>,
+    F: FnMut() -> Result<Option<M>, MemoryReadError<G>>,
+{
+    /// Create a new watcher that attaches to processes using the given function.
+    ///
+    /// The function should return `Ok(None)` if no suitable process could be found.
+    pub fn new(attach: F) -> Self {
+        Self {
+            attach,
+            state: WatcherState::Detached,
+            pending: None,
+            detached_poll_interval: Duration::from_millis(500),
+            attached_poll_interval: Duration::from_millis(100),
+        }
+    }
+
+    /// Set how often to look for a new process while detached.
+    pub fn detached_poll_interval(mut self, interval: Duration) -> Self {
+        self.detached_poll_interval = interval;
+        self
+    }
+
+    /// Set how often to poll the attached process for updates.
+    pub fn attached_poll_interval(mut self, interval: Duration) -> Self {
+        self.attached_poll_interval = interval;
+        self
+    }
+
+    /// Get the PID of the currently attached process, if any.
+    pub fn pid(&self) -> Option<u32> {
+        match &self.state {
+            WatcherState::Detached => None,
+            WatcherState::Attached(tracker) => Some(tracker.pid()),
+        }
+    }
+
+    /// Poll for a new event once, without blocking.
+    ///
+    /// Returns `None` if nothing happened during this poll.
+    pub fn poll(&mut self) -> Option<WatcherEvent<G, T::Output>> {
+        if let Some(event) = self.pending.take() {
+            return Some(event);
+        }
+
+        match &mut self.state {
+            WatcherState::Detached => match (self.attach)() {
+                Ok(Some(memory)) => {
+                    let pid = memory.pid();
+                    self.state = WatcherState::Attached(memory.track_games());
+                    Some(WatcherEvent::Attached(pid))
+                }
+                Ok(None) => None,
+                Err(e) => Some(WatcherEvent::Error(e)),
+            },
+            WatcherState::Attached(tracker) => {
+                let pid = tracker.pid();
+                let detach_event = match tracker.memory_mut().status() {
+                    ProcessStatus::Running => {
+                        return match tracker.update() {
+                            Ok(Some(output)) => Some(WatcherEvent::Finished(output)),
+                            Ok(None) => None,
+                            Err(e) => Some(WatcherEvent::Error(e)),
+                        }
+                    }
+                    ProcessStatus::Exited => WatcherEvent::Detached(pid),
+                    ProcessStatus::Replaced => WatcherEvent::Restarted(pid),
+                };
+
+                if let WatcherState::Attached(tracker) =
+                    std::mem::replace(&mut self.state, WatcherState::Detached)
+                {
+                    if let (_, Some(output)) = tracker.close() {
+                        self.pending = Some(detach_event);
+                        return Some(WatcherEvent::Finished(output));
+                    }
+                }
+
+                Some(detach_event)
+            }
+        }
+    }
+}
+
+impl<G, T, M, F> Iterator for GameWatcher<G, T, M, F>
+where
+    G: TrackableGame,
+    T: TrackGame<G>,
+    M: GameMemory<G> + IntoGameTracker<G, T>,
+    F: FnMut() -> Result<Option<M>, MemoryReadError<G>>,
+{
+    type Item = WatcherEvent<G, T::Output>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.poll() {
+                // avoid spinning on repeated attach failures
+                if let (WatcherEvent::Error(_), WatcherState::Detached) = (&event, &self.state) {
+                    sleep(self.detached_poll_interval);
+                }
+
+                return Some(event);
+            }
+
+            match self.state {
+                WatcherState::Detached => sleep(self.detached_poll_interval),
+                WatcherState::Attached(_) => sleep(self.attached_poll_interval),
+            }
+        }
+    }
+}
+
+impl<G, T, M, F> Debug for GameWatcher<G, T, M, F>
+where
+    G: TrackableGame,
+    T: TrackGame<G>,
+    M: GameMemory<G> + IntoGameTracker<G, T>,
+    F: FnMut() -> Result<Option<M>, MemoryReadError<G>>,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GameWatcher")
+            .field("pid", &self.pid())
+            .field("detached_poll_interval", &self.detached_poll_interval)
+            .field("attached_poll_interval", &self.attached_poll_interval)
+            .finish_non_exhaustive()
+    }
+}
@@ -8,6 +8,7 @@ pub mod any;
 pub mod difficulty;
 pub mod errors;
 pub mod game_id;
+pub mod game_id_str;
 pub mod shot_power;
 pub mod shot_type;
 pub mod spell_card;
@@ -46,6 +47,13 @@ pub trait GameValue: Debug + Copy + Sync + Send + Unpin + 'static {
 
     /// Gets a human-friendly display name for this value.
     fn name(&self) -> &'static str;
+
+    /// Gets a short name for this value, for use in compact layouts.
+    ///
+    /// By default, this is the same as [`Self::name`].
+    fn short_name(&self) -> &'static str {
+        self.name()
+    }
 }
 
 /// A trait for iterating over all possible values for a type.
@@ -86,6 +94,11 @@ pub trait Game:
     /// The specific [`GameId`] value associated with this game.
     const GAME_ID: GameId;
 
+    /// The highest valid raw spell card ID for this game.
+    ///
+    /// Valid spell IDs are contiguous, starting from 1; see [`Self::spell_id_range`].
+    const MAX_SPELL_ID: u32;
+
     /// The type used to represent this game's spell card IDs.
     ///
     /// [`SpellCard`] wraps this type for more convenient usage.
@@ -126,6 +139,26 @@ pub trait Game:
     /// to [`SpellCardInfo`] instances on their own, so client code shouldn't need to use this.
     fn card_info(id: Self::SpellID) -> &'static SpellCardInfo<Self>;
 
+    /// Returns whether the given stage is the final stage of this game's main scenario.
+    ///
+    /// For more details, see [`Stage::is_final_stage`].
+    fn is_final_stage(stage: Self::StageID) -> bool;
+
+    /// Returns whether the given stage is an extra stage.
+    ///
+    /// For more details, see [`Stage::is_extra`].
+    fn is_extra_stage(stage: Self::StageID) -> bool;
+
+    /// Returns how far into the game the given stage is.
+    ///
+    /// For more details, see [`Stage::progress_order`].
+    fn stage_progress_order(stage: Self::StageID) -> u16;
+
+    /// Gets the range of valid raw spell card IDs for this game.
+    fn spell_id_range() -> std::ops::RangeInclusive<u32> {
+        1..=Self::MAX_SPELL_ID
+    }
+
     /// Gets the abbreviated form of this game's English subtitle.
     ///
     /// For more details, see [`GameId::abbreviation`].
@@ -289,3 +322,55 @@ macro_rules! impl_wrapper_traits {
 }
 
 pub(super) use impl_wrapper_traits;
+
+#[cfg(all(test, feature = "th07"))]
+mod tests {
+    use touhou_macros::GameValue;
+
+    use super::*;
+    use crate::th07::Touhou7;
+
+    #[derive(Debug, GameValue)]
+    #[game_id(PCB)]
+    #[game_type(Touhou7)]
+    #[value_type(Difficulty)]
+    pub enum TestDifficulty {
+        Easy = 0,
+        Normal = 1,
+        #[name = "Lunatic Mode"]
+        #[short_name = "L"]
+        Lunatic = 3,
+    }
+
+    #[test]
+    fn derived_game_value_round_trips() {
+        for value in TestDifficulty::iter_all() {
+            assert_eq!(value.game_id(), GameId::PCB);
+            assert_eq!(
+                TestDifficulty::from_raw(value.raw_id(), GameId::PCB).unwrap(),
+                value
+            );
+        }
+
+        assert_eq!(TestDifficulty::Normal.raw_id(), 1);
+        assert_eq!(TestDifficulty::Lunatic.raw_id(), 3);
+    }
+
+    #[test]
+    fn derived_game_value_names() {
+        assert_eq!(GameValue::name(&TestDifficulty::Easy), "Easy");
+        assert_eq!(GameValue::short_name(&TestDifficulty::Easy), "Easy");
+        assert_eq!(GameValue::name(&TestDifficulty::Lunatic), "Lunatic Mode");
+        assert_eq!(GameValue::short_name(&TestDifficulty::Lunatic), "L");
+        assert_eq!(format!("{:>8}", TestDifficulty::Easy), "    Easy");
+    }
+
+    #[test]
+    fn derived_game_value_uses_value_type_errors() {
+        let err: errors::InvalidDifficultyId<Touhou7> =
+            TestDifficulty::from_raw(2, GameId::PCB).unwrap_err();
+        assert!(err.to_string().contains("difficulty"));
+
+        assert!(TestDifficulty::from_raw(0, GameId::IN).is_err());
+    }
+}
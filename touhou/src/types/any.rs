@@ -12,6 +12,7 @@ use super::difficulty::*;
 use super::shot_type::*;
 use super::spell_card::*;
 use super::stage::*;
+use super::errors::InvalidGameValue;
 use super::{Game, GameId, GameValue};
 
 macro_rules! define_any_wrapper {
@@ -63,6 +64,12 @@ macro_rules! define_any_wrapper {
                         $specific_ty::try_from(self.0).unwrap().name()
                     }
 
+                    #[cfg(feature = "th09")]
+                    fn visit_th09(self) -> Self::Output {
+                        use crate::th09::$specific_ty;
+                        $specific_ty::try_from(self.0).unwrap().name()
+                    }
+
                     #[cfg(feature = "th10")]
                     fn visit_th10(self) -> Self::Output {
                         use crate::th10::$specific_ty;
@@ -154,6 +161,22 @@ macro_rules! define_any_wrapper {
                         ))
                     }
 
+                    #[cfg(feature = "th09")]
+                    fn visit_th09(self) -> Self::Output {
+                        use crate::th09::$specific_ty;
+                        $specific_ty::try_from(self.0)
+                            .err()
+                            .map(crate::types::errors::InvalidGameValue::from)
+                    }
+
+                    #[cfg(not(feature = "th09"))]
+                    fn visit_th09(self) -> Self::Output {
+                        Some(crate::types::errors::InvalidGameValue::game_not_supported(
+                            $type_name,
+                            GameId::PoFV,
+                        ))
+                    }
+
                     #[cfg(feature = "th10")]
                     fn visit_th10(self) -> Self::Output {
                         use crate::th10::$specific_ty;
@@ -249,6 +272,75 @@ define_any_wrapper!(
     "spell card"
 );
 
+impl AnySpellCard {
+    /// Creates a spell card value from a game ID and the card's ID within that game.
+    ///
+    /// This is equivalent to [`GameValue::from_raw`].
+    pub fn from_game_local(game: GameId, local: u32) -> Result<Self, InvalidGameValue<u32>> {
+        Self::from_raw(local, game)
+    }
+
+    /// Gets the ID of this card within its game.
+    ///
+    /// This is equivalent to [`Self::id`].
+    pub const fn game_local_id(&self) -> u32 {
+        self.id
+    }
+
+    /// Packs this card's game number and game-local ID into a single integer.
+    ///
+    /// The game number is stored in the high 8 bits, and the game-local ID is stored in the low 24 bits.
+    /// This layout is stable, and can be used for storing cards from multiple games in a single column.
+    pub const fn packed_id(&self) -> u32 {
+        ((self.game.number() as u32) << 24) | (self.id & 0x00FF_FFFF)
+    }
+
+    /// Unpacks a card value created by [`Self::packed_id`].
+    pub fn from_packed_id(packed: u32) -> Result<Self, anyhow::Error> {
+        let game = GameId::new((packed >> 24) as u8)?;
+        Self::from_game_local(game, packed & 0x00FF_FFFF).map_err(anyhow::Error::from)
+    }
+
+    /// Searches every supported game for spell cards whose names contain the given string, ignoring case.
+    ///
+    /// Results are grouped by game, and are in ID order within each game.
+    pub fn search(query: &str) -> impl Iterator<Item = Self> {
+        let mut results = Vec::new();
+
+        #[cfg(feature = "th07")]
+        results.extend(
+            crate::th07::SpellId::search_cards(query)
+                .map(|card| Self::new::<crate::th07::Touhou7>(card.unwrap())),
+        );
+
+        #[cfg(feature = "th08")]
+        results.extend(
+            crate::th08::SpellId::search_cards(query)
+                .map(|card| Self::new::<crate::th08::Touhou8>(card.unwrap())),
+        );
+
+        #[cfg(feature = "th09")]
+        results.extend(
+            crate::th09::SpellId::search_cards(query)
+                .map(|card| Self::new::<crate::th09::Touhou9>(card.unwrap())),
+        );
+
+        #[cfg(feature = "th10")]
+        results.extend(
+            crate::th10::SpellId::search_cards(query)
+                .map(|card| Self::new::<crate::th10::Touhou10>(card.unwrap())),
+        );
+
+        #[cfg(feature = "th15")]
+        results.extend(
+            crate::th15::SpellId::search_cards(query)
+                .map(|card| Self::new::<crate::th15::Touhou15>(card.unwrap())),
+        );
+
+        results.into_iter()
+    }
+}
+
 /// A dynamically-typed representation of a selectable difficulty in a Touhou game.
 ///
 /// See the [module documentation](`self`) for more details.
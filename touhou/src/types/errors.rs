@@ -45,6 +45,39 @@ impl<T: fmt::Display + fmt::Debug> fmt::Display for OutOfRangeError<T> {
 
 impl<T: fmt::Display + fmt::Debug> Error for OutOfRangeError<T> {}
 
+/// An error for values that fall within the range of valid values, but do not correspond to any of them.
+#[derive(Debug, Copy, Clone)]
+pub struct NotInSetError<T> {
+    value: T,
+    valid: &'static str,
+}
+
+impl<T> NotInSetError<T> {
+    pub(crate) fn from_other<U: Into<T>>(src: NotInSetError<U>) -> Self {
+        Self {
+            value: src.value.into(),
+            valid: src.valid,
+        }
+    }
+}
+
+impl<T: fmt::Display + fmt::Debug> NotInSetError<T> {
+    /// Creates a new error. `valid` should be a human-readable list of valid values.
+    pub const fn new(value: T, valid: &'static str) -> Self {
+        Self { value, valid }
+    }
+}
+
+impl<T: fmt::Display + fmt::Debug> fmt::Display for NotInSetError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = &self.value;
+        let valid = self.valid;
+        write!(f, "{value} is not a valid value (valid values are {valid})")
+    }
+}
+
+impl<T: fmt::Display + fmt::Debug> Error for NotInSetError<T> {}
+
 #[derive(Debug, Copy, Clone)]
 pub struct InvalidGameId(u8);
 
@@ -69,18 +102,22 @@ impl Error for InvalidGameId {}
 #[derive(Debug, Copy, Clone)]
 pub enum ValueErrorReason<T> {
     OutOfRange(OutOfRangeError<T>),
+    NotInSet(NotInSetError<T>),
     IntConversion(TryFromIntError),
     WrongGame(GameId),
     GameNotSupported,
+    UnknownName,
 }
 
 impl<T> ValueErrorReason<T> {
     pub(crate) fn from_other<U: Into<T>>(src: ValueErrorReason<U>) -> Self {
         match src {
             ValueErrorReason::OutOfRange(err) => Self::OutOfRange(OutOfRangeError::from_other(err)),
+            ValueErrorReason::NotInSet(err) => Self::NotInSet(NotInSetError::from_other(err)),
             ValueErrorReason::IntConversion(err) => Self::IntConversion(err),
             ValueErrorReason::WrongGame(id) => Self::WrongGame(id),
             ValueErrorReason::GameNotSupported => Self::GameNotSupported,
+            ValueErrorReason::UnknownName => Self::UnknownName,
         }
     }
 }
@@ -89,8 +126,9 @@ impl<T: fmt::Display + fmt::Debug + 'static> ValueErrorReason<T> {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
             Self::OutOfRange(err) => Some(err),
+            Self::NotInSet(err) => Some(err),
             Self::IntConversion(err) => Some(err),
-            Self::WrongGame(_) | Self::GameNotSupported => None,
+            Self::WrongGame(_) | Self::GameNotSupported | Self::UnknownName => None,
         }
     }
 }
@@ -99,9 +137,11 @@ impl<T: fmt::Display + fmt::Debug + 'static> fmt::Display for ValueErrorReason<T
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::OutOfRange(err) => err.fmt(f),
+            Self::NotInSet(err) => err.fmt(f),
             Self::IntConversion(err) => err.fmt(f),
             Self::WrongGame(err) => write!(f, "value is for {}", err.abbreviation()),
             Self::GameNotSupported => "support not compiled".fmt(f),
+            Self::UnknownName => "name not recognized".fmt(f),
         }
     }
 }
@@ -154,6 +194,19 @@ impl<T: fmt::Display + fmt::Debug + 'static> InvalidGameValue<T> {
         )
     }
 
+    pub const fn not_in_set(
+        type_name: &'static str,
+        game: GameId,
+        value: T,
+        valid: &'static str,
+    ) -> Self {
+        Self::new(
+            type_name,
+            game,
+            ValueErrorReason::NotInSet(NotInSetError::new(value, valid)),
+        )
+    }
+
     pub const fn int_conversion(
         type_name: &'static str,
         game: GameId,
@@ -169,6 +222,10 @@ impl<T: fmt::Display + fmt::Debug + 'static> InvalidGameValue<T> {
     pub const fn game_not_supported(type_name: &'static str, game: GameId) -> Self {
         Self::new(type_name, game, ValueErrorReason::GameNotSupported)
     }
+
+    pub const fn unknown_name(type_name: &'static str, game: GameId) -> Self {
+        Self::new(type_name, game, ValueErrorReason::UnknownName)
+    }
 }
 
 impl<T: fmt::Display + fmt::Debug + 'static> From<std::convert::Infallible>
@@ -207,6 +264,12 @@ macro_rules! define_value_error {
                     Self(InvalidGameValue::out_of_range($type_name, G::GAME_ID, value, *valid.start(), *valid.end()), PhantomData)
                 }
 
+                $ty_vis const fn not_in_set(value: $val_ty, valid: &'static str) -> Self {
+                    use crate::types::errors::InvalidGameValue;
+                    use std::marker::PhantomData;
+                    Self(InvalidGameValue::not_in_set($type_name, G::GAME_ID, value, valid), PhantomData)
+                }
+
                 $ty_vis const fn wrong_game(actual: crate::types::GameId) -> Self {
                     use crate::types::errors::InvalidGameValue;
                     use std::marker::PhantomData;
@@ -219,6 +282,12 @@ macro_rules! define_value_error {
                     Self(InvalidGameValue::game_not_supported($type_name, G::GAME_ID), PhantomData)
                 }
 
+                $ty_vis const fn unknown_name() -> Self {
+                    use crate::types::errors::InvalidGameValue;
+                    use std::marker::PhantomData;
+                    Self(InvalidGameValue::unknown_name($type_name, G::GAME_ID), PhantomData)
+                }
+
                 $ty_vis const fn into_inner(self) -> crate::types::errors::InvalidGameValue<$val_ty> {
                     self.0
                 }
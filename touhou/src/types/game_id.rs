@@ -1,6 +1,9 @@
 //! An enumeration for identifying and naming games.
 
 use std::fmt::Display;
+use std::str::FromStr;
+
+use anyhow::anyhow;
 
 use super::errors::InvalidGameId;
 
@@ -10,7 +13,10 @@ macro_rules! define_game_info {
             $id:ident : {
                 id_number: $id_num:literal,
                 title: $title:literal,
-                subtitle: $subtitle:literal
+                subtitle: $subtitle:literal,
+                feature: $feature:literal,
+                score_file: $score_file:literal,
+                memory: $memory:literal
             }
         ),*
     } => {
@@ -32,6 +38,21 @@ macro_rules! define_game_info {
         }
 
         impl GameId {
+            /// Gets a list of every game covered by this crate, in release order.
+            ///
+            /// This includes games whose support has not been enabled in crate features.
+            ///
+            /// # Examples
+            ///
+            /// ```
+            /// # use touhou::types::GameId;
+            /// assert_eq!(GameId::all()[0], GameId::PCB);
+            /// assert!(GameId::all().contains(&GameId::MoF));
+            /// ```
+            pub const fn all() -> &'static [GameId] {
+                &[$(Self::$id),*]
+            }
+
             /// Converts a game's number into the corresponding `GameId` variant.
             ///
             /// This is equivalent to this type's implementation of [`TryFrom<u8>`].
@@ -86,7 +107,7 @@ macro_rules! define_game_info {
             pub const fn numbered_name(&self) -> &'static str {
                 match *self {
                     $(
-                        Self::$id => concat!("Touhou ", stringify!($num_name))
+                        Self::$id => concat!("Touhou ", stringify!($id_num))
                     ),*
                 }
             }
@@ -168,6 +189,37 @@ macro_rules! define_game_info {
                     ),*
                 }
             }
+
+            /// Gets whether support for this game was enabled in crate features.
+            pub const fn is_enabled(&self) -> bool {
+                match *self {
+                    $(
+                        Self::$id => cfg!(feature = $feature)
+                    ),*
+                }
+            }
+
+            /// Gets whether score files for this game can be read in this build of the crate.
+            ///
+            /// This requires both this game's feature and the `score-file` feature to be enabled.
+            pub const fn supports_score_file(&self) -> bool {
+                match *self {
+                    $(
+                        Self::$id => $score_file && cfg!(all(feature = $feature, feature = "score-file"))
+                    ),*
+                }
+            }
+
+            /// Gets whether this game's memory can be read in this build of the crate.
+            ///
+            /// This requires both this game's feature and the `memory` feature to be enabled.
+            pub const fn supports_memory(&self) -> bool {
+                match *self {
+                    $(
+                        Self::$id => $memory && cfg!(all(feature = $feature, feature = "memory"))
+                    ),*
+                }
+            }
         }
 
         impl From<GameId> for u8 {
@@ -192,6 +244,37 @@ macro_rules! define_game_info {
                 }
             }
         }
+
+        impl FromStr for GameId {
+            type Err = anyhow::Error;
+
+            /// Parses a game from its abbreviation (`"PCB"`), its numbered name (`"Touhou 7"`),
+            /// or its number with or without a `th` prefix (`"th07"`, `"th7"`, or `"7"`).
+            ///
+            /// Matching is case-insensitive.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let s = s.trim();
+
+                $(
+                    if s.eq_ignore_ascii_case(stringify!($id)) {
+                        return Ok(Self::$id);
+                    }
+                )*
+
+                let lower = s.to_ascii_lowercase();
+                let number = lower
+                    .strip_prefix("touhou")
+                    .or_else(|| lower.strip_prefix("th"))
+                    .unwrap_or(&lower)
+                    .trim_start();
+
+                number
+                    .parse::<u8>()
+                    .ok()
+                    .and_then(|number| Self::new(number).ok())
+                    .ok_or_else(|| anyhow!("could not parse game ID {}", s))
+            }
+        }
     };
 }
 
@@ -199,22 +282,42 @@ define_game_info! {
     PCB: {
         id_number: 7,
         title: "Youyoumu",
-        subtitle: "Perfect Cherry Blossom"
+        subtitle: "Perfect Cherry Blossom",
+        feature: "th07",
+        score_file: true,
+        memory: true
     },
     IN: {
         id_number: 8,
         title: "Eiyashou",
-        subtitle: "Imperishable Night"
+        subtitle: "Imperishable Night",
+        feature: "th08",
+        score_file: true,
+        memory: true
+    },
+    PoFV: {
+        id_number: 9,
+        title: "Kaeizuka",
+        subtitle: "Phantasmagoria of Flower View",
+        feature: "th09",
+        score_file: true,
+        memory: false
     },
     MoF: {
         id_number: 10,
         title: "Fuujinroku",
-        subtitle: "Mountain of Faith"
+        subtitle: "Mountain of Faith",
+        feature: "th10",
+        score_file: true,
+        memory: true
     },
     LoLK: {
         id_number: 15,
         title: "Kanjuden",
-        subtitle: "Legacy of Lunatic Kingdom"
+        subtitle: "Legacy of Lunatic Kingdom",
+        feature: "th15",
+        score_file: true,
+        memory: true
     }
 }
 
@@ -256,6 +359,10 @@ pub(crate) trait VisitGame: Sized {
         unimplemented!("Support for Touhou 8 was not compiled")
     }
 
+    fn visit_th09(self) -> Self::Output {
+        unimplemented!("Support for Touhou 9 was not compiled")
+    }
+
     fn visit_th10(self) -> Self::Output {
         unimplemented!("Support for Touhou 10 was not compiled")
     }
@@ -268,6 +375,7 @@ pub(crate) trait VisitGame: Sized {
         match game_id {
             GameId::PCB => self.visit_th07(),
             GameId::IN => self.visit_th08(),
+            GameId::PoFV => self.visit_th09(),
             GameId::MoF => self.visit_th10(),
             GameId::LoLK => self.visit_th15(),
         }
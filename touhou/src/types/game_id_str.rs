@@ -0,0 +1,64 @@
+//! Serialization helpers for representing [`GameId`] values as strings.
+//!
+//! By default, [`GameId`] values are serialized as their game number (e.g. `7` for [`GameId::PCB`]).
+//! This module can be used with `#[serde(with = "touhou::types::game_id_str")]` to instead
+//! serialize them using their abbreviations (e.g. `"PCB"`).
+//!
+//! For compatibility, deserialization accepts both the string and integer forms.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use touhou::types::GameId;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Record {
+//!     #[serde(with = "touhou::types::game_id_str")]
+//!     game: GameId,
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Visitor};
+use serde::{Deserializer, Serializer};
+
+use super::GameId;
+
+pub fn serialize<S: Serializer>(value: &GameId, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value.abbreviation())
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<GameId, D::Error> {
+    deserializer.deserialize_any(GameIdVisitor)
+}
+
+struct GameIdVisitor;
+
+impl<'de> Visitor<'de> for GameIdVisitor {
+    type Value = GameId;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a game abbreviation or number")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<GameId, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<GameId, E> {
+        u8::try_from(v)
+            .ok()
+            .and_then(|v| GameId::new(v).ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<GameId, E> {
+        u8::try_from(v)
+            .ok()
+            .and_then(|v| GameId::new(v).ok())
+            .ok_or_else(|| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+}
@@ -7,6 +7,7 @@ use std::str;
 
 use serde::{Deserialize, Serialize};
 
+use super::errors::InvalidCardId;
 use super::{impl_wrapper_traits, Difficulty, Game, GameValue, Stage};
 
 /// Contains information for specific spell cards.
@@ -73,6 +74,18 @@ impl<G: Game> SpellCard<G> {
         Self(card_id)
     }
 
+    /// Creates a spell card from a raw spell ID as stored in game memory.
+    ///
+    /// The games themselves number spell cards starting from 0, whereas card IDs in this crate start from 1,
+    /// so this adds 1 to `game_local` before converting it.
+    pub fn from_raw_game_id(game_local: u32) -> Result<Self, InvalidCardId<G>> {
+        let card_id = game_local
+            .checked_add(1)
+            .ok_or_else(|| InvalidCardId::out_of_range(game_local, G::spell_id_range()))?;
+
+        G::SpellID::from_raw(card_id, G::GAME_ID).map(Self)
+    }
+
     /// Gets the inner game-specific type from this instance.
     pub const fn unwrap(self) -> G::SpellID {
         self.0
@@ -26,6 +26,27 @@ impl<G: Game> Stage<G> {
     pub const fn unwrap(self) -> G::StageID {
         self.0
     }
+
+    /// Returns whether this is the final stage of the main game.
+    ///
+    /// A clear of this stage counts as a full clear. Games with branching final stages
+    /// (such as Imperishable Night's Final A and Final B) return `true` for every branch.
+    pub fn is_final_stage(&self) -> bool {
+        G::is_final_stage(self.0)
+    }
+
+    /// Returns whether this is an extra stage (such as the Extra or Phantasm stages).
+    pub fn is_extra(&self) -> bool {
+        G::is_extra_stage(self.0)
+    }
+
+    /// Returns how far into the game this stage is, for comparing progress between runs.
+    ///
+    /// This generally follows stage order, except that branching stages (such as Imperishable Night's
+    /// Stage 4 Uncanny and Stage 4 Powerful) share the same value.
+    pub fn progress_order(&self) -> u16 {
+        G::stage_progress_order(self.0)
+    }
 }
 
 impl<G: HasLocations> Stage<G> {
@@ -67,7 +88,11 @@ impl<G: Game> Display for Stage<G> {
     }
 }
 
-#[derive(Debug)]
+/// How far a run progressed, as recorded in high score data.
+///
+/// This serializes as an adjacently tagged enum, such as `{"type": "lost_at", "stage": ...}` or `{"type": "all_clear"}`.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "stage", rename_all = "snake_case", bound = "")]
 pub enum StageProgress<G: Game> {
     NotStarted,
     LostAt(Stage<G>),
@@ -75,6 +100,24 @@ pub enum StageProgress<G: Game> {
     AllClear,
 }
 
+impl<G: Game> StageProgress<G> {
+    fn sort_key(&self) -> (u8, u16, bool, Option<Stage<G>>) {
+        match *self {
+            Self::NotStarted => (0, 0, false, None),
+            Self::LostAt(s) => (1, s.progress_order(), false, Some(s)),
+            Self::StageCleared(s) => (1, s.progress_order(), true, Some(s)),
+            Self::AllClear => (2, 0, false, None),
+        }
+    }
+
+    /// Returns the furthest progress out of a set of runs, such as all scores for a single character.
+    ///
+    /// Returns [`NotStarted`](StageProgress::NotStarted) if the iterator is empty.
+    pub fn max_progress(scores: impl IntoIterator<Item = Self>) -> Self {
+        scores.into_iter().max().unwrap_or(Self::NotStarted)
+    }
+}
+
 impl<G: Game> Display for StageProgress<G> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -108,34 +151,14 @@ impl<G: Game> PartialEq for StageProgress<G> {
 
 impl<G: Game> Eq for StageProgress<G> {}
 
+/// Progress is ordered by how far into the game it represents: [`NotStarted`](StageProgress::NotStarted) is
+/// the least and [`AllClear`](StageProgress::AllClear) is the greatest.
+///
+/// Progress through different stages is compared by [`Stage::progress_order`], and clearing a stage counts as
+/// further than losing on it. Progress through different branches of the same stage is ordered by stage ID.
 impl<G: Game> Ord for StageProgress<G> {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self {
-            Self::NotStarted => {
-                if let Self::NotStarted = other {
-                    Ordering::Equal
-                } else {
-                    Ordering::Less
-                }
-            }
-            Self::LostAt(a) => match other {
-                Self::NotStarted => Ordering::Greater,
-                Self::LostAt(b) => a.cmp(b),
-                Self::StageCleared(_) | Self::AllClear => Ordering::Less,
-            },
-            Self::StageCleared(a) => match other {
-                Self::NotStarted | Self::LostAt(_) => Ordering::Greater,
-                Self::StageCleared(b) => a.cmp(b),
-                Self::AllClear => Ordering::Less,
-            },
-            Self::AllClear => {
-                if let Self::AllClear = other {
-                    Ordering::Equal
-                } else {
-                    Ordering::Less
-                }
-            }
-        }
+        self.sort_key().cmp(&other.sort_key())
     }
 }
 
@@ -161,3 +184,42 @@ impl<G: Game> Hash for StageProgress<G> {
         }
     }
 }
+
+#[cfg(all(test, feature = "th08"))]
+mod tests {
+    use super::*;
+    use crate::th08::{Stage as Th08Stage, Touhou8};
+
+    fn round_trip(progress: StageProgress<Touhou8>) -> StageProgress<Touhou8> {
+        let json = serde_json::to_string(&progress).unwrap();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    #[test]
+    fn branching_stage_progress_round_trips() {
+        let values = [
+            StageProgress::NotStarted,
+            StageProgress::LostAt(Stage::new(Th08Stage::FinalA)),
+            StageProgress::LostAt(Stage::new(Th08Stage::FinalB)),
+            StageProgress::StageCleared(Stage::new(Th08Stage::FourB)),
+            StageProgress::AllClear,
+        ];
+
+        for progress in values {
+            assert_eq!(round_trip(progress), progress);
+        }
+    }
+
+    #[test]
+    fn stage_progress_is_adjacently_tagged() {
+        assert_eq!(
+            serde_json::to_value(StageProgress::<Touhou8>::AllClear).unwrap(),
+            serde_json::json!({ "type": "all_clear" })
+        );
+
+        let stage = Stage::<Touhou8>::new(Th08Stage::FinalB);
+        let lost_at = serde_json::to_value(StageProgress::LostAt(stage)).unwrap();
+        assert_eq!(lost_at["type"], "lost_at");
+        assert_eq!(lost_at["stage"], serde_json::to_value(stage).unwrap());
+    }
+}